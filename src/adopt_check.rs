@@ -0,0 +1,73 @@
+//! Adopted Order Verification
+//!
+//! Orders adopted at startup were priced for the market before the restart,
+//! and it may have moved while we were down. Each adopted order gets the
+//! normal refresh check on its first tick with the minimum rest and requote
+//! hysteresis waived, so an out-of-band one is cancelled and re-placed right
+//! away and one within its threshold is kept. The summary is reported once
+//! every adopted order has had its verdict.
+
+use std::collections::HashSet;
+
+#[derive(Debug, Default)]
+pub struct AdoptCheck {
+    pending: HashSet<String>,
+    kept: u32,
+    refreshed: u32,
+    reported: bool,
+}
+
+impl AdoptCheck {
+    pub fn new(order_ids: impl IntoIterator<Item = String>) -> Self {
+        Self { pending: order_ids.into_iter().collect(), ..Self::default() }
+    }
+
+    /// Adopted and not yet checked - refresh deferrals don't apply
+    pub fn is_pending(&self, order_id: &str) -> bool {
+        self.pending.contains(order_id)
+    }
+
+    /// Record the first check's verdict; false if the order wasn't pending
+    pub fn resolve(&mut self, order_id: &str, refreshed: bool) -> bool {
+        if !self.pending.remove(order_id) {
+            return false;
+        }
+        if refreshed { self.refreshed += 1 } else { self.kept += 1 }
+        true
+    }
+
+    /// Stop waiting on orders that filled or were cancelled before a check
+    pub fn retain_live(&mut self, is_live: impl Fn(&str) -> bool) {
+        self.pending.retain(|id| is_live(id));
+    }
+
+    /// (kept, refreshed), once, after the last pending order is resolved
+    pub fn take_summary(&mut self) -> Option<(u32, u32)> {
+        if self.reported || !self.pending.is_empty() || self.kept + self.refreshed == 0 {
+            return None;
+        }
+        self.reported = true;
+        Some((self.kept, self.refreshed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verdicts_once_per_order_then_summary() {
+        let mut c = AdoptCheck::new(["o1".to_string(), "o2".to_string(), "o3".to_string()]);
+        assert!(c.is_pending("o1") && !c.is_pending("o4"));
+        assert!(c.resolve("o1", true));
+        assert!(!c.resolve("o1", false));
+        assert_eq!(c.take_summary(), None);
+        assert!(c.resolve("o2", false));
+        // o3 filled before its first check
+        c.retain_live(|id| id != "o3");
+        assert_eq!(c.take_summary(), Some((1, 1)));
+        assert_eq!(c.take_summary(), None);
+        // Nothing adopted, nothing to report
+        assert_eq!(AdoptCheck::default().take_summary(), None);
+    }
+}
@@ -0,0 +1,67 @@
+//! Alerting
+//!
+//! Logs an `[ALERT]` line and, if a webhook is configured, POSTs
+//! `{"text": ...}` to it. Repeats of the same key are suppressed for a
+//! cooldown so a stuck condition doesn't spam the channel.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{error, warn};
+
+#[derive(Clone)]
+pub struct Alerter {
+    webhook: Option<String>,
+    cooldown: Duration,
+    last_sent: Arc<Mutex<HashMap<String, Instant>>>,
+    client: reqwest::Client,
+}
+
+impl Alerter {
+    pub fn new(webhook: &str, cooldown: Duration) -> Self {
+        Self {
+            webhook: if webhook.is_empty() { None } else { Some(webhook.to_string()) },
+            cooldown,
+            last_sent: Arc::new(Mutex::new(HashMap::new())),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Raise an alert. Returns false if suppressed by the cooldown.
+    pub fn alert(&self, key: &str, msg: &str) -> bool {
+        {
+            let mut last = self.last_sent.lock().unwrap();
+            if let Some(t) = last.get(key) {
+                if t.elapsed() < self.cooldown {
+                    return false;
+                }
+            }
+            last.insert(key.to_string(), Instant::now());
+        }
+
+        error!("[ALERT] {}: {}", key, msg);
+        if let Some(url) = self.webhook.clone() {
+            let body = serde_json::json!({ "text": format!("[{}] {}", key, msg) });
+            let client = self.client.clone();
+            tokio::spawn(async move {
+                if let Err(e) = client.post(&url).json(&body).send().await {
+                    warn!("[ALERT] Webhook delivery failed: {}", e);
+                }
+            });
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cooldown_suppresses_repeats() {
+        let alerter = Alerter::new("", Duration::from_secs(60));
+        assert!(alerter.alert("probe", "slow"));
+        assert!(!alerter.alert("probe", "still slow"));
+        assert!(alerter.alert("other", "different key"));
+    }
+}
@@ -0,0 +1,233 @@
+//! Offline Session Analysis
+//!
+//! `test_multi_layers analyze --input trades.jsonl [--csv layers.csv]`
+//! replays a trade log (see `trade_log`) and prints per-layer realized
+//! spread, markouts and fill rates, the inventory distribution, and PnL
+//! from the same FIFO matcher the bot uses, so the numbers line up with
+//! the live report.
+//!
+//! The log only carries a mid when something filled, so a markout uses the
+//! first later record at or past the horizon, and is skipped when that is
+//! more than twice the horizon away.
+
+use anyhow::{bail, Context, Result};
+use std::collections::BTreeMap;
+
+use crate::exchange::{Liquidity, Side};
+use crate::scratch::markout_bps;
+use crate::trade_log::{self, TradeRecord};
+use crate::{PnL, PolledFill};
+
+pub const MARKOUT_HORIZONS_SECS: [u64; 4] = [1, 5, 30, 60];
+
+#[derive(Debug, Clone, Default)]
+pub struct LayerStats {
+    pub fills: u64,
+    pub buys: u64,
+    pub sells: u64,
+    pub base: f64,
+    /// Size-weighted sum of edge vs mid at fill, bps * base
+    edge_sum: f64,
+    markout_sum: [f64; MARKOUT_HORIZONS_SECS.len()],
+    markout_base: [f64; MARKOUT_HORIZONS_SECS.len()],
+}
+
+impl LayerStats {
+    /// Realized half-spread vs the mid at fill, size-weighted bps
+    pub fn edge_bps(&self) -> f64 {
+        if self.base > 0.0 { self.edge_sum / self.base } else { 0.0 }
+    }
+
+    /// Size-weighted markout at `MARKOUT_HORIZONS_SECS[i]`; None without samples
+    pub fn markout_bps(&self, i: usize) -> Option<f64> {
+        (self.markout_base[i] > 0.0).then(|| self.markout_sum[i] / self.markout_base[i])
+    }
+}
+
+pub struct Report {
+    /// Keyed by level (bps * 10); None = fills not placed by the ladder
+    pub layers: BTreeMap<Option<i32>, LayerStats>,
+    pub span_secs: f64,
+    pub pnl: PnL,
+    pub last_mid: f64,
+    /// Inventory after each fill, sorted
+    pub inventory: Vec<f64>,
+}
+
+impl Report {
+    pub fn fills_per_hour(&self, layer: &LayerStats) -> f64 {
+        if self.span_secs > 0.0 { layer.fills as f64 * 3600.0 / self.span_secs } else { 0.0 }
+    }
+
+    /// Inventory percentile over fills, `q` in 0..=1
+    pub fn inventory_pct(&self, q: f64) -> f64 {
+        if self.inventory.is_empty() {
+            return 0.0;
+        }
+        let i = (q.clamp(0.0, 1.0) * (self.inventory.len() - 1) as f64).round() as usize;
+        self.inventory[i]
+    }
+}
+
+pub fn analyze(records: &[TradeRecord], taker_bps: f64) -> Report {
+    let mut recs: Vec<&TradeRecord> = records.iter().collect();
+    recs.sort_by_key(|r| r.ts_ms);
+    let mut layers: BTreeMap<Option<i32>, LayerStats> = BTreeMap::new();
+    let mut pnl = PnL::default();
+    let mut inventory = Vec::with_capacity(recs.len());
+
+    for (i, r) in recs.iter().enumerate() {
+        let is_buy = r.side == Side::Buy;
+        let l = layers.entry(r.level).or_default();
+        l.fills += 1;
+        if is_buy { l.buys += 1 } else { l.sells += 1 }
+        l.base += r.size;
+        l.edge_sum += markout_bps(is_buy, r.price, r.mid) * r.size;
+        for (h, secs) in MARKOUT_HORIZONS_SECS.iter().enumerate() {
+            let (at, limit) = (r.ts_ms + secs * 1000, r.ts_ms + secs * 2000);
+            let j = i + 1 + recs[i + 1..].partition_point(|x| x.ts_ms < at);
+            if let Some(later) = recs.get(j).filter(|x| x.ts_ms <= limit && x.mid > 0.0) {
+                l.markout_sum[h] += markout_bps(is_buy, r.price, later.mid) * r.size;
+                l.markout_base[h] += r.size;
+            }
+        }
+
+        pnl.record_fill(&PolledFill {
+            order_id: r.order_id.clone(),
+            side: r.side,
+            size: r.size,
+            price: r.price,
+            liquidity: if r.maker { Liquidity::Maker } else { Liquidity::Taker },
+            fee: r.fee,
+            fee_currency: r.fee_currency.clone(),
+        }, taker_bps);
+        inventory.push(pnl.inv());
+    }
+    inventory.sort_by(f64::total_cmp);
+
+    let span_secs = match (recs.first(), recs.last()) {
+        (Some(a), Some(b)) => (b.ts_ms - a.ts_ms) as f64 / 1000.0,
+        _ => 0.0,
+    };
+    let last_mid = recs.last().map(|r| r.mid).unwrap_or(0.0);
+    Report { layers, span_secs, pnl, last_mid, inventory }
+}
+
+fn level_label(level: Option<i32>) -> String {
+    level.map(|k| format!("{:.2}", k as f64 / 10.0)).unwrap_or_else(|| "other".into())
+}
+
+fn fmt_markout(m: Option<f64>) -> String {
+    m.map(|v| format!("{:+.2}", v)).unwrap_or_else(|| "-".into())
+}
+
+pub fn print_report(rep: &Report) {
+    let fills: u64 = rep.layers.values().map(|l| l.fills).sum();
+    println!("═══ SESSION: {} fills over {:.0}s ═══", fills, rep.span_secs);
+    let horizons: Vec<String> = MARKOUT_HORIZONS_SECS.iter().map(|s| format!("mo{}s", s)).collect();
+    println!("{:>7} {:>6} {:>5} {:>5} {:>9} {:>8} {:>8}  {}",
+        "lvl_bps", "fills", "buys", "sells", "base", "fills/h", "edge", horizons.join("  "));
+    for (level, l) in &rep.layers {
+        let mo: Vec<String> = (0..MARKOUT_HORIZONS_SECS.len()).map(|i| format!("{:>6}", fmt_markout(l.markout_bps(i)))).collect();
+        println!("{:>7} {:>6} {:>5} {:>5} {:>9.4} {:>8.1} {:>+8.2}  {}",
+            level_label(*level), l.fills, l.buys, l.sells, l.base, rep.fills_per_hour(l), l.edge_bps(), mo.join(" "));
+    }
+    println!("Inventory (SOL): min {:+.3} p10 {:+.3} p50 {:+.3} p90 {:+.3} max {:+.3}",
+        rep.inventory_pct(0.0), rep.inventory_pct(0.1), rep.inventory_pct(0.5), rep.inventory_pct(0.9), rep.inventory_pct(1.0));
+    let p = &rep.pnl;
+    println!("Matches:{} W:{} L:{} | SPREAD ${:.4} | REBATES ${:.4} | FEES -${:.4} | NET ${:.4} | OPEN ${:.4} @ {:.4}",
+        p.matched, p.wins, p.losses, p.spread, p.reb, p.fees, p.net(), p.open_mtm(rep.last_mid), rep.last_mid);
+}
+
+pub fn layers_csv(rep: &Report) -> String {
+    let mut out = String::from("level_bps,fills,buys,sells,base,fills_per_hour,edge_bps");
+    for s in MARKOUT_HORIZONS_SECS {
+        out.push_str(&format!(",markout_{}s_bps", s));
+    }
+    out.push('\n');
+    for (level, l) in &rep.layers {
+        out.push_str(&format!("{},{},{},{},{:.6},{:.3},{:.4}",
+            level_label(*level), l.fills, l.buys, l.sells, l.base, rep.fills_per_hour(l), l.edge_bps()));
+        for i in 0..MARKOUT_HORIZONS_SECS.len() {
+            out.push(',');
+            if let Some(m) = l.markout_bps(i) {
+                out.push_str(&format!("{:.4}", m));
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Entry point for `analyze --input <trades.jsonl> [--csv <out.csv>]`
+pub fn run(args: &[String], taker_bps: f64) -> Result<()> {
+    let (mut input, mut csv) = (None, None);
+    let mut it = args.iter();
+    while let Some(a) = it.next() {
+        match a.as_str() {
+            "--input" => input = it.next(),
+            "--csv" => csv = it.next(),
+            other => bail!("unknown analyze argument {}", other),
+        }
+    }
+    let Some(input) = input else { bail!("usage: analyze --input <trades.jsonl> [--csv <out.csv>]") };
+    let text = std::fs::read_to_string(input).with_context(|| format!("reading {}", input))?;
+    let records = trade_log::parse(&text).with_context(|| format!("parsing {}", input))?;
+    let rep = analyze(&records, taker_bps);
+    print_report(&rep);
+    if let Some(path) = csv {
+        std::fs::write(path, layers_csv(&rep)).with_context(|| format!("writing {}", path))?;
+        println!("Per-layer stats written to {}", path);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rec(ts_ms: u64, side: Side, price: f64, mid: f64, level: Option<i32>) -> TradeRecord {
+        TradeRecord {
+            ts_ms, order_id: format!("o{}", ts_ms), side, price, size: 1.0, maker: true,
+            fee: None, fee_currency: String::new(), mid, level,
+        }
+    }
+
+    #[test]
+    fn test_layer_stats_markouts_and_pnl() {
+        let log = [
+            rec(0, Side::Buy, 99.99, 100.0, Some(10)),
+            rec(1_000, Side::Sell, 100.02, 100.0, Some(20)),
+            rec(5_000, Side::Sell, 99.99, 99.98, None),
+            rec(30_000, Side::Buy, 99.97, 99.99, Some(10)),
+        ];
+        let text: String = log.iter().map(|r| serde_json::to_string(r).unwrap() + "\n").collect();
+        let parsed = trade_log::parse(&format!("{}\n", text)).unwrap();
+        assert_eq!(parsed, log);
+
+        let rep = analyze(&parsed, 10.0);
+        assert_eq!(rep.layers.len(), 3);
+        let l1 = &rep.layers[&Some(10)];
+        assert_eq!((l1.fills, l1.buys, l1.sells), (2, 2, 0));
+        // 1bps and 2bps under mid
+        assert!((l1.edge_bps() - 1.5).abs() < 0.01);
+        // First buy: 1s markout from the sell at 1s (mid 100) = +1bps; its 60s
+        // horizon and everything after the last fill have no sample
+        assert!((l1.markout_bps(0).unwrap() - 1.0).abs() < 0.01);
+        assert_eq!(l1.markout_bps(3), None);
+        // 5s: the 5s record (mid 99.98) for the first buy only
+        assert!((l1.markout_bps(1).unwrap() + 1.0).abs() < 0.01);
+        assert!(rep.layers[&None].edge_bps() > 0.0);
+        assert!((rep.fills_per_hour(l1) - 240.0).abs() < 1e-9);
+
+        // FIFO: buy 99.99 / sell 100.02 and sell 99.99 / buy 99.97
+        assert_eq!(rep.pnl.matched, 2);
+        assert!((rep.pnl.spread - 0.05).abs() < 1e-9);
+        assert_eq!((rep.inventory_pct(0.0), rep.inventory_pct(0.5), rep.inventory_pct(1.0)), (-1.0, 0.0, 1.0));
+
+        let csv = layers_csv(&rep);
+        assert!(csv.starts_with("level_bps,fills,buys,sells,base,fills_per_hour,edge_bps,markout_1s_bps"));
+        assert!(csv.contains("\n1.00,2,2,0,"));
+        assert!(csv.contains("\nother,1,0,1,"));
+    }
+}
@@ -0,0 +1,159 @@
+//! Shutdown Cancel Sweep
+//!
+//! The symbol-wide DELETE can partially succeed or quietly no-op under load.
+//! After it, re-poll and cancel whatever is left one by one - by orderId,
+//! then by clientOid - until the book is clear or the deadline passes.
+
+use async_trait::async_trait;
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+/// REST calls the sweep needs - abstracted so it can run against a mock
+#[async_trait]
+pub trait CancelApi: Send + Sync {
+    async fn cancel_all(&self);
+    /// Open orders as (orderId, clientOid); `None` if the poll failed
+    async fn open_orders(&self) -> Option<Vec<(String, String)>>;
+    async fn cancel_by_id(&self, order_id: &str) -> bool;
+    async fn cancel_by_client_oid(&self, client_oid: &str) -> bool;
+}
+
+/// How many orders went by which method
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CancelReport {
+    pub bulk: usize,
+    pub by_order_id: usize,
+    pub by_client_oid: usize,
+    /// Still open when the sweep stopped
+    pub remaining: usize,
+    /// Individual-cancel passes after the bulk DELETE
+    pub rounds: u32,
+}
+
+pub async fn cancel_all_with_fallback(api: &dyn CancelApi, deadline: Duration, settle: Duration) -> CancelReport {
+    let start = Instant::now();
+    let mut report = CancelReport::default();
+
+    let before = api.open_orders().await.map(|o| o.len());
+    api.cancel_all().await;
+    tokio::time::sleep(settle).await;
+    // A failed poll can't prove the book is clear - retry it within the deadline
+    let mut left = api.open_orders().await;
+    while left.is_none() && start.elapsed() < deadline {
+        tokio::time::sleep(settle).await;
+        left = api.open_orders().await;
+    }
+    let Some(mut left) = left else {
+        warn!("[SHUTDOWN] Open-order poll kept failing, can't confirm cancels");
+        return report;
+    };
+    report.bulk = before.unwrap_or(0).saturating_sub(left.len());
+
+    // An order is credited to the first method that succeeded for it
+    let mut credited: HashSet<String> = HashSet::new();
+    while !left.is_empty() && start.elapsed() < deadline {
+        report.rounds += 1;
+        for (order_id, client_oid) in &left {
+            let counter = if api.cancel_by_id(order_id).await {
+                &mut report.by_order_id
+            } else if !client_oid.is_empty() && api.cancel_by_client_oid(client_oid).await {
+                &mut report.by_client_oid
+            } else {
+                continue;
+            };
+            if credited.insert(order_id.clone()) {
+                *counter += 1;
+            }
+        }
+        tokio::time::sleep(settle).await;
+        if let Some(now_open) = api.open_orders().await {
+            left = now_open;
+        }
+    }
+
+    report.remaining = left.len();
+    if report.remaining > 0 {
+        warn!("[SHUTDOWN] {} orders still open after {:?}", report.remaining, start.elapsed());
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Bulk cancel only takes the first `bulk_takes` orders; entries in
+    /// `id_fails` / `oid_fails` reject the individual cancel
+    struct MockRest {
+        open: Mutex<Vec<(String, String)>>,
+        bulk_takes: usize,
+        id_fails: HashSet<String>,
+        oid_fails: HashSet<String>,
+    }
+
+    impl MockRest {
+        fn new(n: usize, bulk_takes: usize) -> Self {
+            Self {
+                open: Mutex::new((0..n).map(|i| (format!("o{}", i), format!("b2_{}", i))).collect()),
+                bulk_takes,
+                id_fails: HashSet::new(),
+                oid_fails: HashSet::new(),
+            }
+        }
+        fn remove(&self, pred: impl Fn(&(String, String)) -> bool) -> bool {
+            let mut open = self.open.lock().unwrap();
+            let len = open.len();
+            open.retain(|o| !pred(o));
+            open.len() < len
+        }
+    }
+
+    #[async_trait]
+    impl CancelApi for MockRest {
+        async fn cancel_all(&self) {
+            let mut open = self.open.lock().unwrap();
+            let take = self.bulk_takes.min(open.len());
+            open.drain(..take);
+        }
+        async fn open_orders(&self) -> Option<Vec<(String, String)>> {
+            Some(self.open.lock().unwrap().clone())
+        }
+        async fn cancel_by_id(&self, order_id: &str) -> bool {
+            !self.id_fails.contains(order_id) && self.remove(|o| o.0 == order_id)
+        }
+        async fn cancel_by_client_oid(&self, client_oid: &str) -> bool {
+            !self.oid_fails.contains(client_oid) && self.remove(|o| o.1 == client_oid)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bulk_clears_everything() {
+        let api = MockRest::new(4, 10);
+        let r = cancel_all_with_fallback(&api, Duration::from_secs(1), Duration::ZERO).await;
+        assert_eq!(r, CancelReport { bulk: 4, ..Default::default() });
+    }
+
+    #[tokio::test]
+    async fn test_partial_bulk_falls_back_per_order() {
+        let mut api = MockRest::new(5, 2);
+        api.id_fails.insert("o3".into());
+        let r = cancel_all_with_fallback(&api, Duration::from_secs(1), Duration::ZERO).await;
+        assert_eq!(r.bulk, 2);
+        assert_eq!(r.by_order_id, 2);
+        assert_eq!(r.by_client_oid, 1);
+        assert_eq!(r.remaining, 0);
+        assert_eq!(r.rounds, 1);
+    }
+
+    #[tokio::test]
+    async fn test_stops_at_deadline_with_remaining() {
+        let mut api = MockRest::new(2, 0);
+        api.id_fails.insert("o1".into());
+        api.oid_fails.insert("b2_1".into());
+        let r = cancel_all_with_fallback(&api, Duration::from_millis(20), Duration::from_millis(5)).await;
+        assert_eq!((r.bulk, r.by_order_id, r.by_client_oid, r.remaining), (0, 1, 0, 1));
+        assert!(r.rounds >= 2);
+    }
+}
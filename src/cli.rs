@@ -0,0 +1,159 @@
+//! Command Line
+//!
+//! `test_multi_layers [--drain] [--skip-preflight] [--symbol ETH-USDT]
+//! [--order-usd 25] [--max-inv-sol 15] [--gamma 0.05] [--ofi-pause 0.7]
+//! [--ofi-resume 0.35] [--momentum-threshold 0.003] [--mid-source kucoin|binance]` - the
+//! strategy knobs default to the compiled-in constants, so a parameter sweep
+//! is a shell loop rather than a rebuild. `analyze` is dispatched before this
+//! parser (see `analyze`).
+
+use anyhow::Result;
+use clap::{Parser, ValueEnum};
+
+use crate::config::{Config, MidSource, ToxicitySource};
+
+#[derive(Debug, Parser)]
+#[command(about = "Multi-layer KuCoin market maker")]
+pub struct Args {
+    /// Take over resting orders and let them run out, no placing
+    #[arg(long)]
+    pub drain: bool,
+    /// Skip the API key permission check
+    #[arg(long)]
+    pub skip_preflight: bool,
+    /// Traded pair; else TRADE_SYMBOL, else SOL-USDT
+    #[arg(long)]
+    pub symbol: Option<String>,
+    /// Notional per ladder order, USD
+    #[arg(long, default_value_t = crate::ORDER_USD)]
+    pub order_usd: f64,
+    /// Inventory limit either way, base units
+    #[arg(long, default_value_t = crate::MAX_INV_SOL)]
+    pub max_inv_sol: f64,
+    /// Inventory skew risk aversion
+    #[arg(long, default_value_t = crate::GAMMA)]
+    pub gamma: f64,
+    /// |OFI| that pauses quoting
+    #[arg(long, default_value_t = crate::OFI_PAUSE_THRESHOLD)]
+    pub ofi_pause: f64,
+    /// |OFI| at or below which a pause may lift; default keeps the built-in
+    /// resume/pause ratio (0.35 / 0.70)
+    #[arg(long)]
+    pub ofi_resume: Option<f64>,
+    /// Momentum (fraction) that counts as a trend
+    #[arg(long, default_value_t = crate::MOMENTUM_THRESHOLD)]
+    pub momentum_threshold: f64,
+    /// Book the ladder mid and OFI come from; else the config's
+    /// `quoting.mid_source` / `signals.toxicity_source`
+    #[arg(long, value_enum)]
+    pub mid_source: Option<MidFeed>,
+}
+
+/// `--mid-source`: quote off the Binance futures reference or the KuCoin
+/// spot book we actually trade on
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum MidFeed {
+    Binance,
+    Kucoin,
+}
+
+impl MidFeed {
+    /// Set both the mid and the OFI (toxicity) source; KuCoin starts the
+    /// level2 feed and falls back to the reference until its book is live
+    pub fn apply(self, cfg: &mut Config) {
+        (cfg.quoting.mid_source, cfg.signals.toxicity_source) = match self {
+            MidFeed::Binance => (MidSource::Reference, ToxicitySource::BinanceOfi),
+            MidFeed::Kucoin => (MidSource::Local, ToxicitySource::KucoinImbalance),
+        };
+    }
+}
+
+/// Strategy parameters the tick loop reads, from `Args`
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrategyConfig {
+    pub order_usd: f64,
+    pub max_inv_sol: f64,
+    pub gamma: f64,
+    pub ofi_pause_threshold: f64,
+    pub ofi_resume_threshold: f64,
+    pub momentum_threshold: f64,
+}
+
+impl StrategyConfig {
+    pub fn from_args(args: &Args) -> Result<Self> {
+        if args.order_usd.is_nan() || args.order_usd <= 0.0 {
+            anyhow::bail!("--order-usd must be > 0 (got {})", args.order_usd);
+        }
+        if args.max_inv_sol.is_nan() || args.max_inv_sol <= 0.0 {
+            anyhow::bail!("--max-inv-sol must be > 0 (got {})", args.max_inv_sol);
+        }
+        if !(args.gamma > 0.0 && args.gamma < 10.0) {
+            anyhow::bail!("--gamma must be in (0, 10) (got {})", args.gamma);
+        }
+        if !(args.ofi_pause > 0.0 && args.ofi_pause <= 1.0) {
+            anyhow::bail!("--ofi-pause must be in (0, 1] (got {})", args.ofi_pause);
+        }
+        let ofi_resume = args.ofi_resume
+            .unwrap_or(args.ofi_pause * crate::OFI_RESUME_THRESHOLD / crate::OFI_PAUSE_THRESHOLD);
+        // Resume at or above pause would pause and resume on alternate ticks
+        if !(ofi_resume >= 0.0 && ofi_resume < args.ofi_pause) {
+            anyhow::bail!("--ofi-resume must be in [0, --ofi-pause {}) (got {})", args.ofi_pause, ofi_resume);
+        }
+        if args.momentum_threshold.is_nan() || args.momentum_threshold <= 0.0 {
+            anyhow::bail!("--momentum-threshold must be > 0 (got {})", args.momentum_threshold);
+        }
+        Ok(Self {
+            order_usd: args.order_usd,
+            max_inv_sol: args.max_inv_sol,
+            gamma: args.gamma,
+            ofi_pause_threshold: args.ofi_pause,
+            ofi_resume_threshold: ofi_resume,
+            momentum_threshold: args.momentum_threshold,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_overrides_and_validation() {
+        let parse = |a: &[&str]| Args::try_parse_from(std::iter::once("bot").chain(a.iter().copied()));
+        let args = parse(&[]).unwrap();
+        assert!(!args.drain && args.symbol.is_none());
+        let s = StrategyConfig::from_args(&args).unwrap();
+        assert_eq!((s.order_usd, s.max_inv_sol, s.gamma), (crate::ORDER_USD, crate::MAX_INV_SOL, crate::GAMMA));
+
+        let args = parse(&["--drain", "--order-usd", "40", "--gamma", "0.2", "--ofi-pause", "0.8"]).unwrap();
+        let s = StrategyConfig::from_args(&args).unwrap();
+        assert!(args.drain);
+        assert_eq!((s.order_usd, s.gamma, s.ofi_pause_threshold), (40.0, 0.2, 0.8));
+        // Resume follows pause unless given
+        assert!((s.ofi_resume_threshold - 0.4).abs() < 1e-9);
+        assert_eq!(StrategyConfig::from_args(&parse(&[]).unwrap()).unwrap().ofi_resume_threshold, crate::OFI_RESUME_THRESHOLD);
+        let s = StrategyConfig::from_args(&parse(&["--ofi-pause", "0.3", "--ofi-resume", "0.1"]).unwrap()).unwrap();
+        assert_eq!((s.ofi_pause_threshold, s.ofi_resume_threshold), (0.3, 0.1));
+
+        for bad in [["--order-usd", "0"], ["--max-inv-sol=-1", "--drain"], ["--gamma", "10"], ["--gamma", "0"],
+            ["--ofi-pause", "1.5"], ["--ofi-pause", "0"], ["--ofi-resume", "0.7"], ["--momentum-threshold", "0"]] {
+            assert!(StrategyConfig::from_args(&parse(&bad).unwrap()).is_err(), "{:?}", bad);
+        }
+        assert!(parse(&["--gamma", "abc"]).is_err());
+        assert!(parse(&["--mid-source", "okx"]).is_err());
+        assert!(parse(&["--unknown"]).is_err());
+    }
+
+    #[test]
+    fn test_mid_source_flag_overrides_config() {
+        let parse = |a: &[&str]| Args::try_parse_from(std::iter::once("bot").chain(a.iter().copied())).unwrap();
+        assert_eq!(parse(&[]).mid_source, None);
+        let mut cfg = Config::parse("[quoting]\nmid_source = { blend = { w = 0.25 } }\n").unwrap();
+        let feed = parse(&["--mid-source", "kucoin"]).mid_source.unwrap();
+        feed.apply(&mut cfg);
+        assert_eq!((cfg.quoting.mid_source, cfg.signals.toxicity_source), (MidSource::Local, ToxicitySource::KucoinImbalance));
+        assert!(cfg.quoting.mid_source.needs_local());
+        MidFeed::Binance.apply(&mut cfg);
+        assert_eq!((cfg.quoting.mid_source, cfg.signals.toxicity_source), (MidSource::Reference, ToxicitySource::BinanceOfi));
+    }
+}
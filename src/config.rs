@@ -0,0 +1,1172 @@
+//! Runtime Configuration
+//!
+//! Optional TOML file (`config.toml`, or the path in `MM_CONFIG`) holding
+//! operational knobs. Every field defaults to the compiled-in behaviour, so a
+//! missing file or section changes nothing.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::time::{Duration, Instant};
+
+use crate::exchange::SelfTradePrevention;
+use crate::exchange::ws_order_client_v2::OrderEntry;
+use tracing::info;
+
+const DEFAULT_CONFIG_FILE: &str = "config.toml";
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Bind address for the `/status` HTTP endpoint, e.g. "127.0.0.1:8080";
+    /// empty = disabled (the default)
+    pub status_addr: String,
+    /// Webhook for alerts (Slack/Discord style `{"text": ...}`); empty = log only
+    pub alert_webhook: String,
+    /// Minimum seconds between repeats of the same alert
+    pub alert_cooldown_secs: u64,
+    /// TOML file listing `disabled = [symbols]`, re-read on change (empty = off)
+    pub control_file: String,
+    /// JSONL file every fill is appended to, for `analyze` (empty = off)
+    pub trade_log: String,
+    /// Most REST requests in flight at once, across every call site; the
+    /// rest queue. 0 = unlimited
+    pub rest_max_concurrent: usize,
+    /// tradeIds remembered to catch a fill seen on both the private WS and
+    /// the REST poll; at least 100, 0 = unbounded
+    pub fill_dedup_capacity: usize,
+    /// TOML or JSON file with the ladder's (offset_bps, refresh_bps) pairs,
+    /// see `LadderConfig`. The default file may be absent (built-in 25
+    /// levels); any other path must load or startup fails.
+    pub ladder_file: String,
+    pub latency_probe: LatencyProbeConfig,
+    pub levels: LevelsConfig,
+    pub market_data: MarketDataConfig,
+    pub quoting: QuotingConfig,
+    pub signals: SignalsConfig,
+    pub fees: FeesConfig,
+    pub startup: StartupConfig,
+    pub account: AccountConfig,
+    pub sizing: SizingConfig,
+    pub schedule: ScheduleConfig,
+    pub risk: RiskConfig,
+    pub drain: DrainConfig,
+    pub book_check: BookCheckConfig,
+    pub event_log: EventLogConfig,
+    pub private_ws: PrivateWsConfig,
+    pub hedge: HedgeConfig,
+    pub cadence: CadenceConfig,
+    pub entry_breaker: EntryBreakerConfig,
+    pub market_status: MarketStatusConfig,
+    pub reconnect: ReconnectConfig,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            status_addr: String::new(),
+            alert_webhook: String::new(),
+            alert_cooldown_secs: 300,
+            control_file: String::new(),
+            trade_log: String::new(),
+            rest_max_concurrent: 8,
+            fill_dedup_capacity: 10_000,
+            ladder_file: DEFAULT_LADDER_FILE.to_string(),
+            latency_probe: LatencyProbeConfig::default(),
+            levels: LevelsConfig::default(),
+            market_data: MarketDataConfig::default(),
+            quoting: QuotingConfig::default(),
+            signals: SignalsConfig::default(),
+            fees: FeesConfig::default(),
+            startup: StartupConfig::default(),
+            account: AccountConfig::default(),
+            sizing: SizingConfig::default(),
+            schedule: ScheduleConfig::default(),
+            risk: RiskConfig::default(),
+            drain: DrainConfig::default(),
+            book_check: BookCheckConfig::default(),
+            event_log: EventLogConfig::default(),
+            private_ws: PrivateWsConfig::default(),
+            hedge: HedgeConfig::default(),
+            cadence: CadenceConfig::default(),
+            entry_breaker: EntryBreakerConfig::default(),
+            market_status: MarketStatusConfig::default(),
+            reconnect: ReconnectConfig::default(),
+        }
+    }
+}
+
+/// Synthetic order-entry probe - costs rate limit, so off by default
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct LatencyProbeConfig {
+    pub enabled: bool,
+    pub interval_secs: u64,
+    /// Distance of the probe bid below mid - far enough to never fill
+    pub offset_bps: f64,
+    /// Alert when place -> `open` exceeds this
+    pub alert_ms: u64,
+    /// Give up waiting for `open` after this
+    pub timeout_ms: u64,
+}
+
+impl Default for LatencyProbeConfig {
+    fn default() -> Self {
+        Self { enabled: false, interval_secs: 60, offset_bps: 500.0, alert_ms: 500, timeout_ms: 5000 }
+    }
+}
+
+/// How far-layer prices are chosen
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FarLayerMode {
+    /// Fixed bps from the ladder table (original behaviour)
+    #[default]
+    Bps,
+    /// VWAP of the live KuCoin book for a growing notional per layer
+    BookVwap,
+}
+
+/// How the base ladder distances are chosen
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpacingMode {
+    /// Ladder table bps (original behaviour)
+    #[default]
+    Static,
+    /// Same inner/outer bps as the table, levels placed so each band between
+    /// neighbours carries equal fill probability at the current volatility
+    EqualFillProb,
+}
+
+/// How a live order that has drifted off its level price is moved. KuCoin's
+/// alter cancels and re-places, so a modify loses queue position like a
+/// cancel/replace and each step waits out `min_resting_ms`; walking only
+/// paces how fast an order follows its price.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RepriceMode {
+    /// Cancel it and place a new one (original behaviour)
+    #[default]
+    CancelReplace,
+    /// Modify it straight to the new price
+    Modify,
+    /// Modify it one tick toward the new price per tick, until it's there
+    WalkOneTick,
+}
+
+/// What to do when levels on one side round onto the same tick
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PriceCollision {
+    /// Place them all at that price (original behaviour)
+    #[default]
+    Stack,
+    /// Move each colliding level one tick further from mid
+    Nudge,
+    /// One order carrying the colliding levels' combined size; an order
+    /// already resting on the kept level is refreshed at that size
+    Merge,
+}
+
+/// Level loop pacing
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct LevelsConfig {
+    /// Max WS place/cancel actions per tick; 0 = unlimited. Checked before
+    /// each level, so one level may overrun by its own few actions.
+    pub max_actions_per_tick: usize,
+    /// Visit order under budget pressure: "sequential" | "risk_first"
+    pub priority: String,
+    /// Leave a level alone when its live order already sits on the desired
+    /// price tick, even if the refresh threshold math says otherwise
+    pub skip_matched: bool,
+    pub far_mode: FarLayerMode,
+    /// First ladder index treated as a far layer
+    pub far_from_index: usize,
+    /// BookVwap: far layer n (0-based) sits at the VWAP for `(n + 1) * step`
+    /// USDT of flow; falls back to bps if the 50-level book can't absorb it
+    pub far_vwap_step_usd: f64,
+    /// Level place/cancel ops in flight at once; 1 = sequential
+    pub concurrency: usize,
+    /// Floor on every level's refresh threshold (bps); 0 = table values as-is
+    pub min_refresh_bps: f64,
+    pub spacing: SpacingMode,
+    /// EqualFillProb: horizon the fill model scales sigma to
+    pub fill_horizon_secs: f64,
+    /// EqualFillProb: seconds between re-spacings
+    pub respace_secs: u64,
+    /// Layers from this ladder index on are placed GTT and left to expire
+    /// instead of being refreshed on ordinary drift
+    pub gtt_from_index: usize,
+    /// Server-side `cancelAfter` for those layers; 0 = off (all GTC)
+    pub gtt_cancel_after_secs: u64,
+    /// At `max_orders_per_side`, cancel the furthest live order so a
+    /// closer level can take its slot on a later tick
+    pub warm_cancel: bool,
+    /// Most orders resting on one side; below the ladder length, the outer
+    /// levels stay empty while the inner ones are quoted. 0 = one per level
+    pub max_orders_per_side: usize,
+    /// Send a tick's placements as one HF batch REST call once this many
+    /// messages are queued on the WS socket; 0 = off (trade account only)
+    pub rest_batch_queue_threshold: usize,
+    /// Don't re-place a level side whose expected edge (distance + rebate -
+    /// adverse markout) is below this many bps; unset = off
+    pub min_edge_bps: Option<f64>,
+    /// How long after a fill its markout is taken for the adverse estimate
+    pub edge_markout_secs: f64,
+    /// EWMA weight of each new markout in a level's adverse estimate
+    pub edge_adverse_alpha: f64,
+    /// A live order can't be cancelled for a refresh until it has rested
+    /// this long; risk cancels (trend protect, inventory, skip) ignore it.
+    /// 0 = off
+    pub min_resting_ms: u64,
+    /// After a refresh, hold the level side's next refresh until its new
+    /// order has been within this fraction of the trigger; drift past twice
+    /// the trigger refreshes regardless. 0 = off
+    pub refresh_inner_frac: f64,
+    /// Refresh tiers: levels from each tier's index on are only visited
+    /// every `interval_ms`; levels before the first tier run every tick.
+    /// Empty = every level every tick.
+    pub tiers: Vec<LevelTier>,
+    pub price_collision: PriceCollision,
+    /// Ladder indices (0 = innermost) not quoted at all; resting orders on
+    /// them are cancelled. `disabled_levels` in the control file replaces
+    /// this at runtime.
+    pub disabled_levels: Vec<usize>,
+    /// Modify needs the HF alter endpoint - trade account only, else
+    /// cancel/replace is used
+    pub reprice: RepriceMode,
+    /// WalkOneTick: an order more than this many ticks from its price is
+    /// cancelled and replaced instead of walked; 0 = no limit
+    pub walk_max_gap_ticks: u32,
+}
+
+/// From ladder index `from_index` on, visit levels every `interval_ms`
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct LevelTier {
+    pub from_index: usize,
+    pub interval_ms: u64,
+}
+
+impl Default for LevelsConfig {
+    fn default() -> Self {
+        Self {
+            max_actions_per_tick: 0,
+            priority: "sequential".to_string(),
+            skip_matched: false,
+            far_mode: FarLayerMode::Bps,
+            far_from_index: 15,
+            far_vwap_step_usd: 5000.0,
+            concurrency: 1,
+            min_refresh_bps: 0.0,
+            spacing: SpacingMode::Static,
+            fill_horizon_secs: 60.0,
+            respace_secs: 30,
+            gtt_from_index: 15,
+            gtt_cancel_after_secs: 0,
+            warm_cancel: false,
+            max_orders_per_side: 0,
+            rest_batch_queue_threshold: 0,
+            min_edge_bps: None,
+            edge_markout_secs: 5.0,
+            edge_adverse_alpha: 0.3,
+            min_resting_ms: 0,
+            refresh_inner_frac: 0.0,
+            tiers: Vec::new(),
+            price_collision: PriceCollision::Stack,
+            disabled_levels: Vec::new(),
+            reprice: RepriceMode::CancelReplace,
+            walk_max_gap_ticks: 5,
+        }
+    }
+}
+
+/// Momentum price history bounds
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct MarketDataConfig {
+    /// At most one sample per this many ms
+    pub history_sample_ms: u64,
+    /// Hard cap on retained samples
+    pub history_max_len: usize,
+    /// Price levels kept per side in the KuCoin order book
+    pub book_max_levels: usize,
+}
+
+impl Default for MarketDataConfig {
+    fn default() -> Self {
+        Self { history_sample_ms: 500, history_max_len: 1024, book_max_levels: 50 }
+    }
+}
+
+/// Quote placement shaping
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct QuotingConfig {
+    /// Gamma for the whole-ladder reservation shift (inv * gamma * sigma^2);
+    /// 0 = off. Independent of the per-level skew's GAMMA.
+    pub reservation_gamma: f64,
+    /// Cap on the reservation shift, in bps of mid
+    pub reservation_max_bps: f64,
+    /// KuCoin self-trade prevention mode (CN/CO/CB/DC); unset = none
+    pub stp: Option<SelfTradePrevention>,
+    /// Price band vs mid checked on every order just before send: a quote
+    /// closer than `min` or further than `max` bps is skipped
+    pub min_distance_bps: f64,
+    pub max_distance_bps: f64,
+    /// Fraction of the KuCoin-vs-Binance basis (bps) shifted into the ladder,
+    /// tightening the side that benefits from convergence; 0 = symmetric
+    pub basis_sensitivity: f64,
+    /// Cap on that shift, in bps
+    pub basis_max_bps: f64,
+    /// Per-order size jitter, uniform within ±this percent; 0 = exact sizes
+    pub size_jitter_pct: f64,
+    /// Per-order price jitter away from mid, up to this many bps (capped at
+    /// half the level's refresh threshold); 0 = exact prices
+    pub price_jitter_bps: f64,
+    /// Never pay taker: post-only everywhere, flatten rests at the touch
+    /// instead of an IOC, and a would-cross reject widens that level
+    pub rebate_only: bool,
+    /// Widening (bps) added to a level side per would-cross reject
+    pub rebate_only_widen_bps: f64,
+    /// Level quotes landing on or through the KuCoin opposite touch are
+    /// stepped back inside by up to this many ticks before sending, and a
+    /// post-only would-cross reject is retried once a tick further out in
+    /// the same tick; 0 = off
+    pub post_only_backoff_ticks: u32,
+    /// Mid the ladder is priced from
+    pub mid_source: MidSource,
+    /// Extra skew (bps) toward unwinding right after a fill; 0 = off
+    pub post_fill_skew_bps: f64,
+    /// Seconds for that boost to halve
+    pub post_fill_half_life_secs: f64,
+    /// Scratch fills the mid runs away from with a reducing post-only order
+    pub scratch_on_toxic: bool,
+    /// Adverse markout (bps) that makes a fill toxic
+    pub scratch_toxic_bps: f64,
+    /// How long after a fill its markout is taken
+    pub scratch_markout_secs: f64,
+    /// Ticks conceded past the fill price on the exit
+    pub scratch_exit_ticks: u32,
+    /// Server-side lifetime of a scratch order (GTT)
+    pub scratch_ttl_secs: u64,
+    /// Skip a placement when the age of the mid it was priced from plus the
+    /// expected place latency exceeds this many ms; 0 = off
+    pub mid_latency_budget_ms: u64,
+    /// Percentile of measured WS place latency used as that expectation
+    pub place_latency_pct: f64,
+    /// Anchor the ladder on a drift forecast, mid * (1 + momentum * k),
+    /// instead of the spot mid; 0 = off
+    pub forecast_k: f64,
+    /// Cap on that shift, in bps of mid
+    pub forecast_max_bps: f64,
+    /// Anchor the ladder and refresh targets on an EMA of the reference mid
+    /// with this half-life instead of the raw mid; guards keep the raw mid.
+    /// 0 = off
+    pub anchor_half_life_ms: u64,
+    /// Per-side multipliers on every level's base distance, for running the
+    /// two sides with different economics; 1.0 = as configured
+    pub bid_spread_mult: f64,
+    pub ask_spread_mult: f64,
+    /// Per-side multipliers on every level's size; 1.0 = as sized
+    pub bid_size_mult: f64,
+    pub ask_size_mult: f64,
+    /// Widen a side by this many bps per unit of our resting size over the
+    /// natural KuCoin depth near its touch, beyond `own_depth_neutral`, and
+    /// tighten below it; 0 = off
+    pub own_depth_k: f64,
+    pub own_depth_neutral: f64,
+    /// How far from the touch depth is counted, in bps
+    pub own_depth_window_bps: f64,
+    pub own_depth_max_widen_bps: f64,
+    pub own_depth_max_tighten_bps: f64,
+}
+
+impl Default for QuotingConfig {
+    fn default() -> Self {
+        Self {
+            reservation_gamma: 0.0,
+            reservation_max_bps: 50.0,
+            stp: None,
+            min_distance_bps: 0.0,
+            max_distance_bps: 200.0,
+            basis_sensitivity: 0.0,
+            basis_max_bps: 2.0,
+            size_jitter_pct: 0.0,
+            price_jitter_bps: 0.0,
+            rebate_only: false,
+            rebate_only_widen_bps: 1.0,
+            post_only_backoff_ticks: 0,
+            mid_source: MidSource::Reference,
+            post_fill_skew_bps: 0.0,
+            post_fill_half_life_secs: 30.0,
+            scratch_on_toxic: false,
+            scratch_toxic_bps: 5.0,
+            scratch_markout_secs: 2.0,
+            scratch_exit_ticks: 1,
+            scratch_ttl_secs: 30,
+            mid_latency_budget_ms: 0,
+            place_latency_pct: 0.9,
+            forecast_k: 0.0,
+            forecast_max_bps: 5.0,
+            anchor_half_life_ms: 0,
+            bid_spread_mult: 1.0,
+            ask_spread_mult: 1.0,
+            bid_size_mult: 1.0,
+            ask_size_mult: 1.0,
+            own_depth_k: 0.0,
+            own_depth_neutral: 1.0,
+            own_depth_window_bps: 5.0,
+            own_depth_max_widen_bps: 3.0,
+            own_depth_max_tighten_bps: 1.0,
+        }
+    }
+}
+
+/// Mid anchoring the ladder. `Local` and `Blend` fall back to the
+/// reference mid while no KuCoin mid is available.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MidSource {
+    /// Binance futures bookTicker
+    #[default]
+    Reference,
+    /// KuCoin book mid (REST ticker until the public book is live)
+    Local,
+    /// `w` * local + (1 - w) * reference; w = 0.2 is the old 80/20 fair mid
+    Blend { w: f64 },
+}
+
+impl MidSource {
+    pub fn mid(&self, reference: f64, local: Option<f64>) -> f64 {
+        match (self, local) {
+            (MidSource::Local, Some(l)) => l,
+            (MidSource::Blend { w }, Some(l)) => {
+                let w = w.clamp(0.0, 1.0);
+                w * l + (1.0 - w) * reference
+            }
+            _ => reference,
+        }
+    }
+
+    /// Whether the KuCoin public book feed is needed
+    pub fn needs_local(&self) -> bool {
+        !matches!(self, MidSource::Reference)
+    }
+}
+
+/// Which flow signal drives the OFI pause / trend-protect logic
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToxicitySource {
+    /// Binance futures depth5 OFI (original behaviour)
+    BinanceOfi,
+    /// KuCoin spot depth imbalance
+    KucoinImbalance,
+    /// `kucoin_weight * kucoin + (1 - kucoin_weight) * binance`
+    Blend,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SignalsConfig {
+    pub toxicity_source: ToxicitySource,
+    /// KuCoin share of the blend, 0..1
+    pub kucoin_weight: f64,
+    /// Book levels summed for KuCoin imbalance (depth5 feed: max 5)
+    pub kucoin_depth_levels: usize,
+    /// How often the KuCoin book is sampled into market data
+    pub kucoin_sample_ms: u64,
+    /// Book older than this is ignored (falls back to Binance OFI)
+    pub kucoin_stale_ms: u64,
+    /// Window for the Binance aggTrade aggressor imbalance
+    pub trade_flow_window_secs: u64,
+    /// OFI resume also needs |aggressor imbalance| at or below this; 1 = off
+    pub resume_max_trade_imbalance: f64,
+    /// Both must stay calm this long before resuming; 0 = resume at once
+    pub resume_min_calm_secs: u64,
+    /// Binance OFI with no non-empty depth5 update for this long reads as 0
+    /// (neutral); 0 = never stale
+    pub ofi_stale_secs: u64,
+}
+
+impl Default for SignalsConfig {
+    fn default() -> Self {
+        Self {
+            toxicity_source: ToxicitySource::BinanceOfi,
+            kucoin_weight: 0.5,
+            kucoin_depth_levels: 5,
+            kucoin_sample_ms: 250,
+            kucoin_stale_ms: 2000,
+            trade_flow_window_secs: 10,
+            resume_max_trade_imbalance: 1.0,
+            resume_min_calm_secs: 0,
+            ofi_stale_secs: 10,
+        }
+    }
+}
+
+impl SignalsConfig {
+    /// Flow signal for the pause/skip logic; Binance OFI if the KuCoin book is unavailable
+    pub fn toxicity(&self, binance_ofi: f64, kucoin_imbalance: Option<f64>) -> f64 {
+        match (self.toxicity_source, kucoin_imbalance) {
+            (ToxicitySource::KucoinImbalance, Some(k)) => k,
+            (ToxicitySource::Blend, Some(k)) => {
+                let w = self.kucoin_weight.clamp(0.0, 1.0);
+                w * k + (1.0 - w) * binance_ofi
+            }
+            _ => binance_ofi,
+        }
+    }
+}
+
+/// Fee rates for fills the exchange doesn't report a fee for
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct FeesConfig {
+    /// Taker fee in bps of notional (maker rebate stays REBATE)
+    pub taker_bps: f64,
+}
+
+impl Default for FeesConfig {
+    fn default() -> Self {
+        Self { taker_bps: 10.0 }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StartupMode {
+    /// Cancel everything before the feeds are up (original behaviour)
+    #[default]
+    CancelAll,
+    /// Bring feeds and inventory up first, then adopt our own resting
+    /// level orders and cancel only what can't be adopted
+    Adopt,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct StartupConfig {
+    pub mode: StartupMode,
+    /// Skip the API key permission check (also `--skip-preflight`)
+    pub skip_preflight: bool,
+    /// Base balance (SOL) that counts as flat. When set, startup seeds
+    /// inventory from the account's base balance over it instead of
+    /// trusting the saved FIFO; unset = saved FIFO or flat.
+    pub baseline_balance: Option<f64>,
+    /// Entry price for a seeded position; unset = the mid at startup
+    pub avg_entry: Option<f64>,
+    /// Give each adopted order the refresh check on its first tick, without
+    /// waiting out the minimum rest, so one priced for a market that moved
+    /// during the restart is re-placed at once
+    pub verify_adopted: bool,
+}
+
+impl Default for StartupConfig {
+    fn default() -> Self {
+        Self { mode: StartupMode::default(), skip_preflight: false, baseline_balance: None, avg_entry: None, verify_adopted: true }
+    }
+}
+
+/// KuCoin account the bot trades from - keeps balance, order and fill polls consistent
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TradeMode {
+    #[default]
+    Trade,
+    MarginTrade,
+    MarginIsolatedTrade,
+}
+
+impl TradeMode {
+    /// `tradeType` query value for orders / fills
+    pub fn trade_type(&self) -> &'static str {
+        match self {
+            TradeMode::Trade => "TRADE",
+            TradeMode::MarginTrade => "MARGIN_TRADE",
+            TradeMode::MarginIsolatedTrade => "MARGIN_ISOLATED_TRADE",
+        }
+    }
+
+    /// WS order ops that place into this account
+    pub fn order_entry(&self) -> OrderEntry {
+        match self {
+            TradeMode::Trade => OrderEntry::Spot,
+            TradeMode::MarginTrade => OrderEntry::Margin,
+            TradeMode::MarginIsolatedTrade => OrderEntry::IsolatedMargin,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct AccountConfig {
+    pub trade_mode: TradeMode,
+    /// WS order-entry op set; unset = the one matching `trade_mode`
+    pub order_entry: Option<OrderEntry>,
+    /// SOL / USDT the bot never quotes with, on top of the percentage
+    /// buffer - a cushion for manual withdrawals on a shared account
+    pub reserve_base: f64,
+    pub reserve_quote: f64,
+}
+
+impl AccountConfig {
+    /// Order entry to use; an explicit one must match the polled account
+    pub fn order_entry(&self) -> Result<OrderEntry> {
+        let expected = self.trade_mode.order_entry();
+        match self.order_entry {
+            Some(e) if e != expected => anyhow::bail!(
+                "account.order_entry {:?} sends orders to a different account than trade_mode {:?} polls (expects {:?})",
+                e, self.trade_mode, expected),
+            _ => Ok(expected),
+        }
+    }
+}
+
+/// How the inventory-increasing side's size shrinks with inventory
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SizePolicy {
+    /// `base * exp(ETA * |inv|)` (original behaviour)
+    #[default]
+    Exponential,
+    /// Lot-aligned tiers from `steps`, never below the symbol minimum
+    Steps,
+}
+
+/// From `inventory` SOL (absolute) on, quote `fraction` of the base size
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct SizeStep {
+    pub inventory: f64,
+    pub fraction: f64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SizingConfig {
+    pub policy: SizePolicy,
+    pub steps: Vec<SizeStep>,
+    /// Trailing window for the buy-vs-sell fill volume ratio
+    pub fill_imbalance_window_secs: u64,
+    /// Side size shrinks by sensitivity * |fill imbalance| when it is the
+    /// side filling more; 0 = off
+    pub fill_imbalance_sensitivity: f64,
+    /// Floor on that shrink, as a multiple of the inventory-based size
+    pub fill_imbalance_min_mult: f64,
+    /// Grow the side the live KuCoin book favours by coeff * (|depth
+    /// imbalance| - threshold); 0 = off
+    pub book_boost_coeff: f64,
+    /// |Depth imbalance| below this gets no boost
+    pub book_boost_threshold: f64,
+    /// Cap on that boost, as a multiple of the size
+    pub book_boost_max_mult: f64,
+    /// Clamp every order's notional to this, after all size adjustments;
+    /// never below the exchange minimum size. 0 = off
+    pub max_notional_per_level_usd: f64,
+}
+
+impl Default for SizingConfig {
+    fn default() -> Self {
+        Self {
+            policy: SizePolicy::Exponential,
+            steps: vec![
+                SizeStep { inventory: 3.0, fraction: 0.75 },
+                SizeStep { inventory: 6.0, fraction: 0.5 },
+                SizeStep { inventory: 10.0, fraction: 0.25 },
+            ],
+            fill_imbalance_window_secs: 300,
+            fill_imbalance_sensitivity: 0.0,
+            fill_imbalance_min_mult: 0.25,
+            book_boost_coeff: 0.0,
+            book_boost_threshold: 0.3,
+            book_boost_max_mult: 1.5,
+            max_notional_per_level_usd: 0.0,
+        }
+    }
+}
+
+/// Time-based stand-down, separate from the risk pauses
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ScheduleConfig {
+    /// UTC `"HH:MM-HH:MM"` windows with no quoting (may wrap midnight)
+    pub pause_windows_utc: Vec<String>,
+}
+
+/// Hard risk stops - these halt the bot, not just pause it
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct RiskConfig {
+    /// Max seconds |inventory| may stay above `hold_band_sol` before a
+    /// forced flatten + halt; 0 = off
+    pub max_hold_secs: u64,
+    pub hold_band_sol: f64,
+    /// How far through mid the IOC flatten order is priced
+    pub flatten_slippage_bps: f64,
+    /// Same, when the KuCoin book is stale or empty and the flatten falls
+    /// back to the reference mid
+    pub flatten_fallback_slippage_bps: f64,
+    /// Inventory (SOL) the hold band is centred on and the flatten / scratch
+    /// paths reduce toward; 0 = flat
+    pub inv_target: f64,
+    /// Those paths leave inventory alone within this of `inv_target`
+    pub flatten_band_sol: f64,
+}
+
+impl Default for RiskConfig {
+    fn default() -> Self {
+        Self {
+            max_hold_secs: 0, hold_band_sol: 2.0, flatten_slippage_bps: 30.0, flatten_fallback_slippage_bps: 60.0,
+            inv_target: 0.0, flatten_band_sol: 0.0,
+        }
+    }
+}
+
+/// `--drain` / SIGUSR1: stop placing and let resting orders run out
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct DrainConfig {
+    /// Orders still open after this long are cancelled and the bot exits
+    pub deadline_secs: u64,
+}
+
+impl Default for DrainConfig {
+    fn default() -> Self {
+        Self { deadline_secs: 600 }
+    }
+}
+
+/// Cross-check the KuCoin WS book against the REST snapshot (needs the
+/// public book feed running)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct BookCheckConfig {
+    /// Seconds between checks; 0 = off
+    pub interval_secs: u64,
+    /// Levels per side compared
+    pub top_n: usize,
+    /// Worst per-level price gap tolerated before a resync
+    pub tolerance_bps: f64,
+}
+
+impl Default for BookCheckConfig {
+    fn default() -> Self {
+        Self { interval_secs: 0, top_n: 5, tolerance_bps: 5.0 }
+    }
+}
+
+/// In-memory recent-events ring buffer (`/status?events=N`)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct EventLogConfig {
+    pub capacity: usize,
+    /// Maker fills at or above this notional (USDT) are logged; taker fills always are
+    pub big_fill_usd: f64,
+}
+
+impl Default for EventLogConfig {
+    fn default() -> Self {
+        Self { capacity: 500, big_fill_usd: 100.0 }
+    }
+}
+
+/// Order WS reconnect handling
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ReconnectConfig {
+    /// Hold new placement after a reconnect until orders, balances and
+    /// fills have all been re-polled
+    pub resync_before_placing: bool,
+}
+
+impl Default for ReconnectConfig {
+    fn default() -> Self {
+        Self { resync_before_placing: true }
+    }
+}
+
+/// Reconnect backoff for the private fill WS
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct PrivateWsConfig {
+    /// First retry delay; doubles per failed attempt
+    pub backoff_base_ms: u64,
+    pub backoff_max_ms: u64,
+    /// Each delay is scaled by up to ±this percent so restarts don't reconnect in lockstep
+    pub backoff_jitter_pct: f64,
+}
+
+impl Default for PrivateWsConfig {
+    fn default() -> Self {
+        Self { backoff_base_ms: 500, backoff_max_ms: 30_000, backoff_jitter_pct: 20.0 }
+    }
+}
+
+/// Offset KuCoin inventory on a second venue (log-only venue for now)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct HedgeConfig {
+    pub enabled: bool,
+    /// Hedge venue symbol
+    pub symbol: String,
+    /// Fraction of inventory hedged; 1 = fully delta neutral
+    pub ratio: f64,
+    /// Drift from the target hedge (base units) tolerated before adjusting
+    pub band: f64,
+    /// Smallest hedge order the venue accepts
+    pub min_size: f64,
+}
+
+impl Default for HedgeConfig {
+    fn default() -> Self {
+        Self { enabled: false, symbol: "SOLUSDT".to_string(), ratio: 1.0, band: 0.5, min_size: 0.01 }
+    }
+}
+
+/// Main loop timer periods
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct CadenceConfig {
+    /// Quoting tick
+    pub tick_ms: u64,
+    /// Order/balance reconciliation; must not be faster than the tick
+    pub recon_ms: u64,
+    /// REST fill poll
+    pub fill_poll_ms: u64,
+    /// Summary log / status publish
+    pub log_secs: u64,
+    /// Order-entry requests per second the account can sustain; the tick is
+    /// checked against it at startup
+    pub order_rate_limit_per_sec: f64,
+    /// Recon period right after an order WS reconnect, when local state is
+    /// most likely stale; may be faster than the tick
+    pub recon_fast_ms: u64,
+    /// How long that lasts before relaxing to `recon_ms`; 0 = off
+    pub recon_fast_secs: u64,
+    /// Orphan cancels per recon, from `orphan_cancels_min` when flat up to
+    /// `orphan_cancels_max` at the inventory limit, so cleanup speeds up
+    /// when the bot is stressed
+    pub orphan_cancels_min: usize,
+    pub orphan_cancels_max: usize,
+}
+
+impl Default for CadenceConfig {
+    fn default() -> Self {
+        Self {
+            tick_ms: 500, recon_ms: 1000, fill_poll_ms: 5000, log_secs: 30, order_rate_limit_per_sec: 100.0,
+            recon_fast_ms: 250, recon_fast_secs: 30, orphan_cancels_min: 5, orphan_cancels_max: 5,
+        }
+    }
+}
+
+impl CadenceConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.tick_ms == 0 || self.recon_ms == 0 || self.fill_poll_ms == 0 || self.log_secs == 0 {
+            anyhow::bail!("cadence intervals must be non-zero");
+        }
+        if self.tick_ms > self.recon_ms {
+            anyhow::bail!("cadence.tick_ms ({}) must not exceed cadence.recon_ms ({})", self.tick_ms, self.recon_ms);
+        }
+        if self.recon_fast_secs > 0 && self.recon_fast_ms == 0 {
+            anyhow::bail!("cadence.recon_fast_ms must be non-zero while recon_fast_secs is set");
+        }
+        if self.orphan_cancels_min > self.orphan_cancels_max {
+            anyhow::bail!("cadence.orphan_cancels_min ({}) must not exceed orphan_cancels_max ({})",
+                self.orphan_cancels_min, self.orphan_cancels_max);
+        }
+        Ok(())
+    }
+
+    /// Recon period at `now`: tightened until `fast_until`, then the normal one
+    pub fn recon_period(&self, fast_until: Option<Instant>, now: Instant) -> Duration {
+        match fast_until {
+            Some(until) if now < until => Duration::from_millis(self.recon_fast_ms),
+            _ => Duration::from_millis(self.recon_ms),
+        }
+    }
+
+    /// Orphan-cancel budget at `inv`, linear in |inv| / max_inv
+    pub fn orphan_cancel_budget(&self, inv: f64, max_inv: f64) -> usize {
+        let stress = if max_inv > 0.0 { (inv.abs() / max_inv).min(1.0) } else { 1.0 };
+        let span = (self.orphan_cancels_max - self.orphan_cancels_min) as f64;
+        self.orphan_cancels_min + (span * stress).round() as usize
+    }
+
+    /// Actions one tick can send without exceeding the order-entry rate limit
+    pub fn sustainable_actions_per_tick(&self) -> f64 {
+        self.order_rate_limit_per_sec * self.tick_ms as f64 / 1000.0
+    }
+}
+
+/// Freeze new placement when KuCoin rejects too many of them
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct EntryBreakerConfig {
+    pub enabled: bool,
+    /// Trailing window the error rate is measured over
+    pub window_secs: u64,
+    /// Placements needed in the window before it can trip
+    pub min_samples: usize,
+    /// Failed fraction of placements that trips it
+    pub max_error_rate: f64,
+    /// Freeze length before a single probe placement
+    pub cooldown_secs: u64,
+}
+
+impl Default for EntryBreakerConfig {
+    fn default() -> Self {
+        Self { enabled: false, window_secs: 30, min_samples: 10, max_error_rate: 0.5, cooldown_secs: 60 }
+    }
+}
+
+/// Halt quoting when the symbol is delisted or suspended
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct MarketStatusConfig {
+    pub enabled: bool,
+    /// Symbol endpoint poll period, for `enableTrading` and resumption
+    pub check_secs: u64,
+    /// Consecutive not-trading placement rejects that halt quoting
+    pub reject_threshold: u32,
+    /// Shortest halt before the symbol check may resume quoting
+    pub min_halt_secs: u64,
+    /// Try to flatten inventory on halt (fails harmlessly if the market is shut)
+    pub flatten: bool,
+}
+
+impl Default for MarketStatusConfig {
+    fn default() -> Self {
+        Self { enabled: true, check_secs: 60, reject_threshold: 3, min_halt_secs: 60, flatten: true }
+    }
+}
+
+/// Quote ladder: one (offset_bps, refresh_threshold_bps) pair per level,
+/// innermost first. Levels are keyed by `(offset_bps * 10) as i32`, so
+/// offsets must be positive, strictly increasing and 0.1bps apart.
+///
+/// ```toml
+/// levels = [[0.25, 1.92], [0.75, 4.24], [1.25, 6.52]]
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LadderConfig {
+    pub levels: Vec<(f64, f64)>,
+}
+
+/// `ladder_file` unless configured; the only path allowed to be missing
+pub const DEFAULT_LADDER_FILE: &str = "ladder.toml";
+
+impl LadderConfig {
+    /// Load from `path` (`.json` as JSON, anything else TOML). `default` if
+    /// the path is empty or is `DEFAULT_LADDER_FILE` and doesn't exist; a
+    /// configured path that can't be read is an error, not the built-in table.
+    pub fn load(path: &str, default: &[(f64, f64)]) -> Result<Self> {
+        let text = match std::fs::read_to_string(path) {
+            _ if path.is_empty() => None,
+            Ok(text) => Some(text),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound && path == DEFAULT_LADDER_FILE => None,
+            Err(e) => return Err(e).with_context(|| format!("can't read ladder file {}", path)),
+        };
+        let Some(text) = text else {
+            info!("[LADDER] No ladder file, using the built-in {} levels", default.len());
+            return Ok(Self { levels: default.to_vec() });
+        };
+        let ladder = Self::parse(&text, path.ends_with(".json")).with_context(|| format!("invalid ladder file {}", path))?;
+        info!("[LADDER] Loaded {} levels from {}", ladder.levels.len(), path);
+        Ok(ladder)
+    }
+
+    pub fn parse(text: &str, json: bool) -> Result<Self> {
+        let ladder: Self = if json { serde_json::from_str(text)? } else { toml::from_str(text)? };
+        ladder.validate()?;
+        Ok(ladder)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.levels.is_empty() {
+            anyhow::bail!("ladder has no levels");
+        }
+        for (i, &(bps, refresh)) in self.levels.iter().enumerate() {
+            if !(bps > 0.0 && refresh > 0.0) {
+                anyhow::bail!("level {} ({}, {}): offset and refresh threshold must be positive", i + 1, bps, refresh);
+            }
+            if let Some(&(prev, _)) = i.checked_sub(1).map(|j| &self.levels[j]) {
+                if (bps * 10.0) as i32 <= (prev * 10.0) as i32 {
+                    anyhow::bail!("level {} offset {}bps must be at least 0.1bps past {}bps", i + 1, bps, prev);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Config {
+    /// Load from `MM_CONFIG` or `config.toml`; defaults if the file is absent
+    pub fn load() -> Result<Self> {
+        let path = std::env::var("MM_CONFIG").unwrap_or_else(|_| DEFAULT_CONFIG_FILE.to_string());
+        match std::fs::read_to_string(&path) {
+            Ok(text) => {
+                let cfg = Self::parse(&text).with_context(|| format!("invalid config file {}", path))?;
+                info!("[CONFIG] Loaded {}", path);
+                Ok(cfg)
+            }
+            Err(_) => {
+                info!("[CONFIG] No {} found, using defaults", path);
+                Ok(Self::default())
+            }
+        }
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        let cfg: Self = toml::from_str(text)?;
+        cfg.cadence.validate()?;
+        if (1..100).contains(&cfg.fill_dedup_capacity) {
+            anyhow::bail!("fill_dedup_capacity ({}) must be 0 or at least 100 - the fill poll alone returns 20", cfg.fill_dedup_capacity);
+        }
+        cfg.account.order_entry()?;
+        Ok(cfg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_config_uses_defaults() {
+        let cfg = Config::parse("").unwrap();
+        assert!(cfg.status_addr.is_empty());
+        assert!(!cfg.latency_probe.enabled);
+    }
+
+    #[test]
+    fn test_partial_section_keeps_other_defaults() {
+        let cfg = Config::parse("[latency_probe]\nenabled = true\nalert_ms = 250\n").unwrap();
+        assert!(cfg.latency_probe.enabled);
+        assert_eq!(cfg.latency_probe.alert_ms, 250);
+        assert_eq!(cfg.latency_probe.interval_secs, 60);
+    }
+
+    #[test]
+    fn test_stp_config() {
+        assert_eq!(Config::parse("").unwrap().quoting.stp, None);
+        let cfg = Config::parse("[quoting]\nstp = \"CB\"\n").unwrap();
+        assert_eq!(cfg.quoting.stp, Some(SelfTradePrevention::CB));
+    }
+
+    #[test]
+    fn test_sizing_steps_config() {
+        assert_eq!(Config::parse("").unwrap().sizing.policy, SizePolicy::Exponential);
+        let cfg = Config::parse(
+            "[sizing]\npolicy = \"steps\"\nsteps = [{ inventory = 5.0, fraction = 0.5 }]\n",
+        ).unwrap();
+        assert_eq!(cfg.sizing.policy, SizePolicy::Steps);
+        assert_eq!(cfg.sizing.steps, vec![SizeStep { inventory: 5.0, fraction: 0.5 }]);
+    }
+
+    #[test]
+    fn test_toxicity_source_selection() {
+        let cfg = Config::parse("[signals]\ntoxicity_source = \"blend\"\nkucoin_weight = 0.25\n").unwrap();
+        let s = &cfg.signals;
+        assert!((s.toxicity(0.8, Some(-0.4)) - 0.5).abs() < 1e-9);
+        // No KuCoin book -> Binance only
+        assert_eq!(s.toxicity(0.8, None), 0.8);
+
+        let s = SignalsConfig { toxicity_source: ToxicitySource::KucoinImbalance, ..Default::default() };
+        assert_eq!(s.toxicity(0.8, Some(-0.4)), -0.4);
+        assert_eq!(SignalsConfig::default().toxicity(0.8, Some(-0.4)), 0.8);
+    }
+
+    #[test]
+    fn test_mid_source_blend() {
+        assert_eq!(Config::parse("").unwrap().quoting.mid_source, MidSource::Reference);
+        let cfg = Config::parse("[quoting]\nmid_source = { blend = { w = 0.25 } }\n").unwrap();
+        let src = cfg.quoting.mid_source;
+        assert_eq!(src, MidSource::Blend { w: 0.25 });
+        assert!((src.mid(100.0, Some(104.0)) - 101.0).abs() < 1e-9);
+        // No KuCoin mid yet -> reference only
+        assert_eq!(src.mid(100.0, None), 100.0);
+        assert_eq!(MidSource::Local.mid(100.0, Some(104.0)), 104.0);
+        assert_eq!(MidSource::Reference.mid(100.0, Some(104.0)), 100.0);
+        assert!(!MidSource::Reference.needs_local() && MidSource::Local.needs_local());
+    }
+
+    #[test]
+    fn test_order_entry_follows_trade_mode() {
+        assert_eq!(Config::parse("").unwrap().account.order_entry().unwrap(), OrderEntry::Spot);
+        let cfg = Config::parse("[account]\ntrade_mode = \"margin_isolated_trade\"\n").unwrap();
+        assert_eq!(cfg.account.order_entry().unwrap(), OrderEntry::IsolatedMargin);
+        assert!(Config::parse("[account]\ntrade_mode = \"margin_trade\"\norder_entry = \"margin\"\n").is_ok());
+        // Spot orders while polling the margin account
+        assert!(Config::parse("[account]\ntrade_mode = \"margin_trade\"\norder_entry = \"spot\"\n").is_err());
+    }
+
+    #[test]
+    fn test_cadence_validation() {
+        let cfg = Config::parse("[cadence]
+tick_ms = 100
+").unwrap();
+        assert_eq!((cfg.cadence.tick_ms, cfg.cadence.recon_ms), (100, 1000));
+        assert!((cfg.cadence.sustainable_actions_per_tick() - 10.0).abs() < 1e-9);
+        assert!(Config::parse("[cadence]
+tick_ms = 2000
+").is_err());
+        assert!(Config::parse("[cadence]
+log_secs = 0
+").is_err());
+    }
+
+    #[test]
+    fn test_recon_tightens_until_window_end() {
+        let c = CadenceConfig::default();
+        let t0 = Instant::now();
+        let until = Some(t0 + Duration::from_secs(30));
+        assert_eq!(c.recon_period(None, t0), Duration::from_millis(1000));
+        assert_eq!(c.recon_period(until, t0 + Duration::from_secs(29)), Duration::from_millis(250));
+        assert_eq!(c.recon_period(until, t0 + Duration::from_secs(30)), Duration::from_millis(1000));
+        assert!(Config::parse("[cadence]
+recon_fast_ms = 0
+").is_err());
+    }
+
+    #[test]
+    fn test_orphan_budget_scales_with_inventory() {
+        // Default: the old fixed 5 whatever the inventory
+        let c = CadenceConfig::default();
+        assert_eq!((c.orphan_cancel_budget(0.0, 10.0), c.orphan_cancel_budget(10.0, 10.0)), (5, 5));
+
+        let c = CadenceConfig { orphan_cancels_min: 2, orphan_cancels_max: 12, ..CadenceConfig::default() };
+        assert_eq!(c.orphan_cancel_budget(0.0, 10.0), 2);
+        assert_eq!(c.orphan_cancel_budget(2.5, 10.0), 5);
+        assert_eq!(c.orphan_cancel_budget(-5.0, 10.0), 7);
+        assert_eq!(c.orphan_cancel_budget(10.0, 10.0), 12);
+        // Past the limit stays at the max
+        assert_eq!(c.orphan_cancel_budget(-25.0, 10.0), 12);
+        assert!(Config::parse("[cadence]
+orphan_cancels_min = 6
+orphan_cancels_max = 3
+").is_err());
+    }
+
+    #[test]
+    fn test_ladder_file_parses_and_validates() {
+        let toml = LadderConfig::parse("levels = [[0.25, 1.92], [0.75, 4.24], [1.25, 6.52]]\n", false).unwrap();
+        let json = LadderConfig::parse(r#"{"levels": [[0.25, 1.92], [0.75, 4.24], [1.25, 6.52]]}"#, true).unwrap();
+        assert_eq!(toml, json);
+        assert_eq!(toml.levels[1], (0.75, 4.24));
+        // Not increasing, zero offset, keys colliding at 0.1bps, empty
+        assert!(LadderConfig::parse("levels = [[0.75, 4.24], [0.25, 1.92]]", false).is_err());
+        assert!(LadderConfig::parse("levels = [[0.0, 1.92]]", false).is_err());
+        assert!(LadderConfig::parse("levels = [[0.25, 1.92], [0.26, 1.95]]", false).is_err());
+        assert!(LadderConfig::parse("levels = []", false).is_err());
+        // Default file absent, or none configured - the built-in table
+        assert!(!std::path::Path::new(DEFAULT_LADDER_FILE).exists());
+        let ladder = LadderConfig::load(DEFAULT_LADDER_FILE, &[(0.5, 2.0)]).unwrap();
+        assert_eq!(ladder.levels, vec![(0.5, 2.0)]);
+        assert_eq!(LadderConfig::load("", &[(0.5, 2.0)]).unwrap().levels, vec![(0.5, 2.0)]);
+        // A configured path that's missing (typo) or unreadable stops startup
+        let err = LadderConfig::load("/nonexistent/ladder.toml", &[(0.5, 2.0)]).unwrap_err();
+        assert!(err.to_string().contains("/nonexistent/ladder.toml"));
+        assert!(LadderConfig::load("/", &[(0.5, 2.0)]).is_err());
+    }
+}
@@ -0,0 +1,73 @@
+//! Drain Mode
+//!
+//! Redeploy without giving up queue position: stop placing, leave resting
+//! orders alone and keep reconciling and booking fills until the book is
+//! empty or the deadline passes. Unlike shutdown, nothing is cancelled up
+//! front - only what is still open at the deadline.
+
+use std::time::{Duration, Instant};
+
+/// Outcome of one drain check
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DrainStep {
+    /// Orders still resting; `changed` when the count moved since last check
+    Waiting { changed: bool },
+    /// Every order filled or expired
+    Done,
+    /// Deadline hit with orders still open
+    Expired,
+}
+
+#[derive(Debug)]
+pub struct Drain {
+    started: Instant,
+    deadline: Duration,
+    last_left: Option<usize>,
+}
+
+impl Drain {
+    pub fn new(deadline: Duration, now: Instant) -> Self {
+        Self { started: now, deadline, last_left: None }
+    }
+
+    /// Feed the current open-order count
+    pub fn check(&mut self, left: usize, now: Instant) -> DrainStep {
+        let changed = self.last_left != Some(left);
+        self.last_left = Some(left);
+        if left == 0 {
+            DrainStep::Done
+        } else if now.duration_since(self.started) >= self.deadline {
+            DrainStep::Expired
+        } else {
+            DrainStep::Waiting { changed }
+        }
+    }
+
+    pub fn remaining(&self, now: Instant) -> Duration {
+        self.deadline.saturating_sub(now.duration_since(self.started))
+    }
+
+    pub fn left(&self) -> Option<usize> {
+        self.last_left
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drain_until_empty_or_deadline() {
+        let t0 = Instant::now();
+        let mut d = Drain::new(Duration::from_secs(60), t0);
+        assert_eq!(d.check(4, t0), DrainStep::Waiting { changed: true });
+        assert_eq!(d.check(4, t0 + Duration::from_secs(1)), DrainStep::Waiting { changed: false });
+        assert_eq!(d.check(1, t0 + Duration::from_secs(2)), DrainStep::Waiting { changed: true });
+        assert_eq!(d.remaining(t0 + Duration::from_secs(2)), Duration::from_secs(58));
+        assert_eq!(d.check(0, t0 + Duration::from_secs(3)), DrainStep::Done);
+
+        let mut d = Drain::new(Duration::from_secs(60), t0);
+        assert_eq!(d.check(2, t0 + Duration::from_secs(60)), DrainStep::Expired);
+        assert_eq!(d.remaining(t0 + Duration::from_secs(90)), Duration::ZERO);
+    }
+}
@@ -0,0 +1,140 @@
+//! Order-Entry Circuit Breaker
+//!
+//! When KuCoin rejects placements systemically (maintenance, `400100`
+//! system errors) retrying every tick only adds load. Placement results are
+//! tracked over a trailing window; once the error rate crosses the
+//! threshold new placement freezes for a cooldown, then a single order is
+//! let through as a probe. The probe succeeding resumes quoting, failing
+//! restarts the cooldown. Cancels and reconciliation are never blocked.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BreakerState {
+    Open,
+    Frozen { until: Instant },
+    /// Cooldown over, waiting on one probe placement
+    Probing,
+}
+
+impl BreakerState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BreakerState::Open => "open",
+            BreakerState::Frozen { .. } => "frozen",
+            BreakerState::Probing => "probing",
+        }
+    }
+}
+
+/// How many placements this tick may send
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Admit {
+    All,
+    One,
+    None,
+}
+
+/// State changes worth logging
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Transition {
+    Froze { error_rate: f64 },
+    ProbeFailed,
+    Resumed,
+}
+
+#[derive(Debug)]
+pub struct EntryBreaker {
+    window: Duration,
+    min_samples: usize,
+    max_error_rate: f64,
+    cooldown: Duration,
+    /// (time, ok) per placement while open
+    results: VecDeque<(Instant, bool)>,
+    state: BreakerState,
+}
+
+impl EntryBreaker {
+    pub fn new(window: Duration, min_samples: usize, max_error_rate: f64, cooldown: Duration) -> Self {
+        Self { window, min_samples, max_error_rate, cooldown, results: VecDeque::new(), state: BreakerState::Open }
+    }
+
+    pub fn state(&self) -> BreakerState {
+        self.state
+    }
+
+    /// Call once per tick before placing; moves a finished cooldown to probing
+    pub fn admit(&mut self, now: Instant) -> Admit {
+        match self.state {
+            BreakerState::Open => Admit::All,
+            BreakerState::Frozen { until } if now < until => Admit::None,
+            BreakerState::Frozen { .. } | BreakerState::Probing => {
+                self.state = BreakerState::Probing;
+                Admit::One
+            }
+        }
+    }
+
+    /// Feed one placement result
+    pub fn record(&mut self, ok: bool, now: Instant) -> Option<Transition> {
+        match self.state {
+            BreakerState::Probing if ok => {
+                self.state = BreakerState::Open;
+                self.results.clear();
+                Some(Transition::Resumed)
+            }
+            BreakerState::Probing => {
+                self.state = BreakerState::Frozen { until: now + self.cooldown };
+                Some(Transition::ProbeFailed)
+            }
+            // Stragglers from the tick that tripped it
+            BreakerState::Frozen { .. } => None,
+            BreakerState::Open => {
+                self.results.push_back((now, ok));
+                while self.results.front().is_some_and(|(t, _)| now.duration_since(*t) > self.window) {
+                    self.results.pop_front();
+                }
+                let errors = self.results.iter().filter(|(_, ok)| !ok).count();
+                let error_rate = errors as f64 / self.results.len() as f64;
+                if self.results.len() >= self.min_samples && error_rate > self.max_error_rate {
+                    self.state = BreakerState::Frozen { until: now + self.cooldown };
+                    Some(Transition::Froze { error_rate })
+                } else {
+                    None
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_freeze_probe_resume_cycle() {
+        let t0 = Instant::now();
+        let s = Duration::from_secs;
+        let mut b = EntryBreaker::new(s(30), 4, 0.5, s(60));
+        assert_eq!(b.admit(t0), Admit::All);
+        // Below min samples nothing trips, even all errors
+        assert_eq!(b.record(false, t0), None);
+        assert_eq!(b.record(false, t0), None);
+        assert_eq!(b.record(true, t0), None);
+        assert_eq!(b.record(false, t0), Some(Transition::Froze { error_rate: 0.75 }));
+        assert_eq!(b.record(false, t0), None);
+        assert_eq!(b.admit(t0 + s(59)), Admit::None);
+        // Cooldown over - one probe, which fails
+        assert_eq!(b.admit(t0 + s(60)), Admit::One);
+        assert_eq!(b.record(false, t0 + s(60)), Some(Transition::ProbeFailed));
+        assert_eq!(b.admit(t0 + s(61)), Admit::None);
+        // No placement planned on the first probe tick - still probing next tick
+        assert_eq!(b.admit(t0 + s(120)), Admit::One);
+        assert_eq!(b.admit(t0 + s(121)), Admit::One);
+        assert_eq!(b.record(true, t0 + s(121)), Some(Transition::Resumed));
+        assert_eq!(b.state(), BreakerState::Open);
+        // Old errors were cleared with the resume
+        assert_eq!(b.record(false, t0 + s(122)), None);
+    }
+}
@@ -0,0 +1,80 @@
+//! Recent Event Log
+//!
+//! Fixed-capacity ring buffer of significant events (pauses, reconnects,
+//! halts, big fills, drift) kept in memory for post-incident debugging.
+//! Served by `/status?events=N`; the oldest entries drop off once full.
+
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::RwLock;
+
+#[derive(Debug, Clone)]
+pub struct Event {
+    /// Unix ms
+    pub ts_ms: u64,
+    /// Short category, e.g. "pause", "ws", "halt", "fill"
+    pub kind: &'static str,
+    pub msg: String,
+}
+
+#[derive(Debug)]
+pub struct EventLog {
+    cap: usize,
+    events: VecDeque<Event>,
+}
+
+pub type SharedEventLog = Arc<RwLock<EventLog>>;
+
+pub fn new_shared_event_log(cap: usize) -> SharedEventLog {
+    Arc::new(RwLock::new(EventLog::new(cap)))
+}
+
+/// Append one event to the shared log
+pub async fn record(log: &SharedEventLog, kind: &'static str, msg: impl Into<String>) {
+    log.write().await.push(kind, msg.into(), now_ms());
+}
+
+pub fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+}
+
+impl EventLog {
+    pub fn new(cap: usize) -> Self {
+        Self { cap: cap.max(1), events: VecDeque::with_capacity(cap.max(1)) }
+    }
+
+    pub fn push(&mut self, kind: &'static str, msg: String, ts_ms: u64) {
+        if self.events.len() == self.cap {
+            self.events.pop_front();
+        }
+        self.events.push_back(Event { ts_ms, kind, msg });
+    }
+
+    /// Last `n` events, newest first
+    pub fn recent(&self, n: usize) -> impl Iterator<Item = &Event> {
+        self.events.iter().rev().take(n)
+    }
+
+    pub fn recent_json(&self, n: usize) -> Value {
+        Value::Array(self.recent(n).map(|e| json!({ "ts_ms": e.ts_ms, "kind": e.kind, "msg": e.msg })).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ring_buffer_drops_oldest() {
+        let mut log = EventLog::new(3);
+        for i in 0..5 {
+            log.push("pause", format!("e{}", i), i);
+        }
+        let msgs: Vec<&str> = log.recent(10).map(|e| e.msg.as_str()).collect();
+        assert_eq!(msgs, vec!["e4", "e3", "e2"]);
+        assert_eq!(log.recent(1).next().unwrap().ts_ms, 4);
+        assert_eq!(log.recent_json(2)[1]["msg"], "e3");
+    }
+}
@@ -4,11 +4,12 @@
 //! - Order fills (match events)
 //! - Order status changes (open, done, cancelled)
 //!
-//! Uses exponential backoff for reconnection.
+//! Uses jittered exponential backoff for reconnection (see `Backoff`). Every order event is also
+//! re-broadcast as an `OrderUpdate` for consumers outside the order manager.
 
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, RwLock};
 use tokio::time::interval;
 use futures_util::{StreamExt, SinkExt};
 use tokio_tungstenite::{connect_async, tungstenite::Message};
@@ -17,7 +18,8 @@ use anyhow::Result;
 use serde::{Deserialize, Serialize};
 
 use super::auth::KucoinAuth;
-use super::order_state::{SharedOrderManager, Fill, Side as OrderSide};
+use crate::jitter::jitter_draw;
+use super::order_state::{SharedOrderManager, Fill, Liquidity, Side as OrderSide};
 
 /// Token response from /api/v1/bullet-private
 #[derive(Debug, Deserialize)]
@@ -74,6 +76,23 @@ pub enum ConnectionState {
     Reconnecting,
 }
 
+/// Order lifecycle event from the private feed
+#[derive(Debug, Clone)]
+pub struct OrderUpdate {
+    pub order_id: String,
+    pub client_oid: String,
+    pub kind: OrderUpdateKind,
+    pub received_at: Instant,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum OrderUpdateKind {
+    Open,
+    Match { trade_id: String, side: OrderSide, price: f64, size: f64, liquidity: Liquidity },
+    Done,
+    Canceled,
+}
+
 /// Reconnection stats
 pub struct ReconnectStats {
     pub attempts: u32,
@@ -91,6 +110,41 @@ pub struct KucoinPrivateWs {
     symbol: String,
     state: Arc<RwLock<ConnectionState>>,
     reconnect_stats: Arc<RwLock<ReconnectStats>>,
+    events: broadcast::Sender<OrderUpdate>,
+    backoff: Backoff,
+}
+
+/// Reconnect delay schedule: `base` doubling per failed attempt up to `max`,
+/// each delay scaled by up to ±`jitter` (fraction) and still capped at `max`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Backoff {
+    pub base: Duration,
+    pub max: Duration,
+    pub jitter: f64,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self { base: Duration::from_secs(1), max: Duration::from_secs(30), jitter: 0.0 }
+    }
+}
+
+impl Backoff {
+    /// Delay before retry `attempt` (0 = first); `u` is a uniform draw in [-1, 1)
+    pub fn delay(&self, attempt: u32, u: f64) -> Duration {
+        let max = self.max.as_secs_f64();
+        let raw = (self.base.as_secs_f64() * 2f64.powi(attempt.min(30) as i32)).min(max);
+        let jittered = raw * (1.0 + self.jitter.clamp(0.0, 1.0) * u.clamp(-1.0, 1.0));
+        Duration::from_secs_f64(jittered.clamp(0.0, max))
+    }
+}
+
+/// Log and sleep out the next backoff step
+async fn wait_before_retry(backoff: &Backoff, attempt: &mut u32) {
+    let delay = backoff.delay(*attempt, jitter_draw());
+    *attempt += 1;
+    info!("[KUCOIN-WS] Reconnect attempt {} in {}ms", attempt, delay.as_millis());
+    tokio::time::sleep(delay).await;
 }
 
 impl KucoinPrivateWs {
@@ -114,9 +168,22 @@ impl KucoinPrivateWs {
                 last_disconnect: None,
                 total_disconnects: 0,
             })),
+            events: broadcast::channel(1024).0,
+            backoff: Backoff::default(),
         }
     }
 
+    /// Override the default 1s..30s un-jittered reconnect backoff
+    pub fn with_backoff(mut self, backoff: Backoff) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Subscribe to order lifecycle events
+    pub fn subscribe(&self) -> broadcast::Receiver<OrderUpdate> {
+        self.events.subscribe()
+    }
+
     /// Get connection state
     pub fn state(&self) -> Arc<RwLock<ConnectionState>> {
         self.state.clone()
@@ -133,6 +200,7 @@ impl KucoinPrivateWs {
         
         let (ts, sign, pass, _key) = self.auth.sign("POST", endpoint, "");
         
+        let _permit = crate::rest_limit().acquire().await;
         let resp = client
             .post(format!("{}{}", self.rest_url, endpoint))
             .header("KC-API-KEY", self.auth.api_key())
@@ -163,15 +231,15 @@ impl KucoinPrivateWs {
     pub async fn start(&self) -> Result<tokio::task::JoinHandle<()>> {
         let auth = self.auth.clone();
         let rest_url = self.rest_url.clone();
-        let ws_url_override = self.ws_url.clone();
         let order_manager = self.order_manager.clone();
         let symbol = self.symbol.clone();
         let state = self.state.clone();
         let reconnect_stats = self.reconnect_stats.clone();
+        let events = self.events.clone();
+        let backoff = self.backoff;
 
         let handle = tokio::spawn(async move {
-            let mut backoff_secs = 1u64;
-            const MAX_BACKOFF: u64 = 30;
+            let mut attempt = 0u32;
 
             loop {
                 // Update state
@@ -186,19 +254,14 @@ impl KucoinPrivateWs {
                     Err(e) => {
                         error!("[KUCOIN-WS] Failed to get token: {}", e);
                         *state.write().await = ConnectionState::Reconnecting;
-                        tokio::time::sleep(Duration::from_secs(backoff_secs)).await;
-                        backoff_secs = (backoff_secs * 2).min(MAX_BACKOFF);
+                        wait_before_retry(&backoff, &mut attempt).await;
                         continue;
                     }
                 };
 
                 
-                // Use colo endpoint if configured, otherwise use dynamic endpoint
-                let ws_endpoint = if false && ws_url_override.contains("kucoin.com") { // NEVER use config URL for private WS - always use token URL
-                    ws_url_override.clone()
-                } else {
-                    endpoint
-                };
+                // NEVER use config URL for private WS - always use token URL
+                let ws_endpoint = endpoint;
                 
                 let connect_url = format!("{}?token={}", ws_endpoint, token);
                 info!("[KUCOIN-WS] Connecting to {}", ws_endpoint);
@@ -210,8 +273,7 @@ impl KucoinPrivateWs {
                     Err(e) => {
                         error!("[KUCOIN-WS] Connection failed: {}", e);
                         *state.write().await = ConnectionState::Reconnecting;
-                        tokio::time::sleep(Duration::from_secs(backoff_secs)).await;
-                        backoff_secs = (backoff_secs * 2).min(MAX_BACKOFF);
+                        wait_before_retry(&backoff, &mut attempt).await;
                         continue;
                     }
                 };
@@ -223,7 +285,7 @@ impl KucoinPrivateWs {
                     stats.last_connect = Some(Instant::now());
                     stats.attempts = 0;
                 }
-                backoff_secs = 1; // Reset backoff
+                attempt = 0; // Reset backoff
 
                 info!("[KUCOIN-WS] Connected! Subscribing to order updates...");
 
@@ -284,7 +346,7 @@ impl KucoinPrivateWs {
                                             "message" => {
                                                 // Process order update
                                                 if let Some(data) = ws_msg.data {
-                                                    Self::process_order_message(&order_manager, &data).await;
+                                                    Self::process_order_message(&order_manager, &events, &data).await;
                                                 }
                                             }
                                             _ => {
@@ -323,9 +385,7 @@ impl KucoinPrivateWs {
                 }
                 *state.write().await = ConnectionState::Reconnecting;
                 
-                info!("[KUCOIN-WS] Reconnecting in {}s...", backoff_secs);
-                tokio::time::sleep(Duration::from_secs(backoff_secs)).await;
-                backoff_secs = (backoff_secs * 2).min(MAX_BACKOFF);
+                wait_before_retry(&backoff, &mut attempt).await;
             }
         });
 
@@ -339,6 +399,7 @@ impl KucoinPrivateWs {
         
         let (ts, sign, pass, _key) = auth.sign("POST", endpoint, "");
         
+        let _permit = crate::rest_limit().acquire().await;
         let resp = client
             .post(format!("{}{}", rest_url, endpoint))
             .header("KC-API-KEY", auth.api_key())
@@ -366,9 +427,23 @@ impl KucoinPrivateWs {
     }
 
     /// Process order update message
-    async fn process_order_message(order_manager: &SharedOrderManager, data: &serde_json::Value) {
+    async fn process_order_message(
+        order_manager: &SharedOrderManager,
+        events: &broadcast::Sender<OrderUpdate>,
+        data: &serde_json::Value,
+    ) {
         // Parse order update
         let order_id = data.get("orderId").and_then(|v| v.as_str()).unwrap_or("");
+        let client_oid = data.get("clientOid").and_then(|v| v.as_str()).unwrap_or("");
+        let publish = |kind: OrderUpdateKind| {
+            // No subscribers is fine
+            let _ = events.send(OrderUpdate {
+                order_id: order_id.to_string(),
+                client_oid: client_oid.to_string(),
+                kind,
+                received_at: Instant::now(),
+            });
+        };
         let msg_type = data.get("type").and_then(|v| v.as_str()).unwrap_or("");
         let _status = data.get("status").and_then(|v| v.as_str()).unwrap_or("");
         
@@ -391,19 +466,23 @@ impl KucoinPrivateWs {
                 if price > 0.0 && size > 0.0 {
                     let side = if side_str == "buy" { OrderSide::Buy } else { OrderSide::Sell };
                     
+                    let liquidity = Liquidity::parse(data.get("liquidity").and_then(|v| v.as_str()).unwrap_or(""));
                     let fill = Fill {
                         order_id: order_id.to_string(),
                         trade_id: trade_id.to_string(),
                         side,
                         price,
                         size,
-                        fee: 0.0, // Calculate from maker_fee
+                        liquidity,
+                        fee: 0.0, // Not on the WS match - OrderManager applies its rates
                         fee_currency: "USDT".to_string(),
                         timestamp: 0,
                     };
 
                     let mut mgr = order_manager.write().await;
                     mgr.on_fill(&fill);
+                    drop(mgr);
+                    publish(OrderUpdateKind::Match { trade_id: trade_id.to_string(), side, price, size, liquidity });
                     
                     info!("[FILL] {} {} @ ${:.4} (order {})",
                         side_str.to_uppercase(), size, price, order_id);
@@ -413,12 +492,33 @@ impl KucoinPrivateWs {
                 // Order cancelled or completed
                 let mut mgr = order_manager.write().await;
                 mgr.on_cancel(order_id);
+                drop(mgr);
+                publish(if msg_type == "canceled" { OrderUpdateKind::Canceled } else { OrderUpdateKind::Done });
                 debug!("[ORDER] {} - {}", order_id, msg_type);
             }
             "open" => {
+                publish(OrderUpdateKind::Open);
                 debug!("[ORDER] {} opened", order_id);
             }
             _ => {}
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_caps_and_jitters() {
+        let b = Backoff { base: Duration::from_millis(500), max: Duration::from_secs(4), jitter: 0.2 };
+        assert_eq!(b.delay(0, 0.0), Duration::from_millis(500));
+        assert_eq!(b.delay(2, 0.0), Duration::from_secs(2));
+        assert_eq!(b.delay(10, 0.0), Duration::from_secs(4));
+        assert_eq!(b.delay(0, -1.0), Duration::from_millis(400));
+        assert_eq!(b.delay(1, 0.5), Duration::from_millis(1100));
+        // Jitter never pushes past the ceiling
+        assert_eq!(b.delay(10, 0.99), Duration::from_secs(4));
+        assert_eq!(Backoff::default().delay(40, 0.0), Duration::from_secs(30));
+    }
+}
@@ -16,12 +16,14 @@ pub mod order_state;
 pub mod order_state_machine;
 pub mod kucoin_ws_private;
 pub mod ws_order_client;
+pub mod ws_public;
 
 pub use auth::KucoinAuth;
-pub use rest::KucoinRestClient;
+pub use rest::{KucoinRestClient, RestLimiter};
 pub use types::*;
-pub use order_state::{Side as OrderSide, new_shared_order_manager};
-pub use kucoin_ws_private::{KucoinPrivateWs, ConnectionState};
+pub use order_state::{Side as OrderSide, Liquidity, new_shared_order_manager};
+pub use kucoin_ws_private::{KucoinPrivateWs, ConnectionState, OrderUpdate, OrderUpdateKind};
+pub use ws_public::KucoinPublicWs;
 
 pub mod ws_order_client_v2;
 pub use ws_order_client_v2::{WsOrderClientV2, WsOrderRequest, WsCancelRequest};
@@ -3,6 +3,9 @@
 
 use std::time::Instant;
 
+/// Default per-side depth kept - the deepest snapshot KuCoin pushes
+pub const DEFAULT_MAX_LEVELS: usize = 50;
+
 /// Order book side
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum BookSide {
@@ -29,6 +32,8 @@ pub struct OrderBook {
     pub last_update: Instant,
     /// Sequence number for detecting gaps
     pub sequence: u64,
+    /// Levels kept per side; the worst are dropped after every update
+    max_levels: usize,
 }
 
 impl OrderBook {
@@ -39,9 +44,26 @@ impl OrderBook {
             asks: Vec::with_capacity(50),
             last_update: Instant::now(),
             sequence: 0,
+            max_levels: DEFAULT_MAX_LEVELS,
         }
     }
 
+    /// Cap each side at `max_levels` (at least 1)
+    pub fn with_max_levels(mut self, max_levels: usize) -> Self {
+        self.max_levels = max_levels.max(1);
+        self.truncate();
+        self
+    }
+
+    pub fn max_levels(&self) -> usize {
+        self.max_levels
+    }
+
+    fn truncate(&mut self) {
+        self.bids.truncate(self.max_levels);
+        self.asks.truncate(self.max_levels);
+    }
+
     /// Update from L2 snapshot (50 levels)
     pub fn update_snapshot(&mut self, bids: Vec<(f64, f64)>, asks: Vec<(f64, f64)>, seq: u64) {
         self.bids = bids.into_iter()
@@ -51,6 +73,7 @@ impl OrderBook {
             .map(|(p, s)| PriceLevel { price: p, size: s })
             .collect();
         self.sequence = seq;
+        self.truncate();
         self.last_update = Instant::now();
     }
 
@@ -75,6 +98,7 @@ impl OrderBook {
                     BookSide::Bid => levels.sort_by(|a, b| b.price.partial_cmp(&a.price).unwrap()),
                     BookSide::Ask => levels.sort_by(|a, b| a.price.partial_cmp(&b.price).unwrap()),
                 }
+                levels.truncate(self.max_levels);
             }
         }
         self.last_update = Instant::now();
@@ -154,6 +178,40 @@ impl OrderBook {
         }
     }
 
+    /// Average fill price for a market order of `notional` (quote currency)
+    /// walking this side. `None` if the visible book can't absorb it.
+    pub fn vwap_for_notional(&self, side: BookSide, notional: f64) -> Option<f64> {
+        let levels = match side {
+            BookSide::Bid => &self.bids,
+            BookSide::Ask => &self.asks,
+        };
+        let (mut quote, mut base) = (0.0, 0.0);
+        for l in levels {
+            let take = (notional - quote).min(l.price * l.size);
+            quote += take;
+            base += take / l.price;
+            if quote >= notional - 1e-9 {
+                return (base > 0.0).then(|| quote / base);
+            }
+        }
+        None
+    }
+
+    /// Worst price gap (bps) between this book's top `n` levels and a
+    /// reference snapshot, level by level on both sides. Sizes churn too fast
+    /// to compare across fetch latency. `None` if either side is short.
+    pub fn divergence_bps(&self, bids: &[(f64, f64)], asks: &[(f64, f64)], n: usize) -> Option<f64> {
+        let side = |ours: &[PriceLevel], theirs: &[(f64, f64)]| -> Option<f64> {
+            if ours.len() < n || theirs.len() < n {
+                return None;
+            }
+            Some(ours.iter().zip(theirs).take(n)
+                .map(|(a, (p, _))| (a.price - p).abs() / p * 10000.0)
+                .fold(0.0, f64::max))
+        };
+        Some(side(&self.bids, bids)?.max(side(&self.asks, asks)?))
+    }
+
     // === Queue Position Estimation ===
 
     /// Get total volume at a specific price level
@@ -216,6 +274,63 @@ mod tests {
         assert_eq!(book.ask_depth(3), 7.5);
     }
 
+    #[test]
+    fn test_vwap_for_notional() {
+        let mut book = OrderBook::new("SOL-USDT".to_string());
+        book.update_snapshot(
+            vec![(100.0, 10.0), (99.0, 10.0)],
+            vec![(101.0, 10.0), (102.0, 10.0)],
+            1,
+        );
+        // Inside the first level
+        assert_eq!(book.vwap_for_notional(BookSide::Ask, 505.0), Some(101.0));
+        // 1000 @100 + 990 @99 -> 1990 / 20
+        let v = book.vwap_for_notional(BookSide::Bid, 1990.0).unwrap();
+        assert!((v - 99.5).abs() < 1e-9);
+        assert_eq!(book.vwap_for_notional(BookSide::Bid, 5000.0), None);
+    }
+
+    #[test]
+    fn test_divergence_against_snapshot() {
+        let mut book = OrderBook::new("SOL-USDT".to_string());
+        book.update_snapshot(
+            vec![(100.0, 1.0), (99.0, 1.0)],
+            vec![(101.0, 1.0), (102.0, 1.0)],
+            1,
+        );
+        let asks = [(101.0, 5.0), (102.0, 5.0)];
+        assert_eq!(book.divergence_bps(&[(100.0, 3.0), (99.0, 3.0)], &asks, 2), Some(0.0));
+        // Second bid level off by one whole dollar
+        let d = book.divergence_bps(&[(100.0, 1.0), (98.0, 1.0)], &asks, 2).unwrap();
+        assert!((d - 102.04).abs() < 0.01);
+        assert_eq!(book.divergence_bps(&[(100.0, 1.0)], &asks, 2), None);
+    }
+
+    #[test]
+    fn test_depth_capped_after_many_inserts() {
+        let mut book = OrderBook::new("SOL-USDT".to_string()).with_max_levels(10);
+        for i in 0..1000 {
+            let off = (i % 400) as f64 * 0.01;
+            book.apply_delta(BookSide::Bid, 100.0 - off, 1.0);
+            book.apply_delta(BookSide::Ask, 101.0 + off, 1.0);
+            assert!(book.bids().len() <= 10 && book.asks().len() <= 10);
+        }
+        // The best levels survive
+        assert_eq!(book.best_bid(), Some(100.0));
+        assert_eq!(book.best_ask(), Some(101.0));
+        assert!((book.bids()[9].price - 99.91).abs() < 1e-9);
+        // A better price pushes the worst out
+        book.apply_delta(BookSide::Ask, 100.5, 2.0);
+        assert_eq!(book.asks().len(), 10);
+        assert_eq!(book.best_ask(), Some(100.5));
+        assert!((book.asks()[9].price - 101.08).abs() < 1e-9);
+
+        let many: Vec<(f64, f64)> = (0..60).map(|i| (100.0 - i as f64 * 0.01, 1.0)).collect();
+        book.update_snapshot(many.clone(), many, 2);
+        assert_eq!(book.bids().len(), 10);
+        assert_eq!(OrderBook::new("SOL-USDT".to_string()).max_levels(), DEFAULT_MAX_LEVELS);
+    }
+
     #[test]
     fn test_depth_imbalance() {
         let mut book = OrderBook::new("BTC-USDT".to_string());
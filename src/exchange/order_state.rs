@@ -42,6 +42,21 @@ pub struct TrackedOrder {
     pub last_update: Instant,
 }
 
+/// Which side of the match our order was on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Liquidity {
+    #[default]
+    Maker,
+    Taker,
+}
+
+impl Liquidity {
+    /// KuCoin `liquidity` field ("maker" / "taker"); anything else counts as maker
+    pub fn parse(s: &str) -> Self {
+        if s.eq_ignore_ascii_case("taker") { Liquidity::Taker } else { Liquidity::Maker }
+    }
+}
+
 /// Fill event
 #[derive(Debug, Clone)]
 pub struct Fill {
@@ -50,6 +65,8 @@ pub struct Fill {
     pub side: Side,
     pub price: f64,
     pub size: f64,
+    pub liquidity: Liquidity,
+    /// Exchange-reported fee (0 = not reported, use configured rates)
     pub fee: f64,
     pub fee_currency: String,
     pub timestamp: u64,
@@ -94,6 +111,8 @@ pub struct OrderManager {
     volume_quote: f64,
     /// Maker fee (negative = rebate)
     maker_fee: f64,
+    /// Taker fee rate (positive)
+    taker_fee: f64,
     /// Last fill timestamp
     last_fill_time: Option<Instant>,
     
@@ -109,7 +128,7 @@ pub struct OrderManager {
 }
 
 impl OrderManager {
-    pub fn new(maker_fee: f64) -> Self {
+    pub fn new(maker_fee: f64, taker_fee: f64) -> Self {
         Self {
             active_orders: HashMap::new(),
             client_to_order: HashMap::new(),
@@ -122,6 +141,7 @@ impl OrderManager {
             volume_base: 0.0,
             volume_quote: 0.0,
             maker_fee,
+            taker_fee,
             last_fill_time: None,
             long_entries: VecDeque::new(),
             short_entries: VecDeque::new(),
@@ -159,11 +179,16 @@ impl OrderManager {
         self.volume_quote += fill.price * fill.size;
         self.last_fill_time = Some(Instant::now());
         
-        // Track fees from fill (taker fees are positive, maker rebates are negative)
-        if fill.fee > 0.0 {
-            self.taker_fees += fill.fee;
-        } else {
-            self.total_rebates += -fill.fee;
+        // Maker fills earn the rebate, taker fills pay the fee - exchange-reported
+        // fee if present, else the configured rate
+        let notional = fill.price * fill.size;
+        match fill.liquidity {
+            Liquidity::Maker => {
+                self.total_rebates += if fill.fee != 0.0 { -fill.fee } else { -self.maker_fee * notional };
+            }
+            Liquidity::Taker => {
+                self.taker_fees += if fill.fee != 0.0 { fill.fee.abs() } else { self.taker_fee * notional };
+            }
         }
 
         // Update position with FIFO tracking
@@ -374,6 +399,39 @@ impl OrderManager {
 /// Thread-safe wrapper
 pub type SharedOrderManager = Arc<RwLock<OrderManager>>;
 
-pub fn new_shared_order_manager(maker_fee: f64) -> SharedOrderManager {
-    Arc::new(RwLock::new(OrderManager::new(maker_fee)))
+pub fn new_shared_order_manager(maker_fee: f64, taker_fee: f64) -> SharedOrderManager {
+    Arc::new(RwLock::new(OrderManager::new(maker_fee, taker_fee)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fill(trade_id: &str, side: Side, liquidity: Liquidity, fee: f64) -> Fill {
+        Fill {
+            order_id: "o1".into(), trade_id: trade_id.into(), side, price: 100.0, size: 1.0,
+            liquidity, fee, fee_currency: "USDT".into(), timestamp: 0,
+        }
+    }
+
+    #[test]
+    fn test_mixed_maker_taker_fees() {
+        // 1bps maker rebate, 10bps taker fee
+        let mut mgr = OrderManager::new(-0.0001, 0.001);
+        mgr.on_fill(&fill("t1", Side::Buy, Liquidity::Maker, 0.0));
+        mgr.on_fill(&fill("t2", Side::Sell, Liquidity::Taker, 0.0));
+        // Exchange-reported fee wins over the configured rate
+        mgr.on_fill(&fill("t3", Side::Buy, Liquidity::Taker, 0.25));
+        mgr.on_fill(&fill("t4", Side::Sell, Liquidity::Maker, -0.02));
+
+        assert!((mgr.rebates() - 0.03).abs() < 1e-12);
+        assert!((mgr.taker_fees() - 0.35).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_liquidity_parse() {
+        assert_eq!(Liquidity::parse("taker"), Liquidity::Taker);
+        assert_eq!(Liquidity::parse("maker"), Liquidity::Maker);
+        assert_eq!(Liquidity::parse(""), Liquidity::Maker);
+    }
 }
@@ -5,7 +5,10 @@ use reqwest::Client;
 use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
 use serde::Deserialize;
 use tracing::debug;
-use std::time::Instant;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::{Semaphore, SemaphorePermit};
 
 use super::auth::KucoinAuth;
 use super::types::*;
@@ -65,12 +68,109 @@ pub struct FillsData {
     pub items: Vec<FillInfo>,
 }
 
+/// Most orders KuCoin takes in one `/api/v1/hf/orders/multi` call
+pub const BATCH_MAX_ORDERS: usize = 5;
+
+/// Per-order outcomes of a batch place, in request order
+fn parse_batch_response(body: &str) -> Result<Vec<Result<String>>> {
+    #[derive(Deserialize)]
+    struct BatchResponse {
+        code: String,
+        data: Option<Vec<BatchItem>>,
+        msg: Option<String>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct BatchItem {
+        order_id: Option<String>,
+        #[serde(default)]
+        success: bool,
+        fail_msg: Option<String>,
+    }
+
+    let parsed: BatchResponse = serde_json::from_str(body)?;
+    if parsed.code != "200000" {
+        anyhow::bail!("Batch failed: {} - {}", parsed.code, parsed.msg.unwrap_or_default());
+    }
+    Ok(parsed.data.unwrap_or_default().into_iter().map(|item| match item.order_id {
+        Some(id) if item.success => Ok(id),
+        _ => Err(anyhow::anyhow!("{}", item.fail_msg.unwrap_or_else(|| "rejected".to_string()))),
+    }).collect())
+}
+
+// ==================== CONCURRENCY LIMIT ====================
+
+/// Caps REST requests in flight across every call site, so a burst (recon,
+/// fill polls, cancel fallbacks, batch placement) queues instead of
+/// exhausting the connection pool or tripping account-wide rate limits
+#[derive(Debug)]
+pub struct RestLimiter {
+    permits: Semaphore,
+    limit: usize,
+    waits: AtomicU64,
+    wait_us: AtomicU64,
+    max_wait_us: AtomicU64,
+}
+
+/// Calls that queued for a slot since the last `take_waits`
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct RestWaits {
+    pub count: u64,
+    pub total: Duration,
+    pub max: Duration,
+}
+
+impl RestLimiter {
+    /// `limit` requests at once; 0 = unlimited
+    pub fn new(limit: usize) -> Self {
+        let permits = if limit == 0 { Semaphore::MAX_PERMITS } else { limit };
+        Self {
+            permits: Semaphore::new(permits),
+            limit,
+            waits: AtomicU64::new(0),
+            wait_us: AtomicU64::new(0),
+            max_wait_us: AtomicU64::new(0),
+        }
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Hold the returned permit until the response body has been read
+    pub async fn acquire(&self) -> SemaphorePermit<'_> {
+        if let Ok(permit) = self.permits.try_acquire() {
+            return permit;
+        }
+        let start = Instant::now();
+        // Never closed
+        let permit = self.permits.acquire().await.expect("REST limiter closed");
+        let waited = start.elapsed().as_micros() as u64;
+        self.waits.fetch_add(1, Ordering::Relaxed);
+        self.wait_us.fetch_add(waited, Ordering::Relaxed);
+        self.max_wait_us.fetch_max(waited, Ordering::Relaxed);
+        debug!("[REST] Waited {:.1}ms for one of {} REST slots", waited as f64 / 1000.0, self.limit);
+        permit
+    }
+
+    /// Waits since the last call, resetting the counters
+    pub fn take_waits(&self) -> RestWaits {
+        RestWaits {
+            count: self.waits.swap(0, Ordering::Relaxed),
+            total: Duration::from_micros(self.wait_us.swap(0, Ordering::Relaxed)),
+            max: Duration::from_micros(self.max_wait_us.swap(0, Ordering::Relaxed)),
+        }
+    }
+}
+
 // ==================== REST CLIENT ====================
 
 pub struct KucoinRestClient {
     client: Client,
     base_url: String,
     auth: KucoinAuth,
+    limiter: Option<Arc<RestLimiter>>,
 }
 
 impl KucoinRestClient {
@@ -82,7 +182,20 @@ impl KucoinRestClient {
             .pool_max_idle_per_host(10)
             .tcp_nodelay(true)  // Disable Nagle's algorithm for lower latency
             .build()?;
-        Ok(Self { client, base_url: endpoints.rest_url.clone(), auth })
+        Ok(Self { client, base_url: endpoints.rest_url.clone(), auth, limiter: None })
+    }
+
+    /// Share a concurrency cap with the other REST call sites
+    pub fn with_limiter(mut self, limiter: Arc<RestLimiter>) -> Self {
+        self.limiter = Some(limiter);
+        self
+    }
+
+    async fn permit(&self) -> Option<SemaphorePermit<'_>> {
+        match &self.limiter {
+            Some(l) => Some(l.acquire().await),
+            None => None,
+        }
     }
 
     fn build_headers(&self, method: &str, endpoint: &str, body: &str) -> Result<HeaderMap> {
@@ -106,8 +219,9 @@ impl KucoinRestClient {
         
         debug!("[REST] POST {} | {}", endpoint, body);
         
+        let _permit = self.permit().await;
         let resp = self.client
-            .post(&format!("{}{}", self.base_url, endpoint))
+            .post(format!("{}{}", self.base_url, endpoint))
             .headers(headers)
             .body(body)
             .send()
@@ -142,14 +256,43 @@ impl KucoinRestClient {
         anyhow::bail!("Order failed: {} - {}", parsed.code, parsed.msg.unwrap_or_default())
     }
 
+    /// Place several orders via the HF batch endpoint, `BATCH_MAX_ORDERS`
+    /// per call. One result per order, in order; a failed call fails its chunk.
+    pub async fn place_orders_batch(&self, orders: &[OrderRequest]) -> Result<Vec<Result<String>>> {
+        let endpoint = "/api/v1/hf/orders/multi";
+        let mut results = Vec::with_capacity(orders.len());
+        for chunk in orders.chunks(BATCH_MAX_ORDERS) {
+            let body = serde_json::json!({ "orderList": chunk }).to_string();
+            let headers = self.build_headers("POST", endpoint, &body)?;
+            debug!("[REST] POST {} | {} orders", endpoint, chunk.len());
+
+            let _permit = self.permit().await;
+            let resp = self.client
+                .post(format!("{}{}", self.base_url, endpoint))
+                .headers(headers)
+                .body(body)
+                .send()
+                .await?;
+            let body = resp.text().await?;
+            debug!("[REST] Response: {}", body);
+
+            let mut chunk_results = parse_batch_response(&body)?;
+            // Missing entries count as failed rather than shifting later ones
+            chunk_results.resize_with(chunk.len(), || Err(anyhow::anyhow!("no result in batch response")));
+            results.extend(chunk_results);
+        }
+        Ok(results)
+    }
+
     /// Cancel an order by ID
     pub async fn cancel_order(&self, order_id: &str) -> Result<()> {
         let _start = Instant::now();
         let endpoint = format!("/api/v1/hf/orders/{}", order_id);
         let headers = self.build_headers("DELETE", &endpoint, "")?;
         
+        let _permit = self.permit().await;
         let resp = self.client
-            .delete(&format!("{}{}", self.base_url, endpoint))
+            .delete(format!("{}{}", self.base_url, endpoint))
             .headers(headers)
             .send()
             .await?;
@@ -160,13 +303,54 @@ impl KucoinRestClient {
         Ok(())
     }
 
+    /// Modify an HF order's price and size. KuCoin cancels it and places the
+    /// replacement, whose new orderId is returned.
+    pub async fn modify_order(&self, symbol: &str, order_id: &str, new_price: &str, new_size: &str) -> Result<String> {
+        let endpoint = "/api/v1/hf/orders/alter";
+        let body = serde_json::json!({
+            "symbol": symbol, "orderId": order_id, "newPrice": new_price, "newSize": new_size,
+        }).to_string();
+        let headers = self.build_headers("POST", endpoint, &body)?;
+        debug!("[REST] POST {} | {}", endpoint, body);
+
+        let _permit = self.permit().await;
+        let resp = self.client
+            .post(format!("{}{}", self.base_url, endpoint))
+            .headers(headers)
+            .body(body)
+            .send()
+            .await?;
+        let body = resp.text().await?;
+        debug!("[REST] Response: {}", body);
+
+        #[derive(Deserialize)]
+        struct AlterResponse {
+            code: String,
+            data: Option<AlterData>,
+            msg: Option<String>,
+        }
+
+        #[derive(Deserialize)]
+        struct AlterData {
+            #[serde(rename = "newOrderId")]
+            new_order_id: String,
+        }
+
+        let parsed: AlterResponse = serde_json::from_str(&body)?;
+        match parsed.data {
+            Some(data) if parsed.code == "200000" => Ok(data.new_order_id),
+            _ => anyhow::bail!("Modify failed: {} - {}", parsed.code, parsed.msg.unwrap_or_default()),
+        }
+    }
+
     /// Cancel an order by clientOid (different KuCoin endpoint)
     pub async fn cancel_by_client_oid(&self, symbol: &str, client_oid: &str) -> Result<()> {
         let endpoint = format!("/api/v1/hf/orders/client-order/{}?symbol={}", client_oid, symbol);
         let headers = self.build_headers("DELETE", &endpoint, "")?;
         
+        let _permit = self.permit().await;
         let resp = self.client
-            .delete(&format!("{}{}", self.base_url, endpoint))
+            .delete(format!("{}{}", self.base_url, endpoint))
             .headers(headers)
             .send()
             .await?;
@@ -194,8 +378,9 @@ impl KucoinRestClient {
         let endpoint = format!("/api/v1/hf/orders/{}", order_id);
         let headers = self.build_headers("GET", &endpoint, "")?;
         
+        let _permit = self.permit().await;
         let resp = self.client
-            .get(&format!("{}{}", self.base_url, endpoint))
+            .get(format!("{}{}", self.base_url, endpoint))
             .headers(headers)
             .send()
             .await?;
@@ -215,8 +400,9 @@ impl KucoinRestClient {
         let endpoint = format!("/api/v1/fills?symbol={}&pageSize={}", symbol, limit);
         let headers = self.build_headers("GET", &endpoint, "")?;
         
+        let _permit = self.permit().await;
         let resp = self.client
-            .get(&format!("{}{}", self.base_url, endpoint))
+            .get(format!("{}{}", self.base_url, endpoint))
             .headers(headers)
             .send()
             .await?;
@@ -236,8 +422,9 @@ impl KucoinRestClient {
         let endpoint = format!("/api/v1/hf/orders?symbol={}", symbol);
         let headers = self.build_headers("DELETE", &endpoint, "")?;
         
+        let _permit = self.permit().await;
         let resp = self.client
-            .delete(&format!("{}{}", self.base_url, endpoint))
+            .delete(format!("{}{}", self.base_url, endpoint))
             .headers(headers)
             .send()
             .await?;
@@ -259,8 +446,9 @@ impl KucoinRestClient {
         let endpoint = format!("/api/v1/hf/orders?symbol={}&status=active", symbol);
         let headers = self.build_headers("GET", &endpoint, "")?;
         
+        let _permit = self.permit().await;
         let resp = self.client
-            .get(&format!("{}{}", self.base_url, endpoint))
+            .get(format!("{}{}", self.base_url, endpoint))
             .headers(headers)
             .send()
             .await?;
@@ -287,3 +475,49 @@ impl KucoinRestClient {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_limiter_queues_past_limit_and_counts_waits() {
+        let limiter = Arc::new(RestLimiter::new(2));
+        let (a, b) = (limiter.acquire().await, limiter.acquire().await);
+        assert_eq!(limiter.take_waits(), RestWaits::default());
+
+        let queued = tokio::spawn({
+            let limiter = limiter.clone();
+            async move { drop(limiter.acquire().await) }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!queued.is_finished());
+        drop(a);
+        queued.await.unwrap();
+        drop(b);
+
+        let waits = limiter.take_waits();
+        assert_eq!(waits.count, 1);
+        assert!(waits.max >= Duration::from_millis(15) && waits.total == waits.max);
+        assert_eq!(limiter.take_waits().count, 0);
+        // 0 = no cap
+        let open = RestLimiter::new(0);
+        let _held: Vec<_> = futures_util::future::join_all((0..64).map(|_| open.acquire())).await;
+        assert_eq!(open.take_waits().count, 0);
+    }
+
+    #[test]
+    fn test_parse_batch_response() {
+        let body = r#"{"code":"200000","data":[
+            {"orderId":"6710d8336afcdb0007319c27","clientOid":"b10_1","success":true},
+            {"clientOid":"b20_2","success":false,"failMsg":"Balance insufficient!"}
+        ]}"#;
+        let r = parse_batch_response(body).unwrap();
+        assert_eq!(r.len(), 2);
+        assert_eq!(r[0].as_ref().unwrap(), "6710d8336afcdb0007319c27");
+        assert_eq!(r[1].as_ref().unwrap_err().to_string(), "Balance insufficient!");
+
+        let err = parse_batch_response(r#"{"code":"400100","msg":"Too many orders"}"#).unwrap_err();
+        assert!(err.to_string().contains("Too many orders"));
+    }
+}
@@ -62,6 +62,27 @@ impl Side {
     }
 }
 
+impl std::str::FromStr for Side {
+    type Err = anyhow::Error;
+
+    /// KuCoin sends lowercase; any casing is accepted
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        if s.eq_ignore_ascii_case("buy") {
+            Ok(Side::Buy)
+        } else if s.eq_ignore_ascii_case("sell") {
+            Ok(Side::Sell)
+        } else {
+            anyhow::bail!("unknown side {:?}", s)
+        }
+    }
+}
+
+impl std::fmt::Display for Side {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 // ======================= ORDER TYPE =======================
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -73,18 +94,26 @@ pub enum OrderType {
 
 // ======================= TIME IN FORCE =======================
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[allow(clippy::upper_case_acronyms)]
 pub enum TimeInForce {
+    #[default]
     GTC,  // Good Till Cancelled
     GTT,  // Good Till Time
     IOC,  // Immediate or Cancel
     FOK,  // Fill or Kill
 }
 
-impl Default for TimeInForce {
-    fn default() -> Self {
-        TimeInForce::GTC
-    }
+// ======================= SELF-TRADE PREVENTION =======================
+
+/// KuCoin `stp` modes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum SelfTradePrevention {
+    CN,  // Cancel Newest
+    CO,  // Cancel Oldest
+    CB,  // Cancel Both
+    DC,  // Decrease and Cancel
 }
 
 // ======================= ORDER =======================
@@ -108,6 +137,11 @@ pub struct OrderRequest {
     pub hidden: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub iceberg: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stp: Option<SelfTradePrevention>,
+    /// Seconds until auto-cancel; GTT only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cancel_after: Option<u64>,
 }
 
 impl OrderRequest {
@@ -131,6 +165,8 @@ impl OrderRequest {
             post_only: Some(post_only),
             hidden: None,
             iceberg: None,
+            stp: None,
+            cancel_after: None,
         }
     }
 }
@@ -248,3 +284,35 @@ pub struct WsInstance {
     pub ping_interval: u64,
     pub ping_timeout: u64,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stp_serialization() {
+        let mut order = OrderRequest::limit("c1".into(), "SOL-USDT".into(), Side::Buy, 187.5, 0.1, true);
+        assert!(serde_json::to_value(&order).unwrap().get("stp").is_none());
+
+        for (mode, wire) in [
+            (SelfTradePrevention::CN, "CN"),
+            (SelfTradePrevention::CO, "CO"),
+            (SelfTradePrevention::CB, "CB"),
+            (SelfTradePrevention::DC, "DC"),
+        ] {
+            order.stp = Some(mode);
+            assert_eq!(serde_json::to_value(&order).unwrap()["stp"], wire);
+        }
+    }
+
+    #[test]
+    fn test_side_parse_casing() {
+        assert_eq!("buy".parse::<Side>().unwrap(), Side::Buy);
+        assert_eq!("Sell".parse::<Side>().unwrap(), Side::Sell);
+        assert_eq!("BUY".parse::<Side>().unwrap(), Side::Buy);
+        assert!("".parse::<Side>().is_err());
+        assert!("bid".parse::<Side>().is_err());
+        assert_eq!(Side::Sell.to_string(), "sell");
+        assert_eq!(Side::Buy.to_string().parse::<Side>().unwrap(), Side::Buy);
+    }
+}
@@ -232,13 +232,25 @@ impl LatencyTracker {
     pub fn count(&self) -> usize { self.samples.len() }
     pub fn total(&self) -> u64 { self.total_requests }
 
+    /// Whichever stats are available plus the sample count, so a tracker
+    /// with few (or no) samples still shows up in the log
+    pub fn summary(&self) -> String {
+        let stats = [
+            ("p50", self.percentile(50.0)),
+            ("p99", self.percentile(99.0)),
+            ("p99.9", self.percentile(99.9)),
+            ("mean", self.mean()),
+        ];
+        let mut parts: Vec<String> = stats.iter()
+            .filter_map(|(name, v)| v.map(|d| format!("{}: {:?}", name, d)))
+            .collect();
+        parts.push(format!("samples: {}", self.samples.len()));
+        parts.push(format!("total: {}", self.total_requests));
+        parts.join(" | ")
+    }
+
     pub fn log_summary(&self) {
-        if let (Some(p50), Some(p99), Some(p999), Some(mean)) = 
-            (self.percentile(50.0), self.percentile(99.0), self.percentile(99.9), self.mean()) 
-        {
-            info!("[LATENCY] p50: {:?} | p99: {:?} | p99.9: {:?} | mean: {:?} | total: {}", 
-                p50, p99, p999, mean, self.total_requests);
-        }
+        info!("[LATENCY] {}", self.summary());
     }
 
     pub fn reset(&mut self) {
@@ -522,3 +534,16 @@ impl WsOrderClient {
         in_flight.remove(client_oid);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_with_few_samples() {
+        let mut t = LatencyTracker::new(10);
+        assert_eq!(t.summary(), "samples: 0 | total: 0");
+        t.record(Duration::from_millis(7));
+        assert_eq!(t.summary(), "p50: 7ms | p99: 7ms | p99.9: 7ms | mean: 7ms | samples: 1 | total: 1");
+    }
+}
@@ -5,15 +5,16 @@ use anyhow::{Result, anyhow};
 use futures_util::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, AtomicU64, AtomicU32, Ordering};
 use std::time::{Duration, Instant};
-use tokio::sync::{mpsc, RwLock, oneshot, Mutex};
+use tokio::sync::{broadcast, mpsc, RwLock, oneshot, Mutex};
 use tokio_tungstenite::{connect_async, tungstenite::Message};
 use tracing::{info, warn, error, debug};
 
 use super::KucoinAuth;
+use super::types::SelfTradePrevention;
 
 /// WebSocket Order Request
 #[derive(Debug, Clone, Serialize)]
@@ -30,6 +31,11 @@ pub struct WsOrderRequest {
     pub time_in_force: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub post_only: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stp: Option<SelfTradePrevention>,
+    /// Seconds until the exchange cancels the order; only with `time_in_force: "GTT"`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cancel_after: Option<u64>,
 }
 
 /// WebSocket Order Response
@@ -43,6 +49,34 @@ pub struct WsOrderResponse {
     pub msg: Option<String>,
 }
 
+impl WsOrderResponse {
+    /// Post-only reject because the price would have taken liquidity
+    pub fn is_post_only_cross(&self) -> bool {
+        !self.success && is_post_only_cross_msg(self.msg.as_deref().unwrap_or(""))
+    }
+
+    /// Reject because the symbol isn't open for trading
+    pub fn is_symbol_not_trading(&self) -> bool {
+        !self.success && is_symbol_not_trading(self.code.as_deref())
+    }
+}
+
+/// KuCoin reject text for a post-only order that would have taken liquidity
+pub fn is_post_only_cross_msg(msg: &str) -> bool {
+    let msg = msg.to_ascii_lowercase();
+    msg.contains("post") && (msg.contains("taker") || msg.contains("cross") || msg.contains("immediately"))
+}
+
+/// KuCoin code for an order on a symbol that doesn't exist (delisted)
+pub const SYMBOL_NOT_EXIST_CODE: &str = "900001";
+
+/// KuCoin reject code for a symbol that is gone (delisted). Matched on the
+/// code alone - reject text is free-form, and a suspension shows up as
+/// `enableTrading: false` on the symbol check instead.
+pub fn is_symbol_not_trading(code: Option<&str>) -> bool {
+    code == Some(SYMBOL_NOT_EXIST_CODE)
+}
+
 /// Cancel Request
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -54,6 +88,9 @@ pub struct WsCancelRequest {
     pub client_oid: Option<String>,
 }
 
+/// Samples kept for latency percentiles
+const LATENCY_SAMPLES: usize = 256;
+
 /// Latency tracker
 #[derive(Debug)]
 pub struct LatencyStats {
@@ -62,11 +99,13 @@ pub struct LatencyStats {
     pub min_us: u64,
     pub max_us: u64,
     pub last_us: u64,
+    /// Most recent samples, for percentiles
+    recent: VecDeque<u64>,
 }
 
 impl LatencyStats {
     pub fn new() -> Self {
-        Self { count: 0, total_us: 0, min_us: u64::MAX, max_us: 0, last_us: 0 }
+        Self { count: 0, total_us: 0, min_us: u64::MAX, max_us: 0, last_us: 0, recent: VecDeque::new() }
     }
     
     pub fn record(&mut self, duration: Duration) {
@@ -76,10 +115,25 @@ impl LatencyStats {
         self.min_us = self.min_us.min(us);
         self.max_us = self.max_us.max(us);
         self.last_us = us;
+        if self.recent.len() >= LATENCY_SAMPLES {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(us);
+    }
+    
+    /// Percentile (`q` in 0..=1) over the recent samples; None before any
+    pub fn percentile(&self, q: f64) -> Option<Duration> {
+        if self.recent.is_empty() {
+            return None;
+        }
+        let mut sorted: Vec<u64> = self.recent.iter().copied().collect();
+        sorted.sort_unstable();
+        let i = (q.clamp(0.0, 1.0) * (sorted.len() - 1) as f64).round() as usize;
+        Some(Duration::from_micros(sorted[i]))
     }
     
     pub fn avg_us(&self) -> u64 {
-        if self.count > 0 { self.total_us / self.count } else { 0 }
+        self.total_us.checked_div(self.count).unwrap_or(0)
     }
     
     pub fn summary(&self) -> String {
@@ -95,26 +149,269 @@ impl LatencyStats {
     }
 }
 
+/// Operation a pending request belongs to - selects its latency bucket
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PendingOp {
+    Place,
+    Cancel,
+}
+
 /// Pending request awaiting response
 struct PendingRequest {
     tx: oneshot::Sender<WsOrderResponse>,
     sent_at: Instant,
+    op: PendingOp,
+}
+
+/// Error frame with no pending request to hand it to (no `id`, or a ping
+/// echo) - rate limits, auth and session failures
+#[derive(Debug, Clone, PartialEq)]
+struct ErrorFrame {
+    code: String,
+    msg: String,
+}
+
+impl ErrorFrame {
+    /// A non-success `code` (string or number), or `"type": "error"`
+    fn parse(resp: &serde_json::Value) -> Option<Self> {
+        let code = match resp.get("code") {
+            Some(serde_json::Value::String(c)) => c.clone(),
+            Some(serde_json::Value::Number(n)) => n.to_string(),
+            _ if resp.get("type").and_then(|v| v.as_str()) == Some("error") => String::new(),
+            _ => return None,
+        };
+        if code == "200000" || code == "200" {
+            return None;
+        }
+        let msg = resp.get("msg").or_else(|| resp.get("data")).and_then(|v| v.as_str()).unwrap_or_default();
+        Some(Self { code, msg: msg.to_string() })
+    }
+
+    /// Auth or session failure: nothing more will go through on this
+    /// connection, so it is dropped and the monitor reconnects
+    fn is_session_failure(&self) -> bool {
+        let msg = self.msg.to_ascii_lowercase();
+        matches!(self.code.as_str(), "401" | "400001" | "400002" | "400003" | "400004" | "400005")
+            || ["session", "unauthorized", "token"].iter().any(|k| msg.contains(k))
+    }
+}
+
+/// Result of routing one incoming frame
+#[derive(Debug, PartialEq)]
+enum Dispatch {
+    /// No `id`, or a ping echo
+    Ignored,
+    /// Error frame nobody is waiting on
+    Error(ErrorFrame),
+    /// Has an `id` nobody is waiting for (timed out or foreign) - dropped
+    Unmatched(String),
+    /// Oneshot resolved
+    Resolved { op: PendingOp, latency: Duration },
+}
+
+/// WS order-entry op set. KuCoin's WS order API is HF only; the op prefix
+/// picks the account an order lands in, so it has to match the account the
+/// bot polls balances and orders from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderEntry {
+    #[default]
+    Spot,
+    Margin,
+    IsolatedMargin,
+}
+
+impl OrderEntry {
+    pub fn place_op(&self) -> &'static str {
+        match self {
+            OrderEntry::Spot => "spot.order",
+            OrderEntry::Margin | OrderEntry::IsolatedMargin => "margin.order",
+        }
+    }
+
+    pub fn cancel_op(&self) -> &'static str {
+        match self {
+            OrderEntry::Spot => "spot.cancel",
+            OrderEntry::Margin | OrderEntry::IsolatedMargin => "margin.cancel",
+        }
+    }
+
+    /// None where there is no WS cancel-all; callers fall back to REST
+    pub fn cancel_all_op(&self) -> Option<&'static str> {
+        match self {
+            OrderEntry::Spot => Some("spot.cancelAll"),
+            OrderEntry::Margin | OrderEntry::IsolatedMargin => None,
+        }
+    }
+
+    /// Full place message
+    fn place_msg(&self, id: &str, req: WsOrderRequest) -> serde_json::Value {
+        let mut args = place_args(req);
+        match self {
+            OrderEntry::Spot => {}
+            OrderEntry::Margin => args["isIsolated"] = json!(false),
+            OrderEntry::IsolatedMargin => args["isIsolated"] = json!(true),
+        }
+        json!({ "id": id, "op": self.place_op(), "args": args })
+    }
+
+    /// Full cancel message, leaving out absent ids rather than sending nulls
+    fn cancel_msg(&self, id: &str, req: WsCancelRequest) -> serde_json::Value {
+        let mut args = serde_json::Map::new();
+        args.insert("symbol".to_string(), serde_json::Value::String(req.symbol));
+        if let Some(oid) = req.order_id {
+            args.insert("orderId".to_string(), serde_json::Value::String(oid));
+        }
+        if let Some(coid) = req.client_oid {
+            args.insert("clientOid".to_string(), serde_json::Value::String(coid));
+        }
+        json!({ "id": id, "op": self.cancel_op(), "args": serde_json::Value::Object(args) })
+    }
+}
+
+/// `args` for a place (`spot.order` / `margin.order`) request
+fn place_args(req: WsOrderRequest) -> serde_json::Value {
+    let mut args = json!({
+        "symbol": req.symbol,
+        "side": req.side,
+        "price": req.price,
+        "size": req.size,
+        "clientOid": req.client_oid,
+        "type": req.order_type,
+        "timeInForce": req.time_in_force.unwrap_or_else(|| "GTC".to_string()),
+        // Maker quotes default to post-only; an IOC flatten must be able to take
+        "postOnly": req.post_only.unwrap_or(true)
+    });
+    if let Some(stp) = req.stp {
+        args["stp"] = json!(stp);
+    }
+    if let Some(secs) = req.cancel_after {
+        args["cancelAfter"] = json!(secs);
+    }
+    args
+}
+
+fn parse_order_response(resp: &serde_json::Value) -> WsOrderResponse {
+    WsOrderResponse {
+        order_id: resp.get("data").and_then(|d| d.get("orderId")).and_then(|v| v.as_str()).map(String::from),
+        client_oid: resp.get("data").and_then(|d| d.get("clientOid")).and_then(|v| v.as_str()).map(String::from),
+        success: resp.get("code").and_then(|v| v.as_str()) == Some("200000"),
+        code: resp.get("code").and_then(|v| v.as_str()).map(String::from),
+        msg: resp.get("msg").and_then(|v| v.as_str()).map(String::from),
+    }
+}
+
+/// Correlate an incoming frame with its pending request by `id` and resolve it
+fn dispatch_response(pending: &mut HashMap<String, PendingRequest>, resp: &serde_json::Value) -> Dispatch {
+    let id = resp.get("id").and_then(|v| v.as_str()).filter(|id| *id != "ping");
+    let Some(id) = id else { return ErrorFrame::parse(resp).map_or(Dispatch::Ignored, Dispatch::Error) };
+    match pending.remove(id) {
+        Some(req) => {
+            let latency = req.sent_at.elapsed();
+            // Receiver may have given up (timeout) - nothing to do
+            let _ = req.tx.send(parse_order_response(resp));
+            Dispatch::Resolved { op: req.op, latency }
+        }
+        None => Dispatch::Unmatched(id.to_string()),
+    }
+}
+
+/// Record a resolved request's latency in its bucket
+async fn record_dispatch(
+    dispatch: Dispatch,
+    place_latency: &RwLock<LatencyStats>,
+    cancel_latency: &RwLock<LatencyStats>,
+) {
+    match dispatch {
+        Dispatch::Resolved { op, latency } => {
+            match op {
+                PendingOp::Place => place_latency.write().await.record(latency),
+                PendingOp::Cancel => cancel_latency.write().await.record(latency),
+            }
+            debug!("[WS-ORDER] {:?} response in {:.2}ms", op, latency.as_secs_f64() * 1000.0);
+        }
+        Dispatch::Unmatched(id) => debug!("[WS-ORDER] Dropping response for unknown id {}", id),
+        Dispatch::Error(e) => warn!("[WS-ORDER] Error frame code={} msg={}", e.code, e.msg),
+        Dispatch::Ignored => {}
+    }
 }
 
-/// Reconnection stats
+/// Reconnection counters, updated by the connection tasks
 #[derive(Debug, Default)]
+struct ConnectionCounters {
+    total_connects: u32,
+    total_disconnects: u32,
+    consecutive_failures: u32,
+    /// Reconnect dials made by the monitor
+    connect_attempts: u32,
+    last_connect: Option<Instant>,
+    last_disconnect: Option<Instant>,
+}
+
+impl ConnectionCounters {
+    fn snapshot(&self, now: Instant) -> ReconnectStats {
+        ReconnectStats {
+            total_connects: self.total_connects,
+            total_disconnects: self.total_disconnects,
+            consecutive_failures: self.consecutive_failures,
+            since_last_connect: self.last_connect.map(|t| now.duration_since(t)),
+            since_last_disconnect: self.last_disconnect.map(|t| now.duration_since(t)),
+        }
+    }
+}
+
+/// Reconnection stats snapshot from `get_reconnect_stats`
+#[derive(Debug, Clone, PartialEq)]
 pub struct ReconnectStats {
     pub total_connects: u32,
     pub total_disconnects: u32,
     pub consecutive_failures: u32,
-    pub last_connect: Option<Instant>,
-    pub last_disconnect: Option<Instant>,
+    pub since_last_connect: Option<Duration>,
+    pub since_last_disconnect: Option<Duration>,
+}
+
+impl ReconnectStats {
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "connects": self.total_connects,
+            "disconnects": self.total_disconnects,
+            "consecutive_failures": self.consecutive_failures,
+            "since_last_connect_s": self.since_last_connect.map(|d| d.as_secs_f64()),
+            "since_last_disconnect_s": self.since_last_disconnect.map(|d| d.as_secs_f64()),
+        })
+    }
+}
+
+/// Connection lifecycle, broadcast to `events()` subscribers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionEvent {
+    /// Session is up (initial connect or reconnect)
+    Connected,
+    /// Connection loop ended; the monitor will retry
+    Disconnected,
+    /// Monitor gave up after `max_reconnect_attempts`
+    ReconnectExhausted,
 }
 
 /// Internal connection state
 struct ConnectionState {
     msg_tx: Option<mpsc::Sender<String>>,
     handle: Option<tokio::task::JoinHandle<()>>,
+    /// Reconnect monitor, once started
+    monitor: Option<tokio::task::AbortHandle>,
+}
+
+impl ConnectionState {
+    /// Abort the monitor and the live connection task
+    fn abort_tasks(&mut self) {
+        if let Some(monitor) = self.monitor.take() {
+            monitor.abort();
+        }
+        if let Some(handle) = self.handle.take() {
+            handle.abort();
+        }
+        self.msg_tx = None;
+    }
 }
 
 /// WebSocket Order Client with Auto-Reconnection
@@ -131,13 +428,22 @@ pub struct WsOrderClientV2 {
     conn_state: Arc<Mutex<ConnectionState>>,
     
     // Reconnection control
-    reconnect_stats: Arc<RwLock<ReconnectStats>>,
+    reconnect_stats: Arc<RwLock<ConnectionCounters>>,
     should_reconnect: Arc<AtomicBool>,
     max_reconnect_attempts: u32,
+    /// How often the monitor checks the connection
+    reconnect_check: Duration,
+    /// First reconnect delay, doubled per consecutive failure (capped at 30x)
+    backoff_base: Duration,
+    events_tx: broadcast::Sender<ConnectionEvent>,
     
     // Latency tracking
     place_latency: Arc<RwLock<LatencyStats>>,
     cancel_latency: Arc<RwLock<LatencyStats>>,
+    
+    entry: OrderEntry,
+    /// Set at shutdown: placements are refused, cancels still go
+    entry_closed: AtomicBool,
 }
 
 impl WsOrderClientV2 {
@@ -155,15 +461,31 @@ impl WsOrderClientV2 {
             conn_state: Arc::new(Mutex::new(ConnectionState {
                 msg_tx: None,
                 handle: None,
+                monitor: None,
             })),
-            reconnect_stats: Arc::new(RwLock::new(ReconnectStats::default())),
+            reconnect_stats: Arc::new(RwLock::new(ConnectionCounters::default())),
             should_reconnect: Arc::new(AtomicBool::new(true)),
             max_reconnect_attempts: 10,
+            reconnect_check: Duration::from_secs(2),
+            backoff_base: Duration::from_secs(1),
+            events_tx: broadcast::channel(16).0,
             place_latency: Arc::new(RwLock::new(LatencyStats::new())),
             cancel_latency: Arc::new(RwLock::new(LatencyStats::new())),
+            entry: OrderEntry::Spot,
+            entry_closed: AtomicBool::new(false),
         }
     }
     
+    /// Send orders to another account's op set (default spot)
+    pub fn with_order_entry(mut self, entry: OrderEntry) -> Self {
+        self.entry = entry;
+        self
+    }
+    
+    pub fn order_entry(&self) -> OrderEntry {
+        self.entry
+    }
+    
     /// Get private WS token from REST API
     async fn get_ws_token(&self) -> Result<(String, String)> {
         let endpoint = "/api/v1/bullet-private";
@@ -172,6 +494,7 @@ impl WsOrderClientV2 {
         let (timestamp, signature, passphrase, version) = self.auth.sign("POST", endpoint, "");
         
         let client = reqwest::Client::new();
+        let _permit = crate::rest_limit().acquire().await;
         let resp = client.post(format!("{}{}", self.rest_url, endpoint))
             .header("KC-API-KEY", self.auth.api_key())
             .header("KC-API-SIGN", &signature)
@@ -294,8 +617,10 @@ impl WsOrderClientV2 {
         }
         
         info!("[WS-ORDER] ✓ Connected to WS order endpoint");
+        let _ = self.events_tx.send(ConnectionEvent::Connected);
         
         let connected = self.connected.clone();
+        let events_tx = self.events_tx.clone();
         let pending = self.pending.clone();
         let auth_clone = self.auth.clone();
         let place_latency = self.place_latency.clone();
@@ -371,36 +696,12 @@ impl WsOrderClientV2 {
                                         }
                                     }
                                     
-                                    if let Some(id) = resp.get("id").and_then(|v| v.as_str()) {
-                                        // Skip ping/pong responses
-                                        if id == "ping" {
-                                            continue;
-                                        }
-                                        
-                                        let mut pending_guard = pending.write().await;
-                                        if let Some(req) = pending_guard.remove(id) {
-                                            let latency = req.sent_at.elapsed();
-                                            
-                                            // Track latency based on request type
-                                            if id.contains("place") {
-                                                let mut stats = place_latency.write().await;
-                                                stats.record(latency);
-                                            } else if id.contains("cancel") {
-                                                let mut stats = cancel_latency.write().await;
-                                                stats.record(latency);
-                                            }
-                                            
-                                            let order_resp = WsOrderResponse {
-                                                order_id: resp.get("data").and_then(|d| d.get("orderId")).and_then(|v| v.as_str()).map(String::from),
-                                                client_oid: resp.get("data").and_then(|d| d.get("clientOid")).and_then(|v| v.as_str()).map(String::from),
-                                                success: resp.get("code").and_then(|v| v.as_str()) == Some("200000"),
-                                                code: resp.get("code").and_then(|v| v.as_str()).map(String::from),
-                                                msg: resp.get("msg").and_then(|v| v.as_str()).map(String::from),
-                                            };
-                                            let _ = req.tx.send(order_resp);
-                                            
-                                            debug!("[WS-ORDER] Response in {:.2}ms", latency.as_secs_f64() * 1000.0);
-                                        }
+                                    let dispatch = dispatch_response(&mut *pending.write().await, &resp);
+                                    let session_lost = matches!(&dispatch, Dispatch::Error(e) if e.is_session_failure());
+                                    record_dispatch(dispatch, &place_latency, &cancel_latency).await;
+                                    if session_lost {
+                                        warn!("[WS-ORDER] Session rejected, dropping the connection to reconnect");
+                                        break;
                                     }
                                 }
                             }
@@ -445,6 +746,7 @@ impl WsOrderClientV2 {
                 stats.total_disconnects += 1;
                 stats.last_disconnect = Some(Instant::now());
             }
+            let _ = events_tx.send(ConnectionEvent::Disconnected);
             warn!("[WS-ORDER] Connection loop ended");
         });
         
@@ -461,13 +763,18 @@ impl WsOrderClientV2 {
     pub async fn start(&self) -> Result<tokio::task::JoinHandle<()>> {
         // Initial connection
         self.connect_internal().await?;
-        
-        // Spawn reconnection monitor
+        Ok(self.spawn_monitor().await)
+    }
+    
+    /// Spawn the reconnection monitor; its abort handle is kept for shutdown
+    async fn spawn_monitor(&self) -> tokio::task::JoinHandle<()> {
         let connected = self.connected.clone();
         let reconnecting = self.reconnecting.clone();
         let should_reconnect = self.should_reconnect.clone();
         let reconnect_stats = self.reconnect_stats.clone();
         let max_attempts = self.max_reconnect_attempts;
+        let (check_every, backoff_base) = (self.reconnect_check, self.backoff_base);
+        let events_tx = self.events_tx.clone();
         
         // Clone self for reconnection
         let auth = self.auth.clone();
@@ -480,7 +787,7 @@ impl WsOrderClientV2 {
         let cancel_latency = self.cancel_latency.clone();
         
         let handle = tokio::spawn(async move {
-            let mut check_interval = tokio::time::interval(Duration::from_secs(2));
+            let mut check_interval = tokio::time::interval(check_every);
             
             loop {
                 check_interval.tick().await;
@@ -499,16 +806,18 @@ impl WsOrderClientV2 {
                     
                     if failures >= max_attempts {
                         error!("[WS-ORDER] Max reconnection attempts ({}) reached, giving up", max_attempts);
+                        let _ = events_tx.send(ConnectionEvent::ReconnectExhausted);
                         break;
                     }
                     
-                    // Calculate backoff delay: min(1s * 2^failures, 30s)
-                    let delay_secs = (1u64 << failures.min(5)).min(30);
-                    info!("[WS-ORDER] Reconnecting in {}s (attempt {}/{})", delay_secs, failures + 1, max_attempts);
+                    // Calculate backoff delay: min(base * 2^failures, 30 * base)
+                    let delay = backoff_base * (1u32 << failures.min(5)).min(30);
+                    info!("[WS-ORDER] Reconnecting in {:.1}s (attempt {}/{})", delay.as_secs_f64(), failures + 1, max_attempts);
                     
-                    tokio::time::sleep(Duration::from_secs(delay_secs)).await;
+                    tokio::time::sleep(delay).await;
                     
                     reconnecting.store(true, Ordering::SeqCst);
+                    reconnect_stats.write().await.connect_attempts += 1;
                     
                     // Use URL-based auth for reconnection (same as initial connect)
                     let (timestamp, signature, passphrase) = auth.sign_ws_url();
@@ -546,6 +855,7 @@ impl WsOrderClientV2 {
                                     }
                                     
                                     info!("[WS-ORDER] ✓ Reconnected successfully");
+                                    let _ = events_tx.send(ConnectionEvent::Connected);
                                     
                                     // Spawn new connection handler
                                     let connected_inner = connected.clone();
@@ -553,6 +863,7 @@ impl WsOrderClientV2 {
                                     let place_latency_inner = place_latency.clone();
                                     let cancel_latency_inner = cancel_latency.clone();
                                     let reconnect_stats_inner = reconnect_stats.clone();
+                                    let events_inner = events_tx.clone();
                                     
                                     let handle = tokio::spawn(async move {
                                         // Send initial ping IMMEDIATELY to beat KuCoin's 3s timeout
@@ -583,31 +894,8 @@ impl WsOrderClientV2 {
                                                     match msg {
                                                         Ok(Message::Text(text)) => {
                                                             if let Ok(resp) = serde_json::from_str::<serde_json::Value>(&text) {
-                                                                if let Some(id) = resp.get("id").and_then(|v| v.as_str()) {
-                                                                    if id == "ping" { continue; }
-                                                                    
-                                                                    let mut pending_guard = pending_inner.write().await;
-                                                                    if let Some(req) = pending_guard.remove(id) {
-                                                                        let latency = req.sent_at.elapsed();
-                                                                        
-                                                                        if id.contains("place") {
-                                                                            let mut stats = place_latency_inner.write().await;
-                                                                            stats.record(latency);
-                                                                        } else if id.contains("cancel") {
-                                                                            let mut stats = cancel_latency_inner.write().await;
-                                                                            stats.record(latency);
-                                                                        }
-                                                                        
-                                                                        let order_resp = WsOrderResponse {
-                                                                            order_id: resp.get("data").and_then(|d| d.get("orderId")).and_then(|v| v.as_str()).map(String::from),
-                                                                            client_oid: resp.get("data").and_then(|d| d.get("clientOid")).and_then(|v| v.as_str()).map(String::from),
-                                                                            success: resp.get("code").and_then(|v| v.as_str()) == Some("200000"),
-                                                                            code: resp.get("code").and_then(|v| v.as_str()).map(String::from),
-                                                                            msg: resp.get("msg").and_then(|v| v.as_str()).map(String::from),
-                                                                        };
-                                                                        let _ = req.tx.send(order_resp);
-                                                                    }
-                                                                }
+                                                                let dispatch = dispatch_response(&mut *pending_inner.write().await, &resp);
+                                                                record_dispatch(dispatch, &place_latency_inner, &cancel_latency_inner).await;
                                                             }
                                                         }
                                                         Ok(Message::Ping(data)) => { let _ = write.send(Message::Pong(data)).await; }
@@ -633,6 +921,7 @@ impl WsOrderClientV2 {
                                             stats.total_disconnects += 1;
                                             stats.last_disconnect = Some(Instant::now());
                                         }
+                                        let _ = events_inner.send(ConnectionEvent::Disconnected);
                                         warn!("[WS-ORDER] Connection loop ended");
                                     });
                                     
@@ -653,7 +942,8 @@ impl WsOrderClientV2 {
             }
         });
         
-        Ok(handle)
+        self.conn_state.lock().await.monitor = Some(handle.abort_handle());
+        handle
     }
     
     /// Legacy connect method - now calls start()
@@ -666,6 +956,20 @@ impl WsOrderClientV2 {
         self.should_reconnect.store(false, Ordering::SeqCst);
     }
     
+    /// Refuse every placement from now on; cancels are unaffected
+    pub fn close_entry(&self) {
+        self.entry_closed.store(true, Ordering::SeqCst);
+    }
+    
+    /// Stop reconnecting and abort the monitor and connection tasks; pending
+    /// requests see their response channel close
+    pub async fn shutdown(&self) {
+        self.stop();
+        self.conn_state.lock().await.abort_tasks();
+        self.connected.store(false, Ordering::SeqCst);
+        info!("[WS-ORDER] Shut down");
+    }
+    
     pub fn is_connected(&self) -> bool {
         self.connected.load(Ordering::SeqCst)
     }
@@ -674,51 +978,34 @@ impl WsOrderClientV2 {
         self.reconnecting.load(Ordering::SeqCst)
     }
     
+    /// Subscribe to connection lifecycle events
+    pub fn events(&self) -> broadcast::Receiver<ConnectionEvent> {
+        self.events_tx.subscribe()
+    }
+    
     /// Get sender for orders
     async fn get_sender(&self) -> Result<mpsc::Sender<String>> {
         let state = self.conn_state.lock().await;
         state.msg_tx.clone().ok_or_else(|| anyhow!("Not connected"))
     }
     
+    /// Messages queued for the socket writer; None when not connected
+    pub async fn send_queue_len(&self) -> Option<usize> {
+        let state = self.conn_state.lock().await;
+        state.msg_tx.as_ref().map(|tx| tx.max_capacity() - tx.capacity())
+    }
+
     /// Place order via WebSocket
     pub async fn place_order(&self, req: WsOrderRequest) -> Result<WsOrderResponse> {
+        if self.entry_closed.load(Ordering::SeqCst) {
+            return Err(anyhow!("order entry closed, not placing {}", req.client_oid));
+        }
         let tx = self.get_sender().await?;
         
         let id = format!("place_{}", self.next_id());
-        // KuCoin Pro API format for order placement (spot.order for Classic Account)
-        let msg = json!({
-            "id": id,
-            "op": "spot.order",
-            "args": {
-                "symbol": req.symbol,
-                "side": req.side,
-                "price": req.price,
-                "size": req.size,
-                "clientOid": req.client_oid,
-                "type": req.order_type,
-                "timeInForce": req.time_in_force.unwrap_or_else(|| "GTC".to_string()),
-                "postOnly": true
-            }
-        });
+        let msg = self.entry.place_msg(&id, req);
         
-        let (resp_tx, resp_rx) = oneshot::channel();
-        {
-            let mut pending = self.pending.write().await;
-            pending.insert(id.clone(), PendingRequest { tx: resp_tx, sent_at: Instant::now() });
-        }
-        
-        tx.send(msg.to_string()).await?;
-        
-        // Wait for response with timeout
-        match tokio::time::timeout(Duration::from_secs(5), resp_rx).await {
-            Ok(Ok(resp)) => Ok(resp),
-            Ok(Err(_)) => Err(anyhow!("Response channel closed")),
-            Err(_) => {
-                let mut pending = self.pending.write().await;
-                pending.remove(&id);
-                Err(anyhow!("Order timeout"))
-            }
-        }
+        self.send_and_wait(tx, id, msg, PendingOp::Place, "Order").await
     }
     
     /// Cancel order via WebSocket
@@ -726,49 +1013,67 @@ impl WsOrderClientV2 {
         let tx = self.get_sender().await?;
         
         let id = format!("cancel_{}", self.next_id());
+        let msg = self.entry.cancel_msg(&id, req);
         
-        // Build args object manually to avoid null values
-        let mut args_obj = serde_json::Map::new();
-        args_obj.insert("symbol".to_string(), serde_json::Value::String(req.symbol));
-        if let Some(oid) = req.order_id {
-            args_obj.insert("orderId".to_string(), serde_json::Value::String(oid));
-        }
-        if let Some(coid) = req.client_oid {
-            args_obj.insert("clientOid".to_string(), serde_json::Value::String(coid));
-        }
+        // DEBUG: Log the actual message being sent
+        info!("[WS-ORDER] Sending cancel: {}", msg.to_string());
         
+        self.send_and_wait(tx, id, msg, PendingOp::Cancel, "Cancel").await
+    }
+    
+    /// Cancel every open order on `symbol` in one WS request (`spot.cancelAll`)
+    pub async fn cancel_all(&self, symbol: &str) -> Result<WsOrderResponse> {
+        let op = self.entry.cancel_all_op()
+            .ok_or_else(|| anyhow!("no WS cancel-all for {:?} order entry", self.entry))?;
+        let tx = self.get_sender().await?;
+        let id = format!("cancelall_{}", self.next_id());
         let msg = json!({
             "id": id,
-            "op": "spot.cancel",
-            "args": serde_json::Value::Object(args_obj)
+            "op": op,
+            "args": { "symbol": symbol },
         });
-        
+        self.send_and_wait(tx, id, msg, PendingOp::Cancel, "Cancel-all").await
+    }
+    
+    /// Register `id` as pending, send, and wait up to 5s for its response
+    async fn send_and_wait(
+        &self,
+        tx: mpsc::Sender<String>,
+        id: String,
+        msg: serde_json::Value,
+        op: PendingOp,
+        what: &str,
+    ) -> Result<WsOrderResponse> {
         let (resp_tx, resp_rx) = oneshot::channel();
         {
             let mut pending = self.pending.write().await;
-            pending.insert(id.clone(), PendingRequest { tx: resp_tx, sent_at: Instant::now() });
+            pending.insert(id.clone(), PendingRequest { tx: resp_tx, sent_at: Instant::now(), op });
         }
         
-        // DEBUG: Log the actual message being sent
-        info!("[WS-ORDER] Sending cancel: {}", msg.to_string());
-        
         tx.send(msg.to_string()).await?;
         
+        // Wait for response with timeout
         match tokio::time::timeout(Duration::from_secs(5), resp_rx).await {
             Ok(Ok(resp)) => Ok(resp),
             Ok(Err(_)) => Err(anyhow!("Response channel closed")),
             Err(_) => {
                 let mut pending = self.pending.write().await;
                 pending.remove(&id);
-                Err(anyhow!("Cancel timeout"))
+                Err(anyhow!("{} timeout", what))
             }
         }
     }
     
     /// Get reconnection statistics
-    pub async fn get_reconnect_stats(&self) -> (u32, u32, u32) {
-        let stats = self.reconnect_stats.read().await;
-        (stats.total_connects, stats.total_disconnects, stats.consecutive_failures)
+    pub async fn get_reconnect_stats(&self) -> ReconnectStats {
+        self.reconnect_stats.read().await.snapshot(Instant::now())
+    }
+    
+    /// Zero the connect/disconnect totals and times. Consecutive failures
+    /// drive the reconnect backoff, so they are left alone.
+    pub async fn reset_reconnect_stats(&self) {
+        let mut stats = self.reconnect_stats.write().await;
+        *stats = ConnectionCounters { consecutive_failures: stats.consecutive_failures, ..Default::default() };
     }
     
     /// Get latency stats
@@ -778,13 +1083,290 @@ impl WsOrderClientV2 {
         (place.summary(), cancel.summary())
     }
     
+    /// Place round-trip percentile (`q` in 0..=1); None before any placement
+    pub async fn place_latency_pct(&self, q: f64) -> Option<Duration> {
+        self.place_latency.read().await.percentile(q)
+    }
+    
     /// Log latency summary
     pub async fn log_latency(&self) {
         let (place, cancel) = self.get_latency_stats().await;
-        let (connects, disconnects, failures) = self.get_reconnect_stats().await;
+        let stats = self.get_reconnect_stats().await;
+        let ago = |d: Option<Duration>| d.map_or("never".to_string(), |d| format!("{}s ago", d.as_secs()));
         info!("[WS-ORDER] PLACE latency: {}", place);
         info!("[WS-ORDER] CANCEL latency: {}", cancel);
-        info!("[WS-ORDER] Connections: {} connects, {} disconnects, {} failures", 
-            connects, disconnects, failures);
+        info!("[WS-ORDER] Connections: {} connects, {} disconnects, {} failures | last connect {}, last disconnect {}", 
+            stats.total_connects, stats.total_disconnects, stats.consecutive_failures,
+            ago(stats.since_last_connect), ago(stats.since_last_disconnect));
+    }
+}
+
+impl Drop for WsOrderClientV2 {
+    fn drop(&mut self) {
+        self.should_reconnect.store(false, Ordering::SeqCst);
+        // The tasks only hold the lock briefly; if one has it right now the
+        // flag above still stops the monitor at its next check
+        if let Ok(mut state) = self.conn_state.try_lock() {
+            state.abort_tasks();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pending_with(id: &str, op: PendingOp) -> (HashMap<String, PendingRequest>, oneshot::Receiver<WsOrderResponse>) {
+        let (tx, rx) = oneshot::channel();
+        let mut pending = HashMap::new();
+        pending.insert(id.to_string(), PendingRequest { tx, sent_at: Instant::now(), op });
+        (pending, rx)
+    }
+
+    fn order(stp: Option<SelfTradePrevention>) -> WsOrderRequest {
+        WsOrderRequest {
+            symbol: "SOL-USDT".into(), side: "buy".into(), price: "187.50".into(), size: "0.13".into(),
+            client_oid: "b10_1".into(), order_type: "limit".into(), time_in_force: None,
+            post_only: Some(true), stp, cancel_after: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_stats_snapshot_and_reset() {
+        let auth = KucoinAuth::new(String::new(), String::new(), String::new(), true);
+        let client = WsOrderClientV2::new(auth, String::new(), String::new());
+        let t0 = Instant::now();
+        {
+            let mut c = client.reconnect_stats.write().await;
+            c.total_connects = 3;
+            c.total_disconnects = 2;
+            c.consecutive_failures = 1;
+            c.last_connect = Some(t0);
+        }
+        let snap = client.reconnect_stats.read().await.snapshot(t0 + Duration::from_secs(5));
+        assert_eq!(snap.since_last_connect, Some(Duration::from_secs(5)));
+        assert_eq!(snap.since_last_disconnect, None);
+        assert_eq!((snap.total_connects, snap.total_disconnects), (3, 2));
+        assert_eq!(snap.to_json()["since_last_disconnect_s"], serde_json::Value::Null);
+
+        client.reset_reconnect_stats().await;
+        let snap = client.get_reconnect_stats().await;
+        assert_eq!((snap.total_connects, snap.total_disconnects, snap.consecutive_failures), (0, 0, 1));
+        assert_eq!(snap.since_last_connect, None);
+    }
+
+    #[tokio::test]
+    async fn test_drop_stops_reconnect_tasks() {
+        let auth = KucoinAuth::new(String::new(), String::new(), String::new(), true);
+        // Nothing listens on port 1: every dial fails fast
+        let mut client = WsOrderClientV2::new(auth, String::new(), "ws://127.0.0.1:1".into());
+        client.reconnect_check = Duration::from_millis(5);
+        client.backoff_base = Duration::from_millis(5);
+        client.max_reconnect_attempts = u32::MAX;
+        let stats = client.reconnect_stats.clone();
+        let attempts = || async { stats.read().await.connect_attempts };
+
+        let monitor = client.spawn_monitor().await;
+        for _ in 0..200 {
+            if attempts().await >= 2 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(attempts().await >= 2);
+
+        drop(client);
+        let joined = tokio::time::timeout(Duration::from_secs(1), monitor).await.expect("monitor still running");
+        assert!(joined.unwrap_err().is_cancelled());
+        let after_drop = attempts().await;
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert_eq!(attempts().await, after_drop);
+    }
+
+    #[tokio::test]
+    async fn test_closed_entry_refuses_placements() {
+        let auth = KucoinAuth::new(String::new(), String::new(), String::new(), true);
+        let client = WsOrderClientV2::new(auth, String::new(), String::new());
+        client.close_entry();
+        let err = client.place_order(order(None)).await.unwrap_err();
+        assert!(err.to_string().contains("order entry closed"));
+    }
+
+    #[test]
+    fn test_place_args_stp_modes() {
+        let args = place_args(order(None));
+        assert!(args.get("stp").is_none());
+        assert_eq!(args["timeInForce"], "GTC");
+        assert!(args.get("cancelAfter").is_none());
+        let gtt = place_args(WsOrderRequest { time_in_force: Some("GTT".into()), cancel_after: Some(120), ..order(None) });
+        assert_eq!((gtt["timeInForce"].as_str(), gtt["cancelAfter"].as_u64()), (Some("GTT"), Some(120)));
+
+        for (mode, wire) in [
+            (SelfTradePrevention::CN, "CN"),
+            (SelfTradePrevention::CO, "CO"),
+            (SelfTradePrevention::CB, "CB"),
+            (SelfTradePrevention::DC, "DC"),
+        ] {
+            assert_eq!(place_args(order(Some(mode)))["stp"], wire);
+        }
+    }
+
+    #[test]
+    fn test_place_args_post_only_follows_request() {
+        assert_eq!(place_args(order(None))["postOnly"], true);
+        assert_eq!(place_args(WsOrderRequest { post_only: None, ..order(None) })["postOnly"], true);
+        let ioc = place_args(WsOrderRequest { time_in_force: Some("IOC".into()), post_only: Some(false), ..order(None) });
+        assert_eq!((ioc["postOnly"].as_bool(), ioc["timeInForce"].as_str()), (Some(false), Some("IOC")));
+    }
+
+    #[test]
+    fn test_order_entry_ops() {
+        let cancel = || WsCancelRequest { symbol: "SOL-USDT".into(), order_id: Some("o1".into()), client_oid: None };
+        let spot = OrderEntry::Spot.place_msg("place_1", order(None));
+        assert_eq!(spot["op"], "spot.order");
+        assert!(spot["args"].get("isIsolated").is_none());
+        assert_eq!(OrderEntry::Spot.cancel_msg("cancel_1", cancel())["op"], "spot.cancel");
+        assert_eq!(OrderEntry::Spot.cancel_all_op(), Some("spot.cancelAll"));
+
+        let cross = OrderEntry::Margin.place_msg("place_2", order(None));
+        assert_eq!(cross["op"], "margin.order");
+        assert_eq!(cross["args"]["isIsolated"], false);
+        let isolated = OrderEntry::IsolatedMargin.place_msg("place_3", order(None));
+        assert_eq!(isolated["args"]["isIsolated"], true);
+        let c = OrderEntry::IsolatedMargin.cancel_msg("cancel_2", cancel());
+        assert_eq!(c["op"], "margin.cancel");
+        assert!(c["args"].get("clientOid").is_none());
+        assert_eq!(OrderEntry::Margin.cancel_all_op(), None);
+    }
+
+    #[test]
+    fn test_post_only_cross_reject() {
+        let rej = |msg: &str| WsOrderResponse { success: false, msg: Some(msg.into()), ..Default::default() };
+        assert!(rej("Post only order would be executed as taker").is_post_only_cross());
+        assert!(!rej("Balance insufficient").is_post_only_cross());
+        assert!(!WsOrderResponse { success: true, ..rej("post only taker") }.is_post_only_cross());
+    }
+
+    #[test]
+    fn test_latency_percentile_over_recent_samples() {
+        let mut l = LatencyStats::new();
+        assert_eq!(l.percentile(0.9), None);
+        for ms in 1..=10 {
+            l.record(Duration::from_millis(ms));
+        }
+        assert_eq!(l.percentile(0.0), Some(Duration::from_millis(1)));
+        assert_eq!(l.percentile(0.9), Some(Duration::from_millis(9)));
+        assert_eq!(l.percentile(1.0), Some(Duration::from_millis(10)));
+        // Old samples roll off; the all-time max stays
+        for _ in 0..LATENCY_SAMPLES {
+            l.record(Duration::from_millis(2));
+        }
+        assert_eq!(l.percentile(1.0), Some(Duration::from_millis(2)));
+        assert_eq!(l.max_us, 10_000);
+    }
+
+    #[test]
+    fn test_symbol_not_trading_reject() {
+        let rej = |code: &str, msg: &str| WsOrderResponse { success: false, code: Some(code.into()), msg: Some(msg.into()), ..Default::default() };
+        assert!(rej("900001", "Symbol does not exist").is_symbol_not_trading());
+        assert!(!WsOrderResponse { success: true, ..rej("900001", "") }.is_symbol_not_trading());
+        // Only the code counts - wording alone doesn't halt the market
+        assert!(!rej("400100", "The symbol trading is suspended").is_symbol_not_trading());
+        assert!(!rej("400100", "Balance insufficient").is_symbol_not_trading());
+        assert!(!rej("200004", "Order not trading yet, delisting check pending").is_symbol_not_trading());
+    }
+
+    #[test]
+    fn test_dispatch_resolves_oneshot() {
+        let (mut pending, mut rx) = pending_with("place_ws_ord_1", PendingOp::Place);
+        let resp = json!({"id": "place_ws_ord_1", "code": "200000", "data": {"orderId": "abc", "clientOid": "b10_7"}});
+
+        assert!(matches!(dispatch_response(&mut pending, &resp), Dispatch::Resolved { op: PendingOp::Place, .. }));
+        assert!(pending.is_empty());
+        let got = rx.try_recv().unwrap();
+        assert!(got.success);
+        assert_eq!(got.order_id.as_deref(), Some("abc"));
+        assert_eq!(got.client_oid.as_deref(), Some("b10_7"));
+    }
+
+    #[test]
+    fn test_dispatch_uses_stored_op_not_id_text() {
+        // An id mentioning neither "place" nor "cancel" still lands in the right bucket
+        let (mut pending, mut rx) = pending_with("amend_ws_ord_2", PendingOp::Cancel);
+        let resp = json!({"id": "amend_ws_ord_2", "code": "400100", "msg": "order not exist"});
+
+        assert!(matches!(dispatch_response(&mut pending, &resp), Dispatch::Resolved { op: PendingOp::Cancel, .. }));
+        let got = rx.try_recv().unwrap();
+        assert!(!got.success);
+        assert_eq!(got.msg.as_deref(), Some("order not exist"));
+    }
+
+    #[test]
+    fn test_dispatch_ignores_ping_and_idless_frames() {
+        let (mut pending, _rx) = pending_with("ping", PendingOp::Place);
+        assert_eq!(dispatch_response(&mut pending, &json!({"id": "ping", "op": "pong"})), Dispatch::Ignored);
+        assert_eq!(dispatch_response(&mut pending, &json!({"sessionId": "s", "data": "welcome"})), Dispatch::Ignored);
+        assert_eq!(pending.len(), 1);
+    }
+
+    #[test]
+    fn test_dispatch_surfaces_uncorrelated_error_frames() {
+        let (mut pending, _rx) = pending_with("place_ws_ord_1", PendingOp::Place);
+        let mut frame = |v| match dispatch_response(&mut pending, &v) {
+            Dispatch::Error(e) => e,
+            other => panic!("expected an error frame, got {:?}", other),
+        };
+        let rate = frame(json!({"code": "429000", "msg": "Too Many Requests"}));
+        assert!(!rate.is_session_failure());
+        assert!(frame(json!({"code": "400003", "msg": "KC-API-KEY not exists"})).is_session_failure());
+        // Numeric code, message in `data`, on a ping echo
+        let expired = frame(json!({"id": "ping", "type": "error", "code": 401, "data": "session expired"}));
+        assert_eq!(expired, ErrorFrame { code: "401".into(), msg: "session expired".into() });
+        assert!(expired.is_session_failure());
+        assert_eq!(dispatch_response(&mut pending, &json!({"code": "200000"})), Dispatch::Ignored);
+        assert_eq!(pending.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_session_error_frame_drops_connection() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (tcp, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(tcp).await.unwrap();
+            // A rate limit alone keeps the session; the auth failure doesn't
+            ws.send(Message::Text(json!({"code": "429000", "msg": "Too Many Requests"}).to_string())).await.unwrap();
+            ws.send(Message::Text(json!({"code": "400003", "msg": "KC-API-KEY not exists"}).to_string())).await.unwrap();
+            // Hold the socket open; only the client can end it
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        });
+
+        let auth = KucoinAuth::new(String::new(), String::new(), String::new(), true);
+        let client = WsOrderClientV2::new(auth, String::new(), format!("ws://{}/", addr));
+        let mut events = client.events();
+        client.connect_internal().await.unwrap();
+        assert_eq!(events.recv().await.unwrap(), ConnectionEvent::Connected);
+        let next = tokio::time::timeout(Duration::from_secs(2), events.recv()).await.expect("connection kept");
+        assert_eq!(next.unwrap(), ConnectionEvent::Disconnected);
+        assert!(!client.is_connected());
+        assert_eq!(client.get_reconnect_stats().await.total_disconnects, 1);
+        server.abort();
+    }
+
+    #[test]
+    fn test_dispatch_drops_unmatched_id() {
+        let (mut pending, _rx) = pending_with("place_ws_ord_1", PendingOp::Place);
+        let resp = json!({"id": "place_ws_ord_9", "code": "200000"});
+        assert_eq!(dispatch_response(&mut pending, &resp), Dispatch::Unmatched("place_ws_ord_9".into()));
+        assert!(pending.contains_key("place_ws_ord_1"));
+    }
+
+    #[test]
+    fn test_dispatch_survives_dropped_receiver() {
+        // Caller timed out between lookup and send
+        let (mut pending, rx) = pending_with("cancel_ws_ord_3", PendingOp::Cancel);
+        drop(rx);
+        let resp = json!({"id": "cancel_ws_ord_3", "code": "200000"});
+        assert!(matches!(dispatch_response(&mut pending, &resp), Dispatch::Resolved { .. }));
     }
 }
@@ -1,85 +1,121 @@
 //! KuCoin Public WebSocket Feed
 //!
-//! Receives real-time orderbook updates for market data.
+//! Receives top-of-book depth for market data. Subscribes to
+//! `/spotMarket/level2Depth5` (or `Depth50`), which pushes a full snapshot
+//! each update, so no sequence tracking is needed. A resync (reconnect for a
+//! fresh stream) can still be forced if the book is caught diverging.
 
 use std::sync::Arc;
-use std::time::Instant;
-use tokio::sync::RwLock;
+use std::time::Duration;
+use tokio::sync::{Notify, RwLock};
 use futures_util::{StreamExt, SinkExt};
 use tokio_tungstenite::{connect_async, tungstenite::Message};
 use tracing::{info, warn, error, debug};
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 
-use super::types::OrderBook;
+use super::order_book::OrderBook;
 
 /// KuCoin public WebSocket orderbook feed
 pub struct KucoinPublicWs {
-    ws_url: String,
+    rest_url: String,
     symbol: String,
+    /// Snapshot depth topic: 5 or 50
+    depth: usize,
     orderbook: Arc<RwLock<OrderBook>>,
+    resync: Arc<Notify>,
 }
 
 impl KucoinPublicWs {
-    pub fn new(ws_url: String, symbol: String) -> Self {
+    pub fn new(rest_url: String, symbol: String) -> Self {
         Self {
-            ws_url,
+            rest_url,
+            orderbook: Arc::new(RwLock::new(OrderBook::new(symbol.clone()))),
             symbol,
-            orderbook: Arc::new(RwLock::new(OrderBook::default())),
+            depth: 5,
+            resync: Arc::new(Notify::new()),
         }
     }
 
+    /// Subscribe to the 50-level snapshot instead of 5
+    pub fn with_depth50(mut self) -> Self {
+        self.depth = 50;
+        self
+    }
+
+    /// Cap the maintained book at `max_levels` per side; call before
+    /// taking `orderbook()` handles
+    pub fn with_max_levels(mut self, max_levels: usize) -> Self {
+        self.orderbook = Arc::new(RwLock::new(OrderBook::new(self.symbol.clone()).with_max_levels(max_levels)));
+        self
+    }
+
     /// Get shared orderbook handle
     pub fn orderbook(&self) -> Arc<RwLock<OrderBook>> {
         self.orderbook.clone()
     }
 
-    /// Start the WebSocket feed
-    pub async fn start(&self, token: &str) -> Result<tokio::task::JoinHandle<()>> {
-        let url = format!(
-            "{}?token={}&connectId={}",
-            self.ws_url,
-            token,
-            uuid::Uuid::new_v4()
-        );
-        
+    /// `notify_one()` drops the current connection and resubscribes
+    pub fn resync_handle(&self) -> Arc<Notify> {
+        self.resync.clone()
+    }
+
+    /// Start the WebSocket feed (fresh public token on every reconnect)
+    pub fn start(&self) -> tokio::task::JoinHandle<()> {
+        let rest_url = self.rest_url.clone();
         let symbol = self.symbol.clone();
+        let depth = self.depth;
         let orderbook = self.orderbook.clone();
+        let resync = self.resync.clone();
 
-        info!("[KC-WS-PUB] Connecting to {} for {}", self.ws_url, symbol);
-
-        let handle = tokio::spawn(async move {
+        tokio::spawn(async move {
             loop {
-                match Self::run_connection(&url, &symbol, &orderbook).await {
-                    Ok(_) => {
-                        warn!("[KC-WS-PUB] Connection closed, reconnecting in 1s...");
-                    }
-                    Err(e) => {
-                        error!("[KC-WS-PUB] Connection error: {:?}, reconnecting in 1s...", e);
+                let result = match Self::get_public_token(&rest_url).await {
+                    Ok((token, endpoint)) => {
+                        let url = format!("{}?token={}&connectId={}", endpoint, token, uuid::Uuid::new_v4());
+                        Self::run_connection(&url, &symbol, depth, &orderbook, &resync).await
                     }
+                    Err(e) => Err(e),
+                };
+                match result {
+                    Ok(_) => warn!("[KC-WS-PUB] Connection closed, reconnecting in 1s..."),
+                    Err(e) => error!("[KC-WS-PUB] Connection error: {:?}, reconnecting in 1s...", e),
                 }
-                tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+                tokio::time::sleep(Duration::from_secs(1)).await;
             }
-        });
+        })
+    }
 
-        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-        Ok(handle)
+    async fn get_public_token(rest_url: &str) -> Result<(String, String)> {
+        let _permit = crate::rest_limit().acquire().await;
+        let body: serde_json::Value = reqwest::Client::new()
+            .post(format!("{}/api/v1/bullet-public", rest_url))
+            .send().await?
+            .json().await?;
+        if body["code"].as_str() != Some("200000") {
+            return Err(anyhow!("bullet-public failed: {}", body));
+        }
+        let token = body["data"]["token"].as_str().ok_or_else(|| anyhow!("no token"))?;
+        let endpoint = body["data"]["instanceServers"][0]["endpoint"].as_str()
+            .ok_or_else(|| anyhow!("no instance server"))?;
+        Ok((token.to_string(), endpoint.to_string()))
     }
 
     async fn run_connection(
         url: &str,
         symbol: &str,
+        depth: usize,
         orderbook: &Arc<RwLock<OrderBook>>,
+        resync: &Notify,
     ) -> Result<()> {
         let (ws_stream, _) = connect_async(url).await?;
         let (mut write, mut read) = ws_stream.split();
 
-        info!("[KC-WS-PUB] Connected successfully");
+        info!("[KC-WS-PUB] Connected, subscribing depth{} for {}", depth, symbol);
 
-        // Subscribe to level2 orderbook
         let sub_msg = serde_json::json!({
             "id": uuid::Uuid::new_v4().to_string(),
             "type": "subscribe",
-            "topic": format!("/market/level2:{}", symbol),
+            "topic": format!("/spotMarket/level2Depth{}:{}", depth, symbol),
             "privateChannel": false,
             "response": true
         });
@@ -87,7 +123,7 @@ impl KucoinPublicWs {
         write.send(Message::Text(sub_msg.to_string())).await?;
 
         // Ping task
-        let ping_interval = tokio::time::interval(tokio::time::Duration::from_secs(30));
+        let ping_interval = tokio::time::interval(Duration::from_secs(18));
         tokio::pin!(ping_interval);
 
         loop {
@@ -101,11 +137,17 @@ impl KucoinPublicWs {
                         break;
                     }
                 }
+                _ = resync.notified() => {
+                    warn!("[KC-WS-PUB] Resync requested, reconnecting");
+                    break;
+                }
                 msg = read.next() => {
                     match msg {
                         Some(Ok(Message::Text(text))) => {
-                            if let Err(e) = Self::handle_message(&text, orderbook).await {
-                                debug!("[KC-WS-PUB] Parse error: {:?}", e);
+                            match parse_depth5(&text) {
+                                Ok(Some((bids, asks, ts))) => orderbook.write().await.update_snapshot(bids, asks, ts),
+                                Ok(None) => {}
+                                Err(e) => debug!("[KC-WS-PUB] Parse error: {:?}", e),
                             }
                         }
                         Some(Ok(Message::Close(_))) => {
@@ -125,80 +167,41 @@ impl KucoinPublicWs {
 
         Ok(())
     }
+}
 
-    async fn handle_message(text: &str, orderbook: &Arc<RwLock<OrderBook>>) -> Result<()> {
-        let v: serde_json::Value = serde_json::from_str(text)?;
-        
-        // Check if it's a data message
-        if v.get("type").and_then(|t| t.as_str()) != Some("message") {
-            return Ok(());
-        }
+type Levels = Vec<(f64, f64)>;
 
-        let data = match v.get("data") {
-            Some(d) => d,
-            None => return Ok(()),
-        };
-
-        // Parse changes
-        let changes = match data.get("changes") {
-            Some(c) => c,
-            None => return Ok(()),
-        };
-
-        let mut ob = orderbook.write().await;
-
-        // Process bid changes
-        if let Some(bids) = changes.get("bids").and_then(|b| b.as_array()) {
-            for bid in bids {
-                if let Some(arr) = bid.as_array() {
-                    if let (Some(price), Some(size)) = (
-                        arr.get(0).and_then(|p| p.as_str()?.parse::<f64>().ok()),
-                        arr.get(1).and_then(|s| s.as_str()?.parse::<f64>().ok()),
-                    ) {
-                        // Update or remove from orderbook (size 0 = remove)
-                        if size == 0.0 {
-                            ob.bids.retain(|(p, _)| (*p - price).abs() > 0.00001);
-                        } else {
-                            // Update existing or insert
-                            if let Some(pos) = ob.bids.iter().position(|(p, _)| (*p - price).abs() < 0.00001) {
-                                ob.bids[pos].1 = size;
-                            } else {
-                                ob.bids.push((price, size));
-                                ob.bids.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
-                            }
-                        }
-                    }
-                }
-            }
-        }
+/// Parse a depth5 push into (bids, asks, timestamp). `None` for non-data frames.
+fn parse_depth5(text: &str) -> Result<Option<(Levels, Levels, u64)>> {
+    let v: serde_json::Value = serde_json::from_str(text)?;
+    if v.get("type").and_then(|t| t.as_str()) != Some("message") {
+        return Ok(None);
+    }
+    let data = &v["data"];
+    let side = |key: &str| -> Levels {
+        data[key].as_array().map(|levels| levels.iter().filter_map(|l| {
+            let price = l.get(0)?.as_str()?.parse::<f64>().ok()?;
+            let size = l.get(1)?.as_str()?.parse::<f64>().ok()?;
+            Some((price, size))
+        }).collect()).unwrap_or_default()
+    };
+    let ts = data["timestamp"].as_u64().unwrap_or(0);
+    Ok(Some((side("bids"), side("asks"), ts)))
+}
 
-        // Process ask changes
-        if let Some(asks) = changes.get("asks").and_then(|a| a.as_array()) {
-            for ask in asks {
-                if let Some(arr) = ask.as_array() {
-                    if let (Some(price), Some(size)) = (
-                        arr.get(0).and_then(|p| p.as_str()?.parse::<f64>().ok()),
-                        arr.get(1).and_then(|s| s.as_str()?.parse::<f64>().ok()),
-                    ) {
-                        if size == 0.0 {
-                            ob.asks.retain(|(p, _)| (*p - price).abs() > 0.00001);
-                        } else {
-                            if let Some(pos) = ob.asks.iter().position(|(p, _)| (*p - price).abs() < 0.00001) {
-                                ob.asks[pos].1 = size;
-                            } else {
-                                ob.asks.push((price, size));
-                                ob.asks.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
-                            }
-                        }
-                    }
-                }
-            }
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        // Keep only top N levels
-        ob.bids.truncate(20);
-        ob.asks.truncate(20);
+    #[test]
+    fn test_parse_depth5() {
+        let text = r#"{"type":"message","topic":"/spotMarket/level2Depth5:SOL-USDT","subject":"level2",
+            "data":{"asks":[["187.52","10.5"],["187.53","3"]],"bids":[["187.50","2"],["187.49","8.25"]],"timestamp":1700000000000}}"#;
+        let (bids, asks, ts) = parse_depth5(text).unwrap().unwrap();
+        assert_eq!(bids, vec![(187.50, 2.0), (187.49, 8.25)]);
+        assert_eq!(asks[0], (187.52, 10.5));
+        assert_eq!(ts, 1_700_000_000_000);
 
-        Ok(())
+        assert!(parse_depth5(r#"{"type":"pong","id":"1"}"#).unwrap().is_none());
     }
 }
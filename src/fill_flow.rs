@@ -0,0 +1,70 @@
+//! Rolling Fill Imbalance
+//!
+//! Buy vs sell fill volume over a trailing window. One-directional flow
+//! shows up here before it shows up as inventory, so the quoting side
+//! that keeps getting hit can be shrunk early. The same window over Binance
+//! aggTrades gives the aggressor imbalance used by the OFI resume gate.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+#[derive(Debug)]
+pub struct FillFlow {
+    window: Duration,
+    /// (time, is_buy, base size)
+    fills: VecDeque<(Instant, bool, f64)>,
+}
+
+impl FillFlow {
+    pub fn new(window: Duration) -> Self {
+        Self { window, fills: VecDeque::new() }
+    }
+
+    pub fn record(&mut self, is_buy: bool, size: f64, now: Instant) {
+        self.fills.push_back((now, is_buy, size));
+        self.prune(now);
+    }
+
+    fn prune(&mut self, now: Instant) {
+        while self.fills.front().is_some_and(|(t, _, _)| now.duration_since(*t) > self.window) {
+            self.fills.pop_front();
+        }
+    }
+
+    /// (buy - sell) / (buy + sell) over the window; +1 = only our bids filling
+    pub fn imbalance(&mut self, now: Instant) -> f64 {
+        self.prune(now);
+        let (buy, sell) = self.fills.iter().fold((0.0, 0.0), |(b, s), (_, is_buy, sz)| {
+            if *is_buy { (b + sz, s) } else { (b, s + sz) }
+        });
+        if buy + sell > 0.0 { (buy - sell) / (buy + sell) } else { 0.0 }
+    }
+}
+
+/// (bid, ask) size multipliers: the side filling more shrinks by
+/// `sensitivity * |imbalance|`, floored at `min_mult`; the other is untouched
+pub fn size_multipliers(imbalance: f64, sensitivity: f64, min_mult: f64) -> (f64, f64) {
+    let shrink = |x: f64| (1.0 - sensitivity * x.max(0.0)).clamp(min_mult.min(1.0), 1.0);
+    (shrink(imbalance), shrink(-imbalance))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_imbalance_window_and_multipliers() {
+        let t0 = Instant::now();
+        let mut f = FillFlow::new(Duration::from_secs(60));
+        f.record(true, 3.0, t0);
+        f.record(false, 1.0, t0 + Duration::from_secs(10));
+        assert!((f.imbalance(t0 + Duration::from_secs(10)) - 0.5).abs() < 1e-9);
+        // The buy ages out
+        assert_eq!(f.imbalance(t0 + Duration::from_secs(61)), -1.0);
+
+        let (b, a) = size_multipliers(0.5, 1.0, 0.25);
+        assert!((b - 0.5).abs() < 1e-9 && a == 1.0);
+        assert_eq!(size_multipliers(-1.0, 2.0, 0.25), (1.0, 0.25));
+        assert_eq!(size_multipliers(0.8, 0.0, 0.25), (1.0, 1.0));
+    }
+}
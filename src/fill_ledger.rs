@@ -0,0 +1,65 @@
+//! Level Fill Ledger
+//!
+//! A level order's filled size reaches us two ways: fill by fill from the
+//! private feed / poll, and as `dealSize` on every recon poll, which sets the
+//! order's remainder outright. A fill that a recon snapshot already counted
+//! must not come off the remainder a second time. Per order this keeps the
+//! fills booked and the last `dealSize` seen; only the part of a fill beyond
+//! both is applied to the level.
+
+use std::collections::HashMap;
+
+#[derive(Debug, Default, Clone, Copy)]
+struct Filled {
+    fills: f64,
+    recon: f64,
+}
+
+#[derive(Debug, Default)]
+pub struct FillLedger {
+    orders: HashMap<String, Filled>,
+}
+
+impl FillLedger {
+    /// Book a fill; returns the size not already covered by recon (or an
+    /// earlier fill), i.e. what the level's remainder should still lose
+    pub fn on_fill(&mut self, order_id: &str, size: f64) -> f64 {
+        let f = self.orders.entry(order_id.to_string()).or_default();
+        let before = f.fills.max(f.recon);
+        f.fills += size;
+        f.fills.max(f.recon) - before
+    }
+
+    /// A recon poll's `dealSize` per active order; orders no longer listed
+    /// are dropped
+    pub fn on_recon<'a>(&mut self, deals: impl IntoIterator<Item = (&'a str, f64)>) {
+        let mut next = HashMap::new();
+        for (order_id, deal) in deals {
+            let mut f = self.orders.remove(order_id).unwrap_or_default();
+            f.recon = f.recon.max(deal);
+            next.insert(order_id.to_string(), f);
+        }
+        self.orders = next;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fill_already_in_recon_deal_not_applied_twice() {
+        let mut l = FillLedger::default();
+        // Fill first, then a recon that includes it: the whole fill applies once
+        assert_eq!(l.on_fill("b1", 0.3), 0.3);
+        l.on_recon([("b1", 0.3)]);
+        // Recon first: it saw 0.5 dealt, the late 0.2 fill is already counted
+        l.on_recon([("b1", 0.5)]);
+        assert_eq!(l.on_fill("b1", 0.2), 0.0);
+        // A fill past what recon saw applies only the new part
+        assert!((l.on_fill("b1", 0.4) - 0.4).abs() < 1e-9);
+        // Gone from the book: forgotten, a stray late fill starts over
+        l.on_recon([]);
+        assert_eq!(l.on_fill("b1", 0.1), 0.1);
+    }
+}
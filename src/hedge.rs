@@ -0,0 +1,145 @@
+//! Inventory Hedge
+//!
+//! Offsets KuCoin spot inventory on a second venue (e.g. a Binance perp)
+//! through the `Exchange` trait. The hedge targets `-inventory * ratio`
+//! and is only adjusted once it drifts more than `band` from that target,
+//! so small inventory swings don't churn hedge orders.
+//!
+//! Only the interface and a logging venue exist so far: the hedge position
+//! is assumed to move by whatever was sent, not confirmed from fills.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::info;
+
+use crate::exchange::traits::{Balance, Exchange, Order, OrderId, OrderRequest, OrderType, Side};
+
+/// What the hedge should do this check
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HedgeAction {
+    Hold,
+    Adjust { side: Side, size: f64 },
+}
+
+/// Hedge target is `-inventory * ratio`; adjust by the full drift once it
+/// exceeds `band` and is at least the venue's `min_size`
+pub fn rebalance_decision(inventory: f64, ratio: f64, hedged: f64, band: f64, min_size: f64) -> HedgeAction {
+    let drift = -inventory * ratio - hedged;
+    if drift.abs() <= band || drift.abs() < min_size {
+        return HedgeAction::Hold;
+    }
+    let side = if drift > 0.0 { Side::Buy } else { Side::Sell };
+    HedgeAction::Adjust { side, size: drift.abs() }
+}
+
+pub struct HedgeManager {
+    venue: Arc<dyn Exchange>,
+    symbol: String,
+    ratio: f64,
+    band: f64,
+    min_size: f64,
+    /// Signed hedge position in base units (short hedge < 0)
+    hedged: f64,
+    seq: u64,
+}
+
+impl HedgeManager {
+    pub fn new(venue: Arc<dyn Exchange>, symbol: String, ratio: f64, band: f64, min_size: f64) -> Self {
+        Self { venue, symbol, ratio, band, min_size, hedged: 0.0, seq: 0 }
+    }
+
+    pub fn hedged(&self) -> f64 {
+        self.hedged
+    }
+
+    /// Rebalance against the current KuCoin inventory
+    pub async fn update(&mut self, inventory: f64) -> Result<HedgeAction> {
+        let action = rebalance_decision(inventory, self.ratio, self.hedged, self.band, self.min_size);
+        if let HedgeAction::Adjust { side, size } = action {
+            self.seq += 1;
+            self.venue.place_order(OrderRequest {
+                symbol: self.symbol.clone(),
+                side,
+                order_type: OrderType::Market,
+                price: None,
+                size,
+                client_id: format!("hedge_{}", self.seq),
+            }).await?;
+            self.hedged += if side == Side::Buy { size } else { -size };
+            info!("[HEDGE] {} {:?} {:.4} {} | inv {:.4} | hedge now {:.4}",
+                self.venue.name(), side, size, self.symbol, inventory, self.hedged);
+        }
+        Ok(action)
+    }
+}
+
+/// Hedge venue that only logs - stands in until a real perp connector exists
+#[derive(Debug, Default)]
+pub struct LoggingHedgeVenue;
+
+#[async_trait]
+impl Exchange for LoggingHedgeVenue {
+    fn name(&self) -> &str {
+        "log-only"
+    }
+
+    fn is_connected(&self) -> bool {
+        true
+    }
+
+    async fn place_order(&self, req: OrderRequest) -> Result<OrderId> {
+        info!("[HEDGE] (no-op) would {:?} {:.4} {} as {:?}", req.side, req.size, req.symbol, req.order_type);
+        Ok(OrderId { exchange_id: None, client_id: req.client_id })
+    }
+
+    async fn cancel_order(&self, _symbol: &str, _order_id: &OrderId) -> Result<()> {
+        Ok(())
+    }
+
+    async fn cancel_all_orders(&self, _symbol: &str) -> Result<()> {
+        Ok(())
+    }
+
+    async fn modify_order(&self, _symbol: &str, order_id: &OrderId, _new_price: Option<f64>, _new_size: Option<f64>) -> Result<OrderId> {
+        Ok(order_id.clone())
+    }
+
+    async fn get_balances(&self) -> Result<HashMap<String, Balance>> {
+        Ok(HashMap::new())
+    }
+
+    async fn get_open_orders(&self, _symbol: &str) -> Result<Vec<Order>> {
+        Ok(Vec::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rebalance_decision() {
+        // Inside the band - leave the hedge alone
+        assert_eq!(rebalance_decision(0.4, 1.0, 0.0, 0.5, 0.01), HedgeAction::Hold);
+        // Long spot -> short the full drift
+        assert_eq!(rebalance_decision(2.0, 1.0, 0.0, 0.5, 0.01), HedgeAction::Adjust { side: Side::Sell, size: 2.0 });
+        // Partial ratio, already partly hedged
+        assert_eq!(rebalance_decision(4.0, 0.5, -1.0, 0.5, 0.01), HedgeAction::Adjust { side: Side::Sell, size: 1.0 });
+        // Inventory came back - buy the hedge back
+        assert_eq!(rebalance_decision(0.0, 1.0, -2.0, 0.5, 0.01), HedgeAction::Adjust { side: Side::Buy, size: 2.0 });
+        // Drift beyond the band but below the venue minimum
+        assert_eq!(rebalance_decision(0.6, 1.0, 0.0, 0.5, 1.0), HedgeAction::Hold);
+    }
+
+    #[tokio::test]
+    async fn test_manager_tracks_sent_hedge() {
+        let mut h = HedgeManager::new(Arc::new(LoggingHedgeVenue), "SOLUSDT".into(), 1.0, 0.5, 0.01);
+        assert_eq!(h.update(3.0).await.unwrap(), HedgeAction::Adjust { side: Side::Sell, size: 3.0 });
+        assert_eq!(h.hedged(), -3.0);
+        assert_eq!(h.update(3.3).await.unwrap(), HedgeAction::Hold);
+        assert_eq!(h.update(1.0).await.unwrap(), HedgeAction::Adjust { side: Side::Buy, size: 2.0 });
+        assert_eq!(h.hedged(), -1.0);
+    }
+}
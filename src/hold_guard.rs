@@ -0,0 +1,42 @@
+//! Max Position Hold Time
+//!
+//! Hard risk stop: tracks how long net inventory has sat outside
+//! `±band`. Once that exceeds the configured maximum the strategy flattens
+//! with a marketable order and halts until a manual restart.
+
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Default)]
+pub struct HoldGuard {
+    /// When inventory last left the band (None = inside)
+    outside_since: Option<Instant>,
+}
+
+impl HoldGuard {
+    /// Feed the current inventory; returns how long it has been outside the band
+    pub fn update(&mut self, inv: f64, band: f64, now: Instant) -> Option<Duration> {
+        if inv.abs() <= band {
+            self.outside_since = None;
+            return None;
+        }
+        let since = *self.outside_since.get_or_insert(now);
+        Some(now.duration_since(since))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duration_outside_band_resets_inside() {
+        let t0 = Instant::now();
+        let mut g = HoldGuard::default();
+        assert_eq!(g.update(1.0, 2.0, t0), None);
+        assert_eq!(g.update(3.0, 2.0, t0 + Duration::from_secs(1)), Some(Duration::ZERO));
+        // Flipping sides still counts as outside
+        assert_eq!(g.update(-2.5, 2.0, t0 + Duration::from_secs(61)), Some(Duration::from_secs(60)));
+        assert_eq!(g.update(0.5, 2.0, t0 + Duration::from_secs(62)), None);
+        assert_eq!(g.update(2.5, 2.0, t0 + Duration::from_secs(70)), Some(Duration::ZERO));
+    }
+}
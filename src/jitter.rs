@@ -0,0 +1,24 @@
+//! Jitter Draw
+//!
+//! Quote jitter and reconnect backoff both want a uniform random factor.
+//! uuid v4 is the only RNG we carry, so the draw comes from its random bits.
+
+/// Uniform draw in [-1, 1) from a v4 uuid's random bits. The low 64 bits
+/// open with the fixed variant bits (`10`), so only the 62 below them count.
+pub(crate) fn jitter_draw() -> f64 {
+    let bits = (uuid::Uuid::new_v4().as_u128() as u64) & ((1u64 << 62) - 1);
+    (bits >> 9) as f64 / (1u64 << 53) as f64 * 2.0 - 1.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jitter_draw_in_unit_range() {
+        let draws: Vec<f64> = (0..1000).map(|_| jitter_draw()).collect();
+        assert!(draws.iter().all(|u| (-1.0..1.0).contains(u)));
+        // Both signs turn up
+        assert!(draws.iter().any(|u| *u < 0.0) && draws.iter().any(|u| *u > 0.0));
+    }
+}
@@ -0,0 +1,151 @@
+//! Order-Entry Latency Probe
+//!
+//! Periodically rests a far-out post-only bid, times placement until the
+//! private feed reports it `open`, then cancels it. The round trip is a
+//! synthetic health signal independent of strategy activity.
+
+use anyhow::{anyhow, Result};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, RwLock};
+use tracing::{info, warn};
+
+use crate::alert::Alerter;
+use crate::config::LatencyProbeConfig;
+use crate::exchange::kucoin_ws_private::{OrderUpdate, OrderUpdateKind};
+use crate::exchange::ws_order_client_v2::{LatencyStats, WsCancelRequest, WsOrderClientV2, WsOrderRequest};
+
+/// clientOid prefix - recon never treats probe orders as orphans
+pub const PROBE_OID_PREFIX: &str = "probe_";
+
+pub struct ProbeStats {
+    pub latency: LatencyStats,
+    pub last: Option<Duration>,
+    pub timeouts: u64,
+    pub errors: u64,
+}
+
+pub type SharedProbeStats = Arc<RwLock<ProbeStats>>;
+
+pub fn new_shared_probe_stats() -> SharedProbeStats {
+    Arc::new(RwLock::new(ProbeStats { latency: LatencyStats::new(), last: None, timeouts: 0, errors: 0 }))
+}
+
+impl ProbeStats {
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "last_ms": self.last.map(|d| d.as_secs_f64() * 1000.0),
+            "avg_ms": self.latency.avg_us() as f64 / 1000.0,
+            "max_ms": if self.latency.count > 0 { self.latency.max_us as f64 / 1000.0 } else { 0.0 },
+            "count": self.latency.count,
+            "timeouts": self.timeouts,
+            "errors": self.errors,
+        })
+    }
+}
+
+/// Run one probe, record the outcome and alert on breach
+pub async fn run_probe(
+    ws: Arc<WsOrderClientV2>,
+    events: broadcast::Receiver<OrderUpdate>,
+    req: WsOrderRequest,
+    cfg: LatencyProbeConfig,
+    stats: SharedProbeStats,
+    alerter: Alerter,
+) {
+    let timeout = Duration::from_millis(cfg.timeout_ms);
+    match probe_once(&ws, events, req, timeout).await {
+        Ok(Some(rtt)) => {
+            let ms = rtt.as_secs_f64() * 1000.0;
+            info!("[PROBE] place->open {:.1}ms", ms);
+            let mut st = stats.write().await;
+            st.latency.record(rtt);
+            st.last = Some(rtt);
+            if ms > cfg.alert_ms as f64 {
+                alerter.alert("latency_probe", &format!("place->open {:.0}ms exceeds {}ms", ms, cfg.alert_ms));
+            }
+        }
+        Ok(None) => {
+            stats.write().await.timeouts += 1;
+            alerter.alert("latency_probe", &format!("no open confirmation within {}ms", cfg.timeout_ms));
+        }
+        Err(e) => {
+            warn!("[PROBE] Failed: {}", e);
+            stats.write().await.errors += 1;
+        }
+    }
+}
+
+/// Place one probe order, wait for its `open` event, cancel it.
+/// Returns `None` if `open` didn't arrive within `timeout`.
+pub async fn probe_once(
+    ws: &WsOrderClientV2,
+    mut events: broadcast::Receiver<OrderUpdate>,
+    req: WsOrderRequest,
+    timeout: Duration,
+) -> Result<Option<Duration>> {
+    let client_oid = req.client_oid.clone();
+    let symbol = req.symbol.clone();
+    let start = Instant::now();
+
+    let resp = ws.place_order(req).await?;
+    if !resp.success {
+        return Err(anyhow!("probe rejected: {:?} {:?}", resp.code, resp.msg));
+    }
+
+    let rtt = wait_for_open(&mut events, &client_oid, start, timeout).await;
+
+    // Always clean up, even on timeout
+    let _ = ws.cancel_order(WsCancelRequest {
+        symbol, order_id: resp.order_id, client_oid: None,
+    }).await;
+
+    Ok(rtt)
+}
+
+async fn wait_for_open(
+    events: &mut broadcast::Receiver<OrderUpdate>,
+    client_oid: &str,
+    start: Instant,
+    timeout: Duration,
+) -> Option<Duration> {
+    let deadline = tokio::time::Instant::now() + timeout;
+    loop {
+        match tokio::time::timeout_at(deadline, events.recv()).await {
+            Ok(Ok(u)) if u.client_oid == client_oid && u.kind == OrderUpdateKind::Open => {
+                return Some(u.received_at.duration_since(start));
+            }
+            Ok(Ok(_)) | Ok(Err(broadcast::error::RecvError::Lagged(_))) => continue,
+            Ok(Err(broadcast::error::RecvError::Closed)) | Err(_) => return None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update(client_oid: &str, kind: OrderUpdateKind) -> OrderUpdate {
+        OrderUpdate { order_id: "o1".into(), client_oid: client_oid.into(), kind, received_at: Instant::now() }
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_open_matches_client_oid() {
+        let (tx, mut rx) = broadcast::channel(16);
+        let start = Instant::now();
+        tx.send(update("b12_3", OrderUpdateKind::Open)).unwrap();
+        tx.send(update("probe_1", OrderUpdateKind::Canceled)).unwrap();
+        tx.send(update("probe_1", OrderUpdateKind::Open)).unwrap();
+
+        let rtt = wait_for_open(&mut rx, "probe_1", start, Duration::from_millis(100)).await;
+        assert!(rtt.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_open_times_out() {
+        let (tx, mut rx) = broadcast::channel::<OrderUpdate>(16);
+        tx.send(update("other", OrderUpdateKind::Open)).unwrap();
+        let rtt = wait_for_open(&mut rx, "probe_1", Instant::now(), Duration::from_millis(20)).await;
+        assert!(rtt.is_none());
+    }
+}
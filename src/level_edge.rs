@@ -0,0 +1,116 @@
+//! Per-Level Edge Gate
+//!
+//! Every fill is attributed to the level that placed it and marked out
+//! against the mid `horizon` later. Each level side keeps an EWMA of that
+//! adverse move. Before re-placing a level, its expected net edge -
+//! distance from mid plus rebate, minus the adverse estimate - is checked
+//! against a floor; a level that keeps getting picked off stays out.
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Placed orders remembered for fill attribution
+const MAX_TRACKED_ORDERS: usize = 2000;
+
+/// Expected edge in bps of a resting quote `distance_bps` from mid
+pub fn expected_edge_bps(distance_bps: f64, rebate_bps: f64, adverse_bps: f64) -> f64 {
+    distance_bps + rebate_bps - adverse_bps
+}
+
+#[derive(Debug)]
+pub struct LevelEdge {
+    horizon: Duration,
+    alpha: f64,
+    /// order id -> (level key, is_bid)
+    orders: HashMap<String, (i32, bool)>,
+    order_ids: VecDeque<String>,
+    /// (filled at, key, is_bid, price) awaiting markout
+    pending: VecDeque<(Instant, i32, bool, f64)>,
+    /// EWMA adverse move (bps, + = against us) per (key, is_bid)
+    adverse: HashMap<(i32, bool), f64>,
+}
+
+impl LevelEdge {
+    pub fn new(horizon: Duration, alpha: f64) -> Self {
+        Self {
+            horizon,
+            alpha: alpha.clamp(0.0, 1.0),
+            orders: HashMap::new(),
+            order_ids: VecDeque::new(),
+            pending: VecDeque::new(),
+            adverse: HashMap::new(),
+        }
+    }
+
+    pub fn on_placed(&mut self, order_id: String, key: i32, is_bid: bool) {
+        if self.order_ids.len() >= MAX_TRACKED_ORDERS {
+            if let Some(old) = self.order_ids.pop_front() {
+                self.orders.remove(&old);
+            }
+        }
+        self.orders.insert(order_id.clone(), (key, is_bid));
+        self.order_ids.push_back(order_id);
+    }
+
+    /// Level key of a tracked order
+    pub fn level_of(&self, order_id: &str) -> Option<i32> {
+        self.orders.get(order_id).map(|&(key, _)| key)
+    }
+
+    /// Fills on orders we didn't place through the ladder are ignored
+    pub fn on_fill(&mut self, order_id: &str, price: f64, now: Instant) {
+        if let Some(&(key, is_bid)) = self.orders.get(order_id) {
+            self.pending.push_back((now, key, is_bid, price));
+        }
+    }
+
+    /// Mark out fills past the horizon against `mid`
+    pub fn update(&mut self, mid: f64, now: Instant) {
+        while let Some(&(at, key, is_bid, price)) = self.pending.front() {
+            if now.duration_since(at) < self.horizon {
+                break;
+            }
+            self.pending.pop_front();
+            let moved = (mid - price) / price * 10000.0;
+            let adverse = if is_bid { -moved } else { moved };
+            let e = self.adverse.entry((key, is_bid)).or_insert(adverse);
+            *e = self.alpha * adverse + (1.0 - self.alpha) * *e;
+        }
+    }
+
+    /// Adverse estimate for a level side; 0 until it has been filled
+    pub fn adverse_bps(&self, key: i32, is_bid: bool) -> f64 {
+        self.adverse.get(&(key, is_bid)).copied().unwrap_or(0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adverse_estimate_per_level_side() {
+        let t0 = Instant::now();
+        let s = Duration::from_secs;
+        let mut e = LevelEdge::new(s(5), 0.5);
+        e.on_placed("b1".into(), 10, true);
+        e.on_placed("a1".into(), 10, false);
+        e.on_fill("b1", 100.0, t0);
+        e.on_fill("a1", 100.2, t0);
+        e.on_fill("manual", 100.0, t0);
+        e.update(99.9, t0 + s(4));
+        assert_eq!(e.adverse_bps(10, true), 0.0);
+        // Mid fell 10bps from the bid fill: bid picked off, ask fill was good
+        e.update(99.9, t0 + s(5));
+        assert!((e.adverse_bps(10, true) - 10.0).abs() < 1e-9);
+        assert!(e.adverse_bps(10, false) < 0.0);
+        // A clean fill halves it
+        e.on_fill("b1", 100.0, t0 + s(6));
+        e.update(100.0, t0 + s(11));
+        assert!((e.adverse_bps(10, true) - 5.0).abs() < 1e-9);
+
+        // 3bps out + 1bps rebate vs 5bps adverse -> negative edge
+        assert!((expected_edge_bps(3.0, 1.0, e.adverse_bps(10, true)) + 1.0).abs() < 1e-9);
+        assert_eq!(expected_edge_bps(3.0, 1.0, e.adverse_bps(20, true)), 4.0);
+    }
+}
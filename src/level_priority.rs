@@ -0,0 +1,88 @@
+//! Level Processing Order
+//!
+//! With a per-tick action budget the level loop can stop before reaching
+//! every layer. The priority policy decides which layers are visited first,
+//! so a tight budget is spent where it matters most.
+
+use anyhow::{anyhow, Result};
+
+/// Per-level snapshot taken before the loop runs
+#[derive(Debug, Clone, Copy)]
+pub struct LevelView {
+    /// Index into the ladder (0 = innermost)
+    pub index: usize,
+    /// A resting bid or ask is past its refresh threshold or adverse to trend
+    pub out_of_band: bool,
+    /// Bid or ask slot is empty and could be placed
+    pub has_gap: bool,
+}
+
+pub trait LevelPriority: Send + Sync {
+    fn name(&self) -> &'static str;
+    /// Level indices in the order they should be processed
+    fn order(&self, levels: &[LevelView]) -> Vec<usize>;
+}
+
+/// Array order, innermost first - the original behaviour
+pub struct Sequential;
+
+impl LevelPriority for Sequential {
+    fn name(&self) -> &'static str { "sequential" }
+    fn order(&self, levels: &[LevelView]) -> Vec<usize> {
+        levels.iter().map(|l| l.index).collect()
+    }
+}
+
+/// Out-of-band cancels first (inner before far - most dangerous), then
+/// placements into gaps (inner before far - most likely to fill), then the rest
+pub struct RiskFirst;
+
+impl LevelPriority for RiskFirst {
+    fn name(&self) -> &'static str { "risk_first" }
+    fn order(&self, levels: &[LevelView]) -> Vec<usize> {
+        let mut sorted: Vec<&LevelView> = levels.iter().collect();
+        // Stable sort keeps inner-first within each bucket
+        sorted.sort_by_key(|l| match (l.out_of_band, l.has_gap) {
+            (true, _) => 0,
+            (false, true) => 1,
+            (false, false) => 2,
+        });
+        sorted.into_iter().map(|l| l.index).collect()
+    }
+}
+
+pub fn from_name(name: &str) -> Result<Box<dyn LevelPriority>> {
+    match name {
+        "sequential" => Ok(Box::new(Sequential)),
+        "risk_first" => Ok(Box::new(RiskFirst)),
+        other => Err(anyhow!("unknown level priority '{}' (expected sequential | risk_first)", other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn view(index: usize, out_of_band: bool, has_gap: bool) -> LevelView {
+        LevelView { index, out_of_band, has_gap }
+    }
+
+    #[test]
+    fn test_risk_first_buckets_keep_inner_first() {
+        let levels = [
+            view(0, false, false),
+            view(1, false, true),
+            view(2, true, false),
+            view(3, false, true),
+            view(4, true, true),
+        ];
+        assert_eq!(RiskFirst.order(&levels), vec![2, 4, 1, 3, 0]);
+        assert_eq!(Sequential.order(&levels), vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_from_name() {
+        assert_eq!(from_name("risk_first").unwrap().name(), "risk_first");
+        assert!(from_name("far_first").is_err());
+    }
+}
@@ -1,21 +1,91 @@
 //! TEST_Multi_layers v10.5: Partial Fill Tracking + FIFO Persistence
 use anyhow::Result;
+use clap::Parser;
 use futures_util::StreamExt;
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{Duration, Instant};
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, Notify, RwLock};
 use tokio_tungstenite::connect_async;
-use tracing::{info, warn};
+use tracing::{debug, error, info, warn};
 
+mod adopt_check;
+mod alert;
+mod analyze;
+mod cancel_sweep;
+mod cli;
+mod config;
+mod drain;
+mod entry_breaker;
+mod event_log;
 mod exchange;
+mod fill_flow;
+mod fill_ledger;
+mod hedge;
+mod hold_guard;
+mod jitter;
+mod latency_probe;
+mod level_edge;
+mod level_priority;
+mod market_status;
+mod mid_ema;
+mod ofi_resume;
+mod own_depth;
+mod post_fill_skew;
+mod price_history;
+mod price_collision;
+mod quote_gate;
+mod refresh_tiers;
+mod requote_hysteresis;
+mod resync;
+mod schedule;
+mod scratch;
+mod shutdown;
+mod status;
+mod symbol_control;
+mod trade_dedup;
+mod trade_log;
+mod turnover;
+use adopt_check::AdoptCheck;
+use alert::Alerter;
+use cancel_sweep::CancelApi;
 use exchange::auth::KucoinAuth;
-use exchange::ws_order_client_v2::{WsOrderClientV2, WsOrderRequest, WsCancelRequest};
+use exchange::{KucoinEndpoints, KucoinPrivateWs, KucoinPublicWs, KucoinRestClient, Liquidity, OrderSide, OrderUpdate, OrderUpdateKind, RestLimiter, Side};
+use exchange::order_book::{BookSide, OrderBook};
+use fill_flow::FillFlow;
+use fill_ledger::FillLedger;
+use hedge::{HedgeManager, LoggingHedgeVenue};
+use hold_guard::HoldGuard;
+use jitter::jitter_draw;
+use drain::{Drain, DrainStep};
+use entry_breaker::{Admit, EntryBreaker, Transition};
+use config::{BookCheckConfig, FarLayerMode, LevelsConfig, MidSource, PriceCollision, QuotingConfig, RepriceMode, SignalsConfig, SizePolicy, SizingConfig, SpacingMode, StartupMode, ToxicitySource, TradeMode};
+use level_edge::LevelEdge;
+use level_priority::LevelView;
+use market_status::MarketStatus;
+use mid_ema::MidEma;
+use ofi_resume::ResumeGate;
+use post_fill_skew::PostFillSkew;
+use price_collision::SideQuote;
+use price_history::PriceHistory;
+use quote_gate::{GateReason, GateScope, QuoteGate};
+use refresh_tiers::RefreshTiers;
+use requote_hysteresis::RequoteHysteresis;
+use resync::Resync;
+use schedule::Schedule;
+use scratch::ScratchWatch;
+use shutdown::Shutdown;
+use symbol_control::SymbolControl;
+use trade_dedup::SeenTrades;
+use trade_log::{TradeLog, TradeRecord};
+use turnover::Turnover;
+use exchange::ws_order_client_v2::{is_post_only_cross_msg, SYMBOL_NOT_EXIST_CODE, ConnectionEvent, LatencyStats, WsOrderClientV2, WsOrderRequest, WsOrderResponse, WsCancelRequest};
 
 // ═══════════════════════════════════════════════════════════════════
-// CONFIGURATION - 25 LAYERS PER SIDE
+// CONFIGURATION - 25 LAYERS PER SIDE (default ladder)
 // ═══════════════════════════════════════════════════════════════════
-const LEVELS: [(f64, f64); 25] = [
+const DEFAULT_LEVELS: [(f64, f64); 25] = [
     // V10.10: L1=0.25, L2-L6 +0.5bps gaps, L7-L25 +1bps gaps
     // Close layers (0.5 bps increments after L1)
     (0.25, 1.92), (0.75, 4.24), (1.25, 6.52), (1.75, 8.86), (2.25, 11.16),
@@ -30,17 +100,65 @@ const LEVELS: [(f64, f64); 25] = [
 const ORDER_USD: f64 = 25.0;
 const MAX_INV_SOL: f64 = 15.0;
 const REBATE: f64 = 1.0;
-const SYM: &str = "SOL-USDT";
-const MAX_ORDERS_PER_SIDE: usize = 25; // 25 bids + 25 asks
+const DEFAULT_SYMBOL: &str = "SOL-USDT";
+
+// V10.15: Traded pair from `--symbol` or `TRADE_SYMBOL` (default SOL-USDT),
+// split into base / quote for balance and fee matching. Set at startup;
+// unset (tests) = the default.
+#[derive(Debug, Clone, PartialEq)]
+struct TradeSymbol {
+    name: String,
+    base: String,
+    quote: String,
+}
+
+impl TradeSymbol {
+    fn parse(s: &str) -> Result<Self> {
+        let name = s.trim().to_uppercase();
+        match name.split_once('-') {
+            Some((base, quote)) if !base.is_empty() && !quote.is_empty() && !quote.contains('-') =>
+                Ok(Self { base: base.into(), quote: quote.into(), name: name.clone() }),
+            _ => anyhow::bail!("symbol {:?} is not BASE-QUOTE", s),
+        }
+    }
+
+    /// Binance USDT-M futures stream name for the reference mid, e.g. "solusdt"
+    fn binance_stream(&self) -> String {
+        format!("{}usdt", self.base.to_lowercase())
+    }
+}
+
+static SYMBOL: std::sync::OnceLock<TradeSymbol> = std::sync::OnceLock::new();
+
+fn symbol() -> &'static TradeSymbol {
+    SYMBOL.get_or_init(|| TradeSymbol::parse(DEFAULT_SYMBOL).expect("default symbol"))
+}
+
+// `--symbol X`, else `TRADE_SYMBOL`, else the default
+fn symbol_from(flag: Option<String>, env: Option<String>) -> String {
+    flag.or(env).unwrap_or_else(|| DEFAULT_SYMBOL.to_string())
+}
+
+// V10.15: (offset_bps, refresh_bps) per level from `ladder_file`, set at
+// startup; unset (tests) = DEFAULT_LEVELS. One order per level per side.
+static LADDER: std::sync::OnceLock<Vec<(f64, f64)>> = std::sync::OnceLock::new();
+
+fn levels() -> &'static [(f64, f64)] {
+    LADDER.get_or_init(|| DEFAULT_LEVELS.to_vec())
+}
 
 // ═══════════════════════════════════════════════════════════════════
 // QUANT PARAMETERS
 // ═══════════════════════════════════════════════════════════════════
+// V10.15: ORDER_USD, MAX_INV_SOL, GAMMA, OFI_PAUSE_THRESHOLD, OFI_RESUME_THRESHOLD
+// and MOMENTUM_THRESHOLD are defaults - the loop reads `cli::StrategyConfig`
 const GAMMA: f64 = 0.05;
 const OFI_PAUSE_THRESHOLD: f64 = 0.70;
 const OFI_RESUME_THRESHOLD: f64 = 0.35;
+const OFI_EMPTY_DECAY: f64 = 0.9;          // V10.15: Per depth5 update with no depth
 const VOL_EWMA_LAMBDA: f64 = 0.94;
 const SIGMA_FLOOR: f64 = 0.016;           // V10.5: Reduced 20% from 0.02
+const SIGMA_MIN_INTERVAL_MS: f64 = 10.0;  // V10.15: Floor on the annualization interval
 const MOMENTUM_THRESHOLD: f64 = 0.003;
 const MOMENTUM_WINDOW_SECS: u64 = 180;    // V10.5: Reduced from 300s to 3min
 const ETA: f64 = -0.005;
@@ -50,8 +168,8 @@ const INV_NEUTRAL_ZONE: f64 = 2.0;        // V10.13: Inventory zone considered "
 // V10.3: Cancel timeout - try REST fallback before forcing empty
 const CANCEL_TIMEOUT_SECS: u64 = 5;
 
-// V10.3: Orphan cancel rate limiting (prevent cancel storm)
-const MAX_ORPHAN_CANCELS_PER_TICK: usize = 5;
+// V10.15: Consecutive REST auth failures in recon before halting
+const MAX_REST_AUTH_FAILURES: u32 = 3;
 
 // V10.3: Safety buffer for balance checks
 const BALANCE_SAFETY_BUFFER_PCT: f64 = 0.02; // 2% buffer
@@ -63,7 +181,8 @@ const BALANCE_SAFETY_BUFFER_PCT: f64 = 0.02; // 2% buffer
 enum LevelOrderState {
     Empty,
     // V10.5: Added remaining_size for partial fill tracking
-    Live { order_id: String, price: f64, remaining_size: f64 },
+    // V10.15: placed_at for the minimum resting time (adoption time for adopted orders)
+    Live { order_id: String, price: f64, remaining_size: f64, placed_at: Instant },
     CancelPending { order_id: String, price: f64, sent_at: Instant, attempts: u8 },
     // V10.3: Order stuck - WS cancel failed, needs REST fallback
     CancelStuck { order_id: String, price: f64 },
@@ -72,8 +191,6 @@ enum LevelOrderState {
 impl LevelOrderState {
     fn is_empty(&self) -> bool { matches!(self, LevelOrderState::Empty) }
     fn is_live(&self) -> bool { matches!(self, LevelOrderState::Live { .. }) }
-    fn is_cancel_pending(&self) -> bool { matches!(self, LevelOrderState::CancelPending { .. }) }
-    fn is_cancel_stuck(&self) -> bool { matches!(self, LevelOrderState::CancelStuck { .. }) }
     fn order_id(&self) -> Option<&str> {
         match self {
             LevelOrderState::Live { order_id, .. } => Some(order_id),
@@ -90,13 +207,156 @@ impl LevelOrderState {
 #[derive(Clone)]
 struct ActiveOrder {
     order_id: String,
-    side: String,
+    client_oid: String,
+    side: Side,
     price: f64,
+    /// Unfilled remainder (size - dealSize)
     size: f64,
+    /// V10.15: Filled so far (`dealSize`)
+    deal: f64,
+    /// V10.15: Exchange is already cancelling it (`cancelExist`)
+    cancel_exist: bool,
 }
 
 #[derive(Default, Clone)]
-struct Balances { sol: f64, usdt: f64 }
+struct Balances {
+    sol: f64,
+    usdt: f64,
+    // V10.15: Amounts frozen by open orders (the accounts `holds` field)
+    sol_holds: f64,
+    usdt_holds: f64,
+}
+
+// V10.15: Funds held by our own tracked orders - adopted, live or being
+// cancelled - as (usdt, sol), from the exchange's view of those orders
+fn own_order_holds(orders: &[ActiveOrder], level_orders: &HashMap<i32, (LevelOrderState, LevelOrderState)>) -> (f64, f64) {
+    let own: HashSet<&str> = level_orders.values().flat_map(|(b, a)| [b, a]).filter_map(|s| s.order_id()).collect();
+    orders.iter().filter(|o| own.contains(o.order_id.as_str())).fold((0.0, 0.0), |(u, s), o| {
+        match o.side {
+            Side::Buy => (u + o.price * o.size, s),
+            Side::Sell => (u, s + o.size),
+        }
+    })
+}
+
+// V10.15: `available` excludes holds, but our own orders are already charged
+// through CommitmentTracker (or are about to be cancelled). Add their holds
+// back, never more than the account actually holds, so they aren't counted
+// twice and a restart-after-adopt doesn't under-quote until holds release.
+fn effective_balances(bal: &Balances, (own_usdt, own_sol): (f64, f64)) -> Balances {
+    Balances {
+        usdt: bal.usdt + own_usdt.min(bal.usdt_holds).max(0.0),
+        sol: bal.sol + own_sol.min(bal.sol_holds).max(0.0),
+        ..bal.clone()
+    }
+}
+
+// V10.15: What the placement gates may quote with - the balance less the
+// percentage buffer and the fixed `account.reserve_*` cushion
+fn tradable(balance: f64, reserve: f64) -> f64 {
+    (balance * (1.0 - BALANCE_SAFETY_BUFFER_PCT) - reserve.max(0.0)).max(0.0)
+}
+
+// V10.14: Symbol increments - all price/size rounding goes through here
+#[derive(Clone, Copy, Debug)]
+struct SymbolInfo {
+    price_increment: f64,
+    base_increment: f64,
+    base_min_size: f64,
+}
+
+impl Default for SymbolInfo {
+    // Matches the previously hard-coded 0.01 tick / 0.01 lot
+    fn default() -> Self { Self { price_increment: 0.01, base_increment: 0.01, base_min_size: 0.01 } }
+}
+
+impl SymbolInfo {
+    /// Nearest tick - for computing target prices
+    fn round_price(&self, px: f64) -> f64 { (px / self.price_increment).round() * self.price_increment }
+    /// Nearest lot - for computing target sizes
+    fn round_size(&self, sz: f64) -> f64 { (sz / self.base_increment).round() * self.base_increment }
+    
+    /// Wire price: floored to the tick so KuCoin never rejects on precision
+    fn price_str(&self, px: f64) -> String {
+        let t = truncate_to_increment(px, self.price_increment);
+        if (px - t).abs() > self.price_increment * 1e-3 {
+            debug!("[ROUND] price {} truncated to {}", px, t);
+        }
+        format!("{:.*}", increment_decimals(self.price_increment), t)
+    }
+    /// Wire size: floored to the lot
+    fn size_str(&self, sz: f64) -> String {
+        let t = truncate_to_increment(sz, self.base_increment);
+        if (sz - t).abs() > self.base_increment * 1e-3 {
+            debug!("[ROUND] size {} truncated to {}", sz, t);
+        }
+        format!("{:.*}", increment_decimals(self.base_increment), t)
+    }
+    /// Wire size KuCoin would accept: a number, > 0 and at least `base_min_size`
+    fn check_size(&self, size: &str) -> Result<(), String> {
+        match size.parse::<f64>() {
+            Ok(sz) if sz > 0.0 && sz >= self.base_min_size - self.base_increment * 1e-6 => Ok(()),
+            Ok(sz) => Err(format!("size {} below min {}", sz, self.base_min_size)),
+            Err(_) => Err(format!("bad size {:?}", size)),
+        }
+    }
+}
+
+// V10.15: Every order goes out through here - a zero, negative or sub-minimum
+// size (config error, odd lot increment) is logged and never sent
+async fn place_order_checked(ws: &WsOrderClientV2, sym: &SymbolInfo, req: WsOrderRequest) -> Result<WsOrderResponse> {
+    if let Err(why) = sym.check_size(&req.size) {
+        warn!("[GUARD] Not sending {} {} @ {}: {}", req.side, req.client_oid, req.price, why);
+        anyhow::bail!(why);
+    }
+    ws.place_order(req).await
+}
+
+// V10.15: Limit price `slippage_bps` through mid, rounded away from mid - an
+// IOC here takes liquidity without an unbounded market order
+fn marketable_price(is_sell: bool, mid: f64, slippage_bps: f64, sym: &SymbolInfo) -> f64 {
+    let ticks = mid * (1.0 + if is_sell { -slippage_bps } else { slippage_bps } / 10000.0) / sym.price_increment;
+    (if is_sell { (ticks + 1e-9).floor() } else { (ticks - 1e-9).ceil() }) * sym.price_increment
+}
+
+// V10.15: Rebate-only flatten - first tick strictly beyond mid on the passive side, so
+// the post-only order rests at the touch instead of taking
+fn passive_price(is_sell: bool, mid: f64, sym: &SymbolInfo) -> f64 {
+    let ticks = mid / sym.price_increment;
+    (if is_sell { (ticks + 1e-9).floor() + 1.0 } else { (ticks - 1e-9).ceil() - 1.0 }) * sym.price_increment
+}
+
+// Size scaled by up to ±pct percent, lot-aligned and never below the minimum
+fn jitter_size(size: f64, pct: f64, u: f64, sym: &SymbolInfo) -> f64 {
+    if pct <= 0.0 {
+        return size;
+    }
+    truncate_to_increment(size * (1.0 + pct / 100.0 * u), sym.base_increment).max(sym.base_min_size)
+}
+
+// Price moved only away from mid (post-only safe), by up to `max_bps` but at
+// most half the level's refresh threshold so jitter alone never triggers one
+fn jitter_price(is_bid: bool, price: f64, max_bps: f64, thresh_bps: f64, u: f64, sym: &SymbolInfo) -> f64 {
+    let bps = max_bps.min(thresh_bps * 0.5).max(0.0) * u.abs();
+    if bps == 0.0 {
+        return price;
+    }
+    sym.round_price(price * if is_bid { 1.0 - bps / 10000.0 } else { 1.0 + bps / 10000.0 })
+}
+
+/// Floor to a multiple of `inc`, tolerating float noise just below a multiple
+fn truncate_to_increment(v: f64, inc: f64) -> f64 {
+    ((v / inc) + 1e-9).floor() * inc
+}
+
+/// Decimal places implied by an increment (0.0001 -> 4, 1 -> 0)
+fn increment_decimals(inc: f64) -> usize {
+    let mut d = 0;
+    while d < 12 && ((inc * 10f64.powi(d as i32)).round() - inc * 10f64.powi(d as i32)).abs() > 1e-9 {
+        d += 1;
+    }
+    d
+}
 
 // V10.3: Two-layer commitment tracking
 #[derive(Default, Clone)]
@@ -115,30 +375,854 @@ impl CommitmentTracker {
     
     fn add_inflight_bid(&mut self, notional: f64) { self.inflight_usdt += notional; }
     fn add_inflight_ask(&mut self, size: f64) { self.inflight_sol += size; }
+    // V10.15: Undo a reservation whose place failed
+    fn release_inflight_bid(&mut self, notional: f64) { self.inflight_usdt = (self.inflight_usdt - notional).max(0.0); }
+    fn release_inflight_ask(&mut self, size: f64) { self.inflight_sol = (self.inflight_sol - size).max(0.0); }
     
-    // Move from inflight to live when recon confirms
-    fn confirm_bid(&mut self, notional: f64) {
-        self.inflight_usdt = (self.inflight_usdt - notional).max(0.0);
-        self.live_usdt += notional;
+    // V10.15: A modified order is a new order - the old one's commitment goes,
+    // the new one's is inflight until recon sees it
+    fn rebook_bid(&mut self, old_notional: f64, new_notional: f64) {
+        self.fill_bid(old_notional);
+        self.add_inflight_bid(new_notional);
     }
-    fn confirm_ask(&mut self, size: f64) {
-        self.inflight_sol = (self.inflight_sol - size).max(0.0);
-        self.live_sol += size;
+    fn rebook_ask(&mut self, old_size: f64, new_size: f64) {
+        self.fill_ask(old_size);
+        self.add_inflight_ask(new_size);
     }
     
-    // Remove from live when filled/cancelled
-    fn release_bid(&mut self, notional: f64) { self.live_usdt = (self.live_usdt - notional).max(0.0); }
-    fn release_ask(&mut self, size: f64) { self.live_sol = (self.live_sol - size).max(0.0); }
-    
     // Reset inflight on recon (anything not confirmed is orphan)
     fn reset_inflight(&mut self) { self.inflight_usdt = 0.0; self.inflight_sol = 0.0; }
+    
+    // V10.15: Partial fill between recons - the filled part is no longer committed.
+    // Drawn from live first; an order placed since the last recon is still inflight.
+    fn fill_bid(&mut self, notional: f64) {
+        let from_live = notional.min(self.live_usdt);
+        self.live_usdt -= from_live;
+        self.inflight_usdt = (self.inflight_usdt - (notional - from_live)).max(0.0);
+    }
+    fn fill_ask(&mut self, size: f64) {
+        let from_live = size.min(self.live_sol);
+        self.live_sol -= from_live;
+        self.inflight_sol = (self.inflight_sol - (size - from_live)).max(0.0);
+    }
+    
+    // V10.15: Post-recon invariant - live commitments must equal what the final
+    // level states hold. Corrects and returns the (usdt, sol) drift if any.
+    fn repair_from_levels(&mut self, level_orders: &HashMap<i32, (LevelOrderState, LevelOrderState)>) -> Option<(f64, f64)> {
+        let (mut usdt, mut sol) = (0.0, 0.0);
+        for (bid, ask) in level_orders.values() {
+            if let LevelOrderState::Live { price, remaining_size, .. } = bid { usdt += remaining_size * price; }
+            if let LevelOrderState::Live { remaining_size, .. } = ask { sol += remaining_size; }
+        }
+        let drift = (self.live_usdt - usdt, self.live_sol - sol);
+        if drift.0.abs() < 0.01 && drift.1.abs() < 1e-4 {
+            return None;
+        }
+        self.live_usdt = usdt;
+        self.live_sol = sol;
+        Some(drift)
+    }
+}
+
+// V10.15: Live order already on the desired price tick - requoting would
+// cancel and re-place at the same price
+fn is_matched(state: &LevelOrderState, desired: f64, tick: f64) -> bool {
+    matches!(state, LevelOrderState::Live { price, .. } if (price - desired).abs() < tick * 0.5)
+}
+
+// V10.15: clientOid suffix is a per-order sequence, not the tick counter - a
+// level cancelled and re-placed (same tick or later) must never reuse an OID
+struct OidSeq(u64);
+
+impl OidSeq {
+    /// Seed from wall-clock ms so a restart never reissues a previous run's OIDs
+    fn new(seed: u64) -> Self { Self(seed) }
+    fn next(&mut self, is_bid: bool, key: i32) -> String {
+        self.0 += 1;
+        format!("{}{}_{}", if is_bid { 'b' } else { 'a' }, key, self.0)
+    }
+}
+
+// V10.15: Equal-fill-probability ladder. Fill intensity decays as
+// exp(-d / s), s = sigma scaled to the horizon in bps. Fill probability is
+// spread evenly from the first to the last table level, so distances are
+// -s * ln(p): dense near mid where fills happen, sparse further out.
+fn equal_fill_prob_spacing(sigma: f64, horizon_secs: f64, first_bps: f64, last_bps: f64, n: usize) -> Vec<f64> {
+    const SECS_PER_YEAR: f64 = 365.0 * 86400.0;
+    // Keep exp(-last / s) representable
+    let s = (sigma * (horizon_secs / SECS_PER_YEAR).sqrt() * 10000.0).max(last_bps / 700.0).max(1e-6);
+    let (p0, pn) = ((-first_bps / s).exp(), (-last_bps / s).exp());
+    let mut out: Vec<f64> = Vec::with_capacity(n);
+    for i in 0..n {
+        let p = p0 + (pn - p0) * i as f64 / (n.max(2) - 1) as f64;
+        let d = (-s * p.ln()).clamp(first_bps, last_bps);
+        // Float noise can tie neighbours - keep the ladder strictly outward
+        out.push(out.last().map_or(d, |prev| d.max(prev + 0.01)));
+    }
+    out
+}
+
+// V10.15: Base (bid, ask) distance in bps per ladder level, starting from
+// `base` (table or equal-fill-prob spacing). In BookVwap mode far layers sit
+// where the book's VWAP for a growing notional lies, measured from the book
+// mid and never inside the last near layer; past what the book can fill a
+// side keeps its base bps. Each side stays ordered outward.
+fn layer_distances(lc: &LevelsConfig, base: &[f64], book: Option<&OrderBook>) -> Vec<(f64, f64)> {
+    let book_mid = book.and_then(|b| b.mid_price()).filter(|_| lc.far_mode == FarLayerMode::BookVwap);
+    let (Some(book), Some(mid)) = (book, book_mid) else {
+        return base.iter().map(|bps| (*bps, *bps)).collect();
+    };
+    let floor = lc.far_from_index.checked_sub(1).and_then(|i| base.get(i)).copied().unwrap_or(0.0);
+    let (mut last_bid, mut last_ask) = (floor, floor);
+    base.iter().enumerate().map(|(i, bps)| {
+        if i < lc.far_from_index {
+            return (*bps, *bps);
+        }
+        let notional = lc.far_vwap_step_usd * (i - lc.far_from_index + 1) as f64;
+        let dist = |side: BookSide| book.vwap_for_notional(side, notional)
+            .map_or(*bps, |px| (px - mid).abs() / mid * 10000.0);
+        last_bid = dist(BookSide::Bid).max(last_bid);
+        last_ask = dist(BookSide::Ask).max(last_ask);
+        (last_bid, last_ask)
+    }).collect()
+}
+
+// V10.15: Level key from our clientOid ("b{key}_{seq}" / "a{key}_{seq}")
+fn level_key_from_oid(client_oid: &str) -> Option<(bool, i32)> {
+    let is_bid = match client_oid.chars().next()? { 'b' => true, 'a' => false, _ => return None };
+    let (key, _) = client_oid[1..].split_once('_')?;
+    Some((is_bid, key.parse().ok()?))
+}
+
+// V10.15: Put resting orders from a previous run back into their level slots.
+// Returns the ids that couldn't be adopted (foreign, unknown level, duplicate).
+fn adopt_orders(orders: &[ActiveOrder], level_orders: &mut HashMap<i32, (LevelOrderState, LevelOrderState)>) -> Vec<String> {
+    let mut rejected = Vec::new();
+    for o in orders {
+        let slot = level_key_from_oid(&o.client_oid)
+            .filter(|(is_bid, _)| *is_bid == (o.side == Side::Buy))
+            .and_then(|(is_bid, key)| level_orders.get_mut(&key).map(|(b, a)| if is_bid { b } else { a }));
+        match slot {
+            Some(state) if state.is_empty() && o.cancel_exist => {
+                *state = LevelOrderState::CancelPending { order_id: o.order_id.clone(), price: o.price, sent_at: Instant::now(), attempts: 0 };
+            }
+            Some(state) if state.is_empty() => {
+                *state = LevelOrderState::Live { order_id: o.order_id.clone(), price: o.price, remaining_size: o.size, placed_at: Instant::now() };
+            }
+            _ => rejected.push(o.order_id.clone()),
+        }
+    }
+    rejected
+}
+
+// V10.15: Level refresh threshold, never below the configured floor
+fn refresh_threshold(index: usize, min_refresh_bps: f64) -> f64 {
+    levels()[index].1.max(min_refresh_bps)
+}
+
+// V10.15: Distance of a resting price from its refresh target, in bps
+fn drift_bps(price: f64, target: f64) -> f64 {
+    ((price - target).abs() / target) * 10000.0
+}
+
+// V10.15: Far layers go out GTT when enabled - they expire server-side and
+// recon sees them gone (Empty), so the loop just re-places them
+fn level_time_in_force(index: usize, lc: &LevelsConfig) -> (&'static str, Option<u64>) {
+    if lc.gtt_cancel_after_secs > 0 && index >= lc.gtt_from_index {
+        ("GTT", Some(lc.gtt_cancel_after_secs))
+    } else {
+        ("GTC", None)
+    }
+}
+
+// V10.15: Drift (bps) that triggers a refresh cancel; GTT layers wait for
+// the severe-stale bound and otherwise just age out
+fn refresh_trigger_bps(index: usize, lc: &LevelsConfig) -> f64 {
+    let thresh = refresh_threshold(index, lc.min_refresh_bps);
+    if level_time_in_force(index, lc).1.is_some() { thresh * 2.0 } else { thresh }
+}
+
+// V10.15: One order operation from the level loop. Planned sequentially,
+// executed with bounded concurrency, applied after all complete.
+#[derive(Debug, Clone)]
+enum LevelOp {
+    Place { key: i32, is_bid: bool, price: f64, size: f64, req: WsOrderRequest },
+    Cancel { key: i32, is_bid: bool, order_id: String, price: f64 },
+    /// Move a live order in place (HF alter); `Placed` carries its new orderId
+    Modify { key: i32, is_bid: bool, order_id: String, price: f64, size: f64 },
+    /// V10.12 REST backup for a severely stale order; outcome ignored
+    RestCancel { order_id: String },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum OpOutcome {
+    Placed(String),
+    Sent,
+    Failed,
+    /// Post-only reject - the price would have crossed
+    WouldCross,
+    /// Not sent - order entry frozen
+    Held,
+    /// Rejected - the symbol no longer exists (delisted)
+    NotTrading,
+    /// Not sent - the mid it was priced from would be too old when it landed
+    Stale,
+}
+
+// V10.15: Next move for a live order `price` whose level price is now
+// `target` - None once it's there
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Reprice {
+    CancelReplace,
+    Modify(f64),
+}
+
+fn reprice_step(mode: RepriceMode, price: f64, target: f64, tick: f64, max_gap_ticks: u32) -> Option<Reprice> {
+    let (at, gap) = ((price / tick).round(), (target / tick).round() - (price / tick).round());
+    if gap == 0.0 {
+        return None;
+    }
+    Some(match mode {
+        RepriceMode::CancelReplace => Reprice::CancelReplace,
+        RepriceMode::Modify => Reprice::Modify((target / tick).round() * tick),
+        // Too far behind a fast move - walking would leave it lagging for ticks
+        RepriceMode::WalkOneTick if max_gap_ticks > 0 && gap.abs() > max_gap_ticks as f64 => Reprice::CancelReplace,
+        RepriceMode::WalkOneTick => Reprice::Modify((at + gap.signum()) * tick),
+    })
+}
+
+// V10.15: Latency budget for a tick's placements - the mid they were priced
+// from, the expected place latency and the most that may add up to
+#[derive(Debug, Clone, Copy)]
+struct MidBudget {
+    mid_at: Instant,
+    est_latency: Duration,
+    budget: Duration,
+}
+
+impl MidBudget {
+    /// Would an order sent at `now` land on a mid older than the budget
+    fn exceeded(&self, now: Instant) -> bool {
+        now.duration_since(self.mid_at) + self.est_latency > self.budget
+    }
+}
+
+// V10.15: REST twin of a WS place request
+fn rest_order(req: &WsOrderRequest) -> Result<exchange::OrderRequest> {
+    use exchange::{OrderType, TimeInForce};
+    Ok(exchange::OrderRequest {
+        client_oid: req.client_oid.clone(),
+        side: req.side.parse()?,
+        order_type: OrderType::Limit,
+        symbol: req.symbol.clone(),
+        price: req.price.clone(),
+        size: req.size.clone(),
+        time_in_force: Some(match req.time_in_force.as_deref() {
+            Some("GTT") => TimeInForce::GTT,
+            Some("IOC") => TimeInForce::IOC,
+            Some("FOK") => TimeInForce::FOK,
+            _ => TimeInForce::GTC,
+        }),
+        post_only: req.post_only,
+        hidden: None,
+        iceberg: None,
+        stp: req.stp,
+        cancel_after: req.cancel_after,
+    })
+}
+
+// V10.15: WS send queue backed up - place the tick's orders in HF batch REST
+// calls instead; size guard as on the WS path
+async fn place_batch_via_rest(rest: &KucoinRestClient, sym: &SymbolInfo, places: Vec<LevelOp>) -> Vec<(LevelOp, OpOutcome)> {
+    let mut results = Vec::with_capacity(places.len());
+    let (mut sendable, mut orders) = (Vec::new(), Vec::new());
+    for op in places {
+        match &op {
+            LevelOp::Place { req, .. } if sym.check_size(&req.size).is_ok() => match rest_order(req) {
+                Ok(order) => {
+                    orders.push(order);
+                    sendable.push(op);
+                }
+                Err(e) => {
+                    warn!("[GUARD] Not sending {} @ {}: {}", req.client_oid, req.price, e);
+                    results.push((op, OpOutcome::Failed));
+                }
+            },
+            LevelOp::Place { req, .. } => {
+                warn!("[GUARD] Not sending {} {} @ {}: size {}", req.side, req.client_oid, req.price, req.size);
+                results.push((op, OpOutcome::Failed));
+            }
+            _ => results.push((op, OpOutcome::Failed)),
+        }
+    }
+    match rest.place_orders_batch(&orders).await {
+        Ok(placed) => {
+            for (op, r) in sendable.into_iter().zip(placed) {
+                let outcome = match r {
+                    Ok(id) => OpOutcome::Placed(id),
+                    Err(e) if is_post_only_cross_msg(&e.to_string()) => OpOutcome::WouldCross,
+                    Err(e) => {
+                        debug!("[BATCH] Place rejected: {}", e);
+                        OpOutcome::Failed
+                    }
+                };
+                results.push((op, outcome));
+            }
+        }
+        Err(e) => {
+            warn!("[BATCH] REST batch place failed: {}", e);
+            results.extend(sendable.into_iter().map(|op| (op, OpOutcome::Failed)));
+        }
+    }
+    results
+}
+
+// V10.15: Signed size the reduction paths trade to bring `inv` back to
+// `target` (+ = sell); nothing once it is within `band` of it
+fn reduction_to_target(inv: f64, target: f64, band: f64) -> f64 {
+    let excess = inv - target;
+    if excess.abs() <= band { 0.0 } else { excess }
+}
+
+// V10.15: Mid a flatten is priced from; `Degraded` takes the wider buffer
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ExitAnchor {
+    Mid(f64),
+    Degraded(f64),
+}
+
+// With the KuCoin book running, its mid - or, if it's stale or empty, the
+// reference mid as a degraded exit. Without the book feed, the ladder mid
+// as before.
+fn exit_anchor(book: Option<&OrderBook>, stale_ms: u64, ladder_mid: f64, ref_mid: f64) -> ExitAnchor {
+    match book {
+        None => ExitAnchor::Mid(ladder_mid),
+        Some(b) => match b.mid_price().filter(|_| !b.is_stale(stale_ms)) {
+            Some(mid) => ExitAnchor::Mid(mid),
+            None => ExitAnchor::Degraded(ref_mid),
+        },
+    }
+}
+
+async fn current_exit_anchor(book: &Option<Arc<RwLock<OrderBook>>>, stale_ms: u64, ladder_mid: f64, ref_mid: f64) -> ExitAnchor {
+    match book {
+        Some(b) => exit_anchor(Some(&*b.read().await), stale_ms, ladder_mid, ref_mid),
+        None => exit_anchor(None, stale_ms, ladder_mid, ref_mid),
+    }
+}
+
+// V10.15: clientOid prefix of flatten orders - a rebate-only one rests, and
+// recon must not take it for an orphan
+const FLATTEN_OID_PREFIX: &str = "flat_";
+
+// V10.15: One order taking `inv` back to `risk.inv_target` at the anchor's
+// mid - IOC through the touch, or post-only at the touch under rebate-only.
+// Err is the alert text.
+async fn flatten_inventory(ws: &WsOrderClientV2, sym: &SymbolInfo, cfg: &config::Config, inv: f64, anchor: ExitAnchor) -> Result<String, String> {
+    let excess = reduction_to_target(inv, cfg.risk.inv_target, cfg.risk.flatten_band_sol);
+    if excess == 0.0 {
+        return Ok(format!("Inventory {:.4} within {} of target {} - nothing to flatten",
+            inv, cfg.risk.flatten_band_sol, cfg.risk.inv_target));
+    }
+    let is_sell = excess > 0.0;
+    let size = truncate_to_increment(excess.abs(), sym.base_increment);
+    let (mid, slippage_bps, via) = match anchor {
+        ExitAnchor::Mid(mid) => (mid, cfg.risk.flatten_slippage_bps, ""),
+        ExitAnchor::Degraded(mid) => (mid, cfg.risk.flatten_fallback_slippage_bps, " (degraded: reference mid)"),
+    };
+    if mid <= 0.0 || size < sym.base_min_size {
+        return Err("no mid or size below minimum - flatten manually".into());
+    }
+    if matches!(anchor, ExitAnchor::Degraded(_)) {
+        warn!("[RISK] KuCoin book stale or empty - pricing flatten off reference mid {:.4}, {}bps buffer", mid, slippage_bps);
+    }
+    // V10.15: Rebate-only never takes - rest post-only at the touch instead
+    let (px, tif, post_only) = if cfg.quoting.rebate_only {
+        (passive_price(is_sell, mid, sym), "GTC", true)
+    } else {
+        (marketable_price(is_sell, mid, slippage_bps, sym), "IOC", false)
+    };
+    let r = place_order_checked(ws, sym, WsOrderRequest {
+        symbol: symbol().name.clone(), side: if is_sell { "sell" } else { "buy" }.into(),
+        price: sym.price_str(px), size: sym.size_str(size),
+        client_oid: format!("{}{}", FLATTEN_OID_PREFIX, uuid::Uuid::new_v4().simple()),
+        order_type: "limit".into(), time_in_force: Some(tif.into()),
+        post_only: Some(post_only), stp: cfg.quoting.stp, cancel_after: None
+    }).await;
+    match r {
+        Ok(r) if r.success => Ok(format!("Flatten {} {} {} @ {:.4} sent{}", tif, if is_sell { "sell" } else { "buy" }, size, px, via)),
+        Ok(r) => Err(format!("flatten rejected: {:?} {:?}", r.code, r.msg)),
+        Err(e) => Err(format!("flatten failed: {}", e)),
+    }
+}
+
+// V10.15: Post-only price stepped clear of the opposite touch - a bid to a
+// tick under the best ask, an ask to a tick over the best bid. No touch (a
+// would-cross reject) = one tick. None if no step is needed or it would take
+// more than `max_ticks`.
+fn post_only_backoff(is_bid: bool, price: f64, touch: Option<f64>, tick: f64, max_ticks: u32) -> Option<f64> {
+    let p = (price / tick).round();
+    let steps = match touch {
+        Some(t) if is_bid => p - (t / tick).round() + 1.0,
+        Some(t) => (t / tick).round() - p + 1.0,
+        None => 1.0,
+    };
+    let dir = if is_bid { -1.0 } else { 1.0 };
+    (steps > 0.0 && steps <= max_ticks as f64).then_some((p + dir * steps) * tick)
+}
+
+// V10.15: The retry for a would-cross placement, a tick further from the book.
+// It's a new order, so it gets its own clientOid - the rejected one's plus
+// "r", which keeps the level prefix for adoption.
+fn backed_off_place(op: &LevelOp, sym: &SymbolInfo, max_ticks: u32) -> Option<LevelOp> {
+    let LevelOp::Place { key, is_bid, price, size, req } = op else { return None };
+    let to = post_only_backoff(*is_bid, *price, None, sym.price_increment, max_ticks)?;
+    Some(LevelOp::Place {
+        key: *key, is_bid: *is_bid, price: to, size: *size,
+        req: WsOrderRequest { price: sym.price_str(to), client_oid: format!("{}r", req.client_oid), ..req.clone() },
+    })
+}
+
+async fn send_place(ws: &WsOrderClientV2, sym: &SymbolInfo, req: &WsOrderRequest) -> OpOutcome {
+    match place_order_checked(ws, sym, req.clone()).await {
+        Ok(r) if r.success => r.order_id.map_or(OpOutcome::Failed, OpOutcome::Placed),
+        Ok(r) if r.is_post_only_cross() => OpOutcome::WouldCross,
+        Ok(r) if r.is_symbol_not_trading() => OpOutcome::NotTrading,
+        _ => OpOutcome::Failed,
+    }
+}
+
+async fn execute_level_op(
+    ws: &WsOrderClientV2, rest: Option<&KucoinRestClient>, auth: &KucoinAuth, sym: &SymbolInfo,
+    mid_budget: Option<MidBudget>, backoff_ticks: u32, op: LevelOp,
+) -> (LevelOp, OpOutcome) {
+    let outcome = match &op {
+        LevelOp::Place { .. } if mid_budget.is_some_and(|b| b.exceeded(Instant::now())) => OpOutcome::Stale,
+        LevelOp::Place { req, .. } => send_place(ws, sym, req).await,
+        LevelOp::Cancel { order_id, .. } => match ws.cancel_order(WsCancelRequest {
+            symbol: symbol().name.clone(), order_id: Some(order_id.clone()), client_oid: None
+        }).await {
+            // WS cancel sent - CancelPending regardless of r.success; recon confirms
+            Ok(_) => OpOutcome::Sent,
+            Err(_) => OpOutcome::Failed,
+        },
+        LevelOp::RestCancel { order_id } => {
+            rest_cancel_order(auth, order_id).await;
+            OpOutcome::Sent
+        }
+        LevelOp::Modify { order_id, price, size, .. } => match rest {
+            Some(rest) => match rest.modify_order(&symbol().name, order_id, &sym.price_str(*price), &sym.size_str(*size)).await {
+                Ok(id) => OpOutcome::Placed(id),
+                Err(e) => {
+                    debug!("[REPRICE] Modify {} rejected: {}", order_id, e);
+                    OpOutcome::Failed
+                }
+            },
+            None => OpOutcome::Failed,
+        },
+    };
+    // V10.15: Would cross - once more a tick further out rather than wait a tick
+    // to fail the same way, unless the first attempt used up the mid's latency
+    // budget. A failed retry reports the original op, whose reservation is the
+    // one to release.
+    let budget_left = !mid_budget.is_some_and(|b| b.exceeded(Instant::now()));
+    if let (OpOutcome::WouldCross, true, Some(retry)) = (&outcome, budget_left, backed_off_place(&op, sym, backoff_ticks)) {
+        let LevelOp::Place { req, .. } = &retry else { unreachable!() };
+        debug!("[POST-ONLY] {} {} would cross - retrying at {}", req.side, req.client_oid, req.price);
+        return match send_place(ws, sym, req).await {
+            placed @ OpOutcome::Placed(_) => (retry, placed),
+            retried => (op, retried),
+        };
+    }
+    (op, outcome)
+}
+
+// Up to `concurrency` ops in flight (0 or 1 = one at a time, in plan order)
+async fn run_level_ops<F, Fut>(ops: Vec<LevelOp>, concurrency: usize, exec: F) -> Vec<(LevelOp, OpOutcome)>
+where
+    F: Fn(LevelOp) -> Fut,
+    Fut: std::future::Future<Output = (LevelOp, OpOutcome)>,
+{
+    futures_util::stream::iter(ops).map(exec).buffer_unordered(concurrency.max(1)).collect().await
+}
+
+fn apply_level_op(
+    level_orders: &mut HashMap<i32, (LevelOrderState, LevelOrderState)>,
+    commitments: &mut CommitmentTracker,
+    op: &LevelOp,
+    outcome: &OpOutcome,
+) {
+    fn side(
+        level_orders: &mut HashMap<i32, (LevelOrderState, LevelOrderState)>, key: i32, is_bid: bool,
+    ) -> &mut LevelOrderState {
+        let slot = level_orders.entry(key).or_insert((LevelOrderState::Empty, LevelOrderState::Empty));
+        if is_bid { &mut slot.0 } else { &mut slot.1 }
+    }
+    match (op, outcome) {
+        (LevelOp::Place { key, is_bid, price, size, .. }, OpOutcome::Placed(oid)) => {
+            *side(level_orders, *key, *is_bid) = LevelOrderState::Live {
+                order_id: oid.clone(), price: *price, remaining_size: *size, placed_at: Instant::now(),
+            };
+        }
+        // Reservation made at planning time
+        (LevelOp::Place { is_bid: true, price, size, .. }, _) => commitments.release_inflight_bid(size * price),
+        (LevelOp::Place { is_bid: false, size, .. }, _) => commitments.release_inflight_ask(*size),
+        (LevelOp::Modify { key, is_bid, price, size, .. }, OpOutcome::Placed(oid)) => {
+            let state = side(level_orders, *key, *is_bid);
+            if let LevelOrderState::Live { price: old_price, remaining_size, .. } = state {
+                if *is_bid {
+                    commitments.rebook_bid(*remaining_size * *old_price, size * price);
+                } else {
+                    commitments.rebook_ask(*remaining_size, *size);
+                }
+            }
+            *state = LevelOrderState::Live {
+                order_id: oid.clone(), price: *price, remaining_size: *size, placed_at: Instant::now(),
+            };
+        }
+        (LevelOp::Cancel { key, is_bid, order_id, price }, OpOutcome::Sent) => {
+            *side(level_orders, *key, *is_bid) = LevelOrderState::CancelPending {
+                order_id: order_id.clone(), price: *price, sent_at: Instant::now(), attempts: 1,
+            };
+        }
+        _ => {}
+    }
+}
+
+// V10.15: How much longer a Live order must rest before a refresh may cancel
+// it; None once it has, or for a state that isn't Live
+fn rest_remaining(state: &LevelOrderState, min_rest: Duration, now: Instant) -> Option<Duration> {
+    let LevelOrderState::Live { placed_at, .. } = state else { return None };
+    min_rest.checked_sub(now.duration_since(*placed_at)).filter(|d| !d.is_zero())
+}
+
+// V10.15: One live level order's refresh check. Anti-spoofing min rest holds
+// refresh cancels only - a risk cancel (trend protect) goes regardless. An
+// adopted order's first check waives min rest and hysteresis; a walk step
+// waives hysteresis only.
+struct RefreshCheck {
+    bps_diff: f64,
+    refresh_over: f64,
+    adopted: bool,
+    walk_on: bool,
+    risk_cancel: bool,
+    hysteresis_ok: bool,
+}
+
+#[derive(Debug, PartialEq)]
+enum RefreshVerdict {
+    Keep,
+    Deferred(Duration),
+    Held,
+    Refresh,
+}
+
+impl RefreshCheck {
+    fn verdict(&self, state: &LevelOrderState, min_rest: Duration, now: Instant) -> RefreshVerdict {
+        let over = self.bps_diff > self.refresh_over;
+        if self.risk_cancel {
+            return RefreshVerdict::Refresh;
+        }
+        if !over && !self.walk_on {
+            return RefreshVerdict::Keep;
+        }
+        if let Some(wait) = rest_remaining(state, min_rest, now).filter(|_| !self.adopted) {
+            return RefreshVerdict::Deferred(wait);
+        }
+        let settled = self.walk_on || self.adopted || !state.is_live() || self.hysteresis_ok;
+        if over && !settled { RefreshVerdict::Held } else { RefreshVerdict::Refresh }
+    }
+}
+
+// V10.15: Merge mode - a live order placed for another number of merged levels
+// than its side carries now is refreshed, so the kept order takes on the
+// merged size (or gives it back when the merge dissolves)
+fn merge_resize_due(placed_lots: &HashMap<(i32, bool), u32>, key: i32, is_bid: bool, q: &SideQuote) -> bool {
+    !q.merged() && placed_lots.get(&(key, is_bid)).copied().unwrap_or(1) != q.lots
+}
+
+// V10.15: Split planned ops into (send, held) for the entry breaker - cancels
+// always go; frozen holds every placement, probing lets the first one through
+fn hold_placements(ops: Vec<LevelOp>, admit: Admit) -> (Vec<LevelOp>, Vec<LevelOp>) {
+    let mut allowed = match admit {
+        Admit::All => return (ops, Vec::new()),
+        Admit::One => 1,
+        Admit::None => 0,
+    };
+    ops.into_iter().partition(|op| match op {
+        LevelOp::Place { .. } if allowed > 0 => { allowed -= 1; true }
+        LevelOp::Place { .. } => false,
+        _ => true,
+    })
+}
+
+// V10.15: SIGINT mid-tick - split a REST batch into (send, held); once the
+// flag is up nothing is sent, so no batch lands after the cancel-all
+fn hold_batch_on_shutdown(batch: Vec<LevelOp>, shutdown: &Shutdown) -> (Vec<LevelOp>, Vec<LevelOp>) {
+    batch.into_iter().partition(|_| !shutdown.is_set())
+}
+
+// V10.15: The SIGINT task's work - WS entry closes before the flag goes up,
+// so a WS placement racing the signal is refused by the client itself
+fn begin_shutdown(ws: &WsOrderClientV2, shutdown: &Shutdown) {
+    ws.close_entry();
+    shutdown.trigger();
+}
+
+// V10.15: Rebate-only - extra bps per (level key, is_bid). A would-cross
+// reject widens that side by `step` (the retry waits for the next tick);
+// a successful place clears it.
+fn update_cross_widen(widen: &mut HashMap<(i32, bool), f64>, op: &LevelOp, outcome: &OpOutcome, step: f64) {
+    let LevelOp::Place { key, is_bid, .. } = op else { return };
+    match outcome {
+        OpOutcome::WouldCross => *widen.entry((*key, *is_bid)).or_insert(0.0) += step,
+        OpOutcome::Placed(_) => { widen.remove(&(*key, *is_bid)); }
+        _ => {}
+    }
+}
+
+// V10.15: Sync a Live level with its exchange order. An order the exchange
+// is already cancelling (`cancelExist`) becomes CancelPending so it isn't
+// refreshed or counted as resting; otherwise the remaining size follows the
+// partial fills. Returns the size still committed.
+fn sync_live(state: &mut LevelOrderState, o: &ActiveOrder) -> f64 {
+    match state {
+        LevelOrderState::Live { .. } if o.cancel_exist => {
+            *state = LevelOrderState::CancelPending { order_id: o.order_id.clone(), price: o.price, sent_at: Instant::now(), attempts: 0 };
+            0.0
+        }
+        LevelOrderState::Live { remaining_size, .. } => {
+            *remaining_size = o.size;
+            o.size
+        }
+        _ => 0.0,
+    }
+}
+
+// V10.3: Untracked exchange orders to cancel this recon, at most `budget`.
+// Tracked orders, latency probes, scratch exits, flatten orders and orders
+// cancelled in the last few seconds (still visible on the exchange) are
+// never flagged.
+fn detect_orphans(
+    active_orders: &[ActiveOrder],
+    tracked_ids: &HashSet<String>,
+    recently_cancelled: &HashMap<String, Instant>,
+    budget: usize,
+) -> Vec<String> {
+    active_orders.iter()
+        .filter(|o| !tracked_ids.contains(&o.order_id)
+            && !recently_cancelled.contains_key(&o.order_id)
+            && !o.client_oid.starts_with(latency_probe::PROBE_OID_PREFIX)
+            && !o.client_oid.starts_with(scratch::SCRATCH_OID_PREFIX)
+            && !o.client_oid.starts_with(FLATTEN_OID_PREFIX))
+        .take(budget)
+        .map(|o| o.order_id.clone())
+        .collect()
+}
+
+// V10.15: Private-feed canceled/done for an order we're cancelling - free the
+// level now instead of waiting for recon to see it gone. Live orders are left
+// to the fill/recon path. Returns whether a level was cleared.
+fn confirm_cancel(level_orders: &mut HashMap<i32, (LevelOrderState, LevelOrderState)>, order_id: &str) -> bool {
+    for (bid_state, ask_state) in level_orders.values_mut() {
+        for state in [bid_state, ask_state] {
+            let cancelling = match state {
+                LevelOrderState::CancelPending { order_id: oid, .. } | LevelOrderState::CancelStuck { order_id: oid, .. } => oid == order_id,
+                _ => false,
+            };
+            if cancelling {
+                *state = LevelOrderState::Empty;
+                return true;
+            }
+        }
+    }
+    false
+}
+
+// V10.15: Warm cancel victim - the live order on one side furthest from mid,
+// provided it sits further out than `than` (a lower bid / higher ask).
+// Levels in `skip` already have a cancel planned this tick.
+fn furthest_live(
+    level_orders: &HashMap<i32, (LevelOrderState, LevelOrderState)>,
+    is_bid: bool,
+    than: f64,
+    skip: &HashSet<(i32, bool)>,
+) -> Option<(i32, String, f64)> {
+    level_orders.iter()
+        .filter(|(key, _)| !skip.contains(&(**key, is_bid)))
+        .filter_map(|(key, (b, a))| match if is_bid { b } else { a } {
+            LevelOrderState::Live { order_id, price, .. } => Some((*key, order_id.clone(), *price)),
+            _ => None,
+        })
+        .filter(|(_, _, price)| if is_bid { *price < than } else { *price > than })
+        .min_by(|x, y| if is_bid { x.2.total_cmp(&y.2) } else { y.2.total_cmp(&x.2) })
+}
+
+// V10.15: Per-side cap for an empty level side about to place. Below `cap` it
+// places; at the cap, with warm cancel on, the live order furthest out (past
+// `price`) goes so this level can take its slot on a later tick.
+#[derive(Debug, PartialEq)]
+enum SideSlot {
+    Free,
+    WarmCancel(i32, String, f64),
+    Full,
+}
+
+fn side_slot(
+    level_orders: &HashMap<i32, (LevelOrderState, LevelOrderState)>,
+    is_bid: bool,
+    price: f64,
+    count: usize,
+    cap: usize,
+    warm_cancel: bool,
+    skip: &HashSet<(i32, bool)>,
+) -> SideSlot {
+    if count < cap {
+        return SideSlot::Free;
+    }
+    match furthest_live(level_orders, is_bid, price, skip).filter(|_| warm_cancel) {
+        Some((key, order_id, price)) => SideSlot::WarmCancel(key, order_id, price),
+        None => SideSlot::Full,
+    }
+}
+
+// V10.15: Apply a fill to the level holding `order_id`, shrinking its remaining
+// size and commitment. Returns false if the order isn't one of our live levels.
+fn apply_level_fill(
+    level_orders: &mut HashMap<i32, (LevelOrderState, LevelOrderState)>,
+    commitments: &mut CommitmentTracker,
+    order_id: &str,
+    filled: f64,
+) -> bool {
+    for (bid_state, ask_state) in level_orders.values_mut() {
+        for (state, is_bid) in [(bid_state, true), (ask_state, false)] {
+            let LevelOrderState::Live { order_id: oid, price, remaining_size, .. } = state else { continue };
+            if oid != order_id { continue; }
+            let filled = filled.min(*remaining_size);
+            if is_bid { commitments.fill_bid(filled * *price); } else { commitments.fill_ask(filled); }
+            *remaining_size -= filled;
+            if *remaining_size <= 1e-9 {
+                // Fully filled - recon would find it gone anyway
+                *state = LevelOrderState::Empty;
+            }
+            return true;
+        }
+    }
+    false
+}
+
+// V10.15: Reservation price - shift the whole ladder's anchor away from the
+// heavy side (long -> lower, short -> higher). Returns (shift_bps, shifted mid).
+fn reservation_mid(mid: f64, inv: f64, gamma: f64, sigma: f64, max_bps: f64) -> (f64, f64) {
+    let shift_bps = (inv * gamma * sigma * sigma * 10000.0).clamp(-max_bps, max_bps);
+    (shift_bps, mid * (1.0 - shift_bps / 10000.0))
+}
+
+// V10.15: Short-horizon forecast anchor - lean the ladder along the drift
+// momentum measures. Returns (shift_bps, forecast_mid), shift capped at ±max_bps.
+fn forecast_mid(mid: f64, momentum: f64, k: f64, max_bps: f64) -> (f64, f64) {
+    let shift_bps = (momentum * k * 10000.0).clamp(-max_bps, max_bps);
+    (shift_bps, mid * (1.0 + shift_bps / 10000.0))
+}
+
+// V10.15: Basis asymmetry - KuCoin rich vs Binance (basis > 0) should revert
+// down, so asks tighten and bids widen by the same amount; cheap is the mirror.
+// Same sign convention as the inventory skew (positive = tighter asks).
+fn basis_skew_bps(kucoin_mid: f64, ref_mid: f64, sensitivity: f64, max_bps: f64) -> f64 {
+    if kucoin_mid <= 0.0 || ref_mid <= 0.0 {
+        return 0.0;
+    }
+    let basis_bps = (kucoin_mid - ref_mid) / ref_mid * 10000.0;
+    (basis_bps * sensitivity).clamp(-max_bps, max_bps)
+}
+
+// V10.15: Last-resort price band - distance from mid on the correct side,
+// within [min_bps, max_bps]. Logs and returns false otherwise.
+fn within_band(is_bid: bool, price: f64, mid: f64, min_bps: f64, max_bps: f64) -> bool {
+    let dist_bps = if mid > 0.0 {
+        let away = if is_bid { mid - price } else { price - mid };
+        away / mid * 10000.0
+    } else { f64::NAN };
+    // Tolerate float noise at the edges
+    let ok = dist_bps >= min_bps - 1e-9 && dist_bps <= max_bps + 1e-9;
+    if !ok {
+        warn!("[BAND] Skipping {} @ {:.4}: {:.2}bps from mid {:.4} outside [{}, {}]",
+            if is_bid { "bid" } else { "ask" }, price, dist_bps, mid, min_bps, max_bps);
+    }
+    ok
 }
 
 // V10.3: Symmetric inventory gating functions
-fn can_place_bid(inv: f64, size: f64) -> bool { inv + size <= MAX_INV_SOL }
-fn can_place_ask(inv: f64, size: f64) -> bool { inv - size >= -MAX_INV_SOL }
-fn needs_cancel_bid(inv: f64, size: f64, skip_bids: bool) -> bool { skip_bids || inv + size > MAX_INV_SOL }
-fn needs_cancel_ask(inv: f64, size: f64) -> bool { inv - size < -MAX_INV_SOL }
+// V10.15: Size for the side that would grow |inv|
+fn inventory_size(base_sz: f64, abs_inv: f64, sizing: &SizingConfig, sym: &SymbolInfo) -> f64 {
+    match sizing.policy {
+        SizePolicy::Exponential => (base_sz * (ETA * abs_inv).exp()).max(0.01),
+        SizePolicy::Steps => {
+            let fraction = sizing.steps.iter()
+                .filter(|s| abs_inv >= s.inventory)
+                .fold(1.0_f64, |f, s| f.min(s.fraction.clamp(0.0, 1.0)));
+            // Whole lots, at least the lot count that covers the symbol minimum
+            let lots = (base_sz * fraction / sym.base_increment + 1e-9).floor();
+            let min_lots = (sym.base_min_size / sym.base_increment - 1e-9).ceil().max(1.0);
+            lots.max(min_lots) * sym.base_increment
+        }
+    }
+}
+
+// V10.15: Size times a fill-flow multiplier (<= 1), lot-aligned, never below the minimum
+fn scaled_size(size: f64, mult: f64, sym: &SymbolInfo) -> f64 {
+    if mult >= 1.0 {
+        return size;
+    }
+    truncate_to_increment(size * mult, sym.base_increment).max(sym.base_min_size)
+}
+
+// V10.15: Per-side A/B multipliers on (bid, ask) base distances
+fn side_spreads(bid_bps: f64, ask_bps: f64, qc: &QuotingConfig) -> (f64, f64) {
+    (bid_bps * qc.bid_spread_mult, ask_bps * qc.ask_spread_mult)
+}
+
+// V10.15: Size times a per-side A/B multiplier (either way), lot-aligned, never below the minimum
+fn side_size(size: f64, mult: f64, sym: &SymbolInfo) -> f64 {
+    if mult == 1.0 {
+        return size;
+    }
+    truncate_to_increment(size * mult, sym.base_increment).max(sym.base_min_size)
+}
+
+// V10.15: Size clamped to `max_usd` of notional at `price` (0 = off), lot-aligned,
+// never below the minimum; true when it was clamped
+fn cap_notional(size: f64, price: f64, max_usd: f64, sym: &SymbolInfo) -> (f64, bool) {
+    if max_usd <= 0.0 || price <= 0.0 || size * price <= max_usd {
+        return (size, false);
+    }
+    (truncate_to_increment(max_usd / price, sym.base_increment).max(sym.base_min_size), true)
+}
+
+// V10.15: (bid, ask) multipliers from near-touch KuCoin depth imbalance - the
+// side with the deeper book behind it (bids when imbalance > 0) grows by
+// coeff * (|imbalance| - threshold), capped at max_mult; the other stays 1
+fn book_boost_multipliers(imbalance: Option<f64>, sizing: &SizingConfig) -> (f64, f64) {
+    let Some(imb) = imbalance.filter(|_| sizing.book_boost_coeff > 0.0) else { return (1.0, 1.0) };
+    let excess = imb.abs() - sizing.book_boost_threshold;
+    if excess <= 0.0 {
+        return (1.0, 1.0);
+    }
+    let boost = (1.0 + sizing.book_boost_coeff * excess).min(sizing.book_boost_max_mult.max(1.0));
+    if imb > 0.0 { (boost, 1.0) } else { (1.0, boost) }
+}
+
+// V10.15: Cold-start inventory - the base balance over the configured
+// baseline; None when the saved FIFO already agrees with it
+fn cold_start_inventory(base_total: f64, baseline: f64, loaded_inv: f64) -> Option<f64> {
+    let inv = base_total - baseline;
+    ((inv - loaded_inv).abs() > 0.0001).then_some(inv)
+}
+
+fn can_place_bid(inv: f64, size: f64, max_inv: f64) -> bool { inv + size <= max_inv }
+fn can_place_ask(inv: f64, size: f64, max_inv: f64) -> bool { inv - size >= -max_inv }
+fn needs_cancel_bid(inv: f64, size: f64, skip_bids: bool, max_inv: f64) -> bool { skip_bids || inv + size > max_inv }
+fn needs_cancel_ask(inv: f64, size: f64, max_inv: f64) -> bool { inv - size < -max_inv }
 // V10.5: FIFO state persistence path
 const FIFO_STATE_FILE: &str = "fifo_state.json";
 
@@ -153,6 +1237,8 @@ struct PnL {
     lq: VecDeque<Entry>, sq: VecDeque<Entry>,
     buys: u64, sells: u64, spread: f64, reb: f64,
     matched: u64, wins: u64, losses: u64,
+    // V10.15: Taker fees paid (fills that crossed instead of resting)
+    fees: f64,
 }
 impl PnL {
     fn buy(&mut self, px: f64, sz: f64, r: f64) {
@@ -186,19 +1272,80 @@ impl PnL {
     fn inv(&self) -> f64 { 
         self.lq.iter().map(|e| e.sz).sum::<f64>() - self.sq.iter().map(|e| e.sz).sum::<f64>() 
     }
-    fn net(&self) -> f64 { self.spread + self.reb }
+    fn net(&self) -> f64 { self.spread + self.reb - self.fees }
     
-    // V10.5: Save FIFO state to disk
-    fn save(&self) {
-        let lq: Vec<SerEntry> = self.lq.iter().map(|e| SerEntry { px: e.px, sz: e.sz }).collect();
-        let sq: Vec<SerEntry> = self.sq.iter().map(|e| SerEntry { px: e.px, sz: e.sz }).collect();
-        let state = serde_json::json!({
-            "lq": lq, "sq": sq,
-            "buys": self.buys, "sells": self.sells,
-            "spread": self.spread, "reb": self.reb,
-            "matched": self.matched, "wins": self.wins, "losses": self.losses
-        });
-        if let Ok(json) = serde_json::to_string_pretty(&state) {
+    // V10.15: Open FIFO lots marked at `mid` - longs gain above their entry,
+    // shorts below. Kept apart from realized spread in the report.
+    fn open_mtm(&self, mid: f64) -> f64 {
+        self.lq.iter().map(|e| e.sz * (mid - e.px)).sum::<f64>()
+            + self.sq.iter().map(|e| e.sz * (e.px - mid)).sum::<f64>()
+    }
+    
+    // V10.15: Size-weighted entry of the open inventory (None when flat)
+    fn avg_entry(&self) -> Option<f64> {
+        let q = if self.inv() > 0.0 { &self.lq } else { &self.sq };
+        let sz: f64 = q.iter().map(|e| e.sz).sum();
+        (sz > 0.0001).then(|| q.iter().map(|e| e.sz * e.px).sum::<f64>() / sz)
+    }
+    
+    fn total(&self, mid: f64) -> f64 { self.net() + self.open_mtm(mid) }
+    
+    // V10.15: Replace the open lots with one lot of `inv` (short when
+    // negative) at `entry`; realized figures and counters are untouched
+    fn seed_inventory(&mut self, inv: f64, entry: f64) {
+        self.lq.clear();
+        self.sq.clear();
+        if inv > 0.0001 {
+            self.lq.push_back(Entry { px: entry, sz: inv });
+        } else if inv < -0.0001 {
+            self.sq.push_back(Entry { px: entry, sz: -inv });
+        }
+    }
+    
+    // V10.15: Book the exchange-reported fee as is - negative is a rebate
+    // credit, positive a fee paid, maker or taker. Without one, fall back to
+    // the model: REBATE bps for makers, `taker_bps` for takers.
+    fn record_fill(&mut self, fill: &PolledFill, taker_bps: f64) {
+        let (r, fee) = Self::fee_split(fill.fee_usdt(), fill, taker_bps);
+        self.fees += fee;
+        match fill.side {
+            Side::Buy => self.buy(fill.price, fill.size, r),
+            Side::Sell => self.sell(fill.price, fill.size, r),
+        }
+    }
+
+    // (rebate, fee) for a fill, from `reported` or else the model
+    fn fee_split(reported: Option<f64>, fill: &PolledFill, taker_bps: f64) -> (f64, f64) {
+        let notional = fill.size * fill.price;
+        match (reported, fill.liquidity) {
+            (Some(fee), _) if fee < 0.0 => (-fee, 0.0),
+            (Some(fee), _) => (0.0, fee),
+            (None, Liquidity::Maker) => (notional * REBATE / 10000.0, 0.0),
+            (None, Liquidity::Taker) => (0.0, notional * taker_bps / 10000.0),
+        }
+    }
+
+    // V10.15: A feed fill was booked on the model fee; the poll's row for it
+    // brings the exchange's, and the difference moves rebates and fees
+    fn correct_fee(&mut self, fill: &PolledFill, taker_bps: f64) {
+        let Some(reported) = fill.fee_usdt() else { return };
+        let (r0, f0) = Self::fee_split(None, fill, taker_bps);
+        let (r1, f1) = Self::fee_split(Some(reported), fill, taker_bps);
+        self.reb += r1 - r0;
+        self.fees += f1 - f0;
+    }
+    
+    // V10.5: Save FIFO state to disk
+    fn save(&self) {
+        let lq: Vec<SerEntry> = self.lq.iter().map(|e| SerEntry { px: e.px, sz: e.sz }).collect();
+        let sq: Vec<SerEntry> = self.sq.iter().map(|e| SerEntry { px: e.px, sz: e.sz }).collect();
+        let state = serde_json::json!({
+            "lq": lq, "sq": sq,
+            "buys": self.buys, "sells": self.sells,
+            "spread": self.spread, "reb": self.reb, "fees": self.fees,
+            "matched": self.matched, "wins": self.wins, "losses": self.losses
+        });
+        if let Ok(json) = serde_json::to_string_pretty(&state) {
             let _ = std::fs::write(FIFO_STATE_FILE, json);
         }
     }
@@ -227,6 +1374,7 @@ impl PnL {
                     matched: v["matched"].as_u64().unwrap_or(0),
                     wins: v["wins"].as_u64().unwrap_or(0),
                     losses: v["losses"].as_u64().unwrap_or(0),
+                    fees: v["fees"].as_f64().unwrap_or(0.0),
                 };
                 info!("[FIFO] Loaded state: inv={:.3} SOL, spread=${:.4}, reb=${:.4}", 
                     pnl.inv(), pnl.spread, pnl.reb);
@@ -238,36 +1386,113 @@ impl PnL {
     }
 }
 
-#[derive(Default)]
+// V10.15: Shutdown/drain PnL report - realized vs open inventory at mark
+fn log_final_report(pnl: &PnL, m: f64, runtime: Duration) {
+    let inv = pnl.inv();
+    info!("═══════════════════════════════════════════════════════════════");
+    info!("[SHUTDOWN] FINAL PnL REPORT");
+    info!("Runtime: {}s | Buys:{} Sells:{} | Matches:{}", 
+        runtime.as_secs(), pnl.buys, pnl.sells, pnl.matched);
+    match pnl.avg_entry() {
+        Some(entry) => info!("Inventory: {:.4} SOL (${:.2}) avg entry {:.4} mark {:.4}", inv, inv * m, entry, m),
+        None => info!("Inventory: flat"),
+    }
+    info!("REALIZED SPREAD: ${:.4} | REBATES: ${:.4} | FEES: -${:.4} | REALIZED NET: ${:.4}",
+        pnl.spread, pnl.reb, pnl.fees, pnl.net());
+    info!("OPEN INVENTORY MTM: ${:.4} | TOTAL: ${:.4}", pnl.open_mtm(m), pnl.total(m));
+    info!("═══════════════════════════════════════════════════════════════");
+}
+
 struct MarketData {
     mid: f64, ofi: f64, last_mid: f64, ewma_var: f64,
+    // V10.15: Last depth5 update that had depth behind it
+    ofi_at: Option<Instant>,
     // V10.5c: Weighted mid price (0.8 Binance + 0.2 KuCoin)
     kucoin_mid: f64,
-    price_history: VecDeque<(Instant, f64)>,
+    // V10.15: KuCoin-native book signals (None = feed off or stale)
+    kc_imbalance: Option<f64>,
+    kc_spread_bps: Option<f64>,
+    kc_book_mid: Option<f64>,
+    // V10.15: KuCoin (best bid, best ask) for the post-only backoff
+    kc_touch: Option<(f64, f64)>,
+    // V10.15: Last update of the KuCoin book behind kc_book_mid
+    kc_book_at: Option<Instant>,
+    // V10.15: Downsampled + length-capped (was every tick over the window)
+    price_history: PriceHistory,
+    // V10.15: Binance aggTrade aggressor flow (buy = buyer took liquidity)
+    trade_flow: FillFlow,
     // V10: Track actual update interval for correct sigma annualization
     last_update: Option<Instant>,
     update_interval_ms: f64,
+    // V10.15: Smoothed reference mid for the quoting anchor
+    anchor_ema: MidEma,
 }
 
 impl MarketData {
-    // V10.5c: Weighted fair mid - 80% Binance futures, 20% KuCoin spot
-    // Filters out "fake outs" where futures moves but spot doesn't
-    fn fair_mid(&self) -> f64 {
-        if self.kucoin_mid > 0.0 {
-            0.8 * self.mid + 0.2 * self.kucoin_mid
-        } else {
-            self.mid  // Fallback to Binance only if no KuCoin data
+    fn new(price_history: PriceHistory, trade_flow: FillFlow) -> Self {
+        Self {
+            mid: 0.0, ofi: 0.0, last_mid: 0.0, ewma_var: 0.0, ofi_at: None, kucoin_mid: 0.0,
+            kc_imbalance: None, kc_spread_bps: None, kc_book_mid: None, kc_touch: None, kc_book_at: None,
+            price_history, trade_flow, last_update: None, update_interval_ms: 0.0,
+            anchor_ema: MidEma::new(Duration::ZERO),
+        }
+    }
+    
+    fn with_anchor_half_life(mut self, half_life: Duration) -> Self {
+        self.anchor_ema = MidEma::new(half_life);
+        self
+    }
+    
+    // V10.15: KuCoin mid for `MidSource` - the live book, else the REST ticker
+    fn local_mid(&self) -> Option<f64> {
+        self.kc_book_mid.or((self.kucoin_mid > 0.0).then_some(self.kucoin_mid))
+    }
+    
+    // V10.15: When the inputs of the `source` mid were last updated - the older
+    // one for a blend. The REST ticker fallback isn't timed; the reference is.
+    fn mid_time(&self, source: MidSource) -> Option<Instant> {
+        match (source, self.kc_book_at) {
+            (MidSource::Local, Some(k)) => Some(k),
+            (MidSource::Blend { .. }, Some(k)) => self.last_update.map(|r| r.min(k)),
+            _ => self.last_update,
+        }
+    }
+    
+    // V10.15: An empty book decays the last OFI toward 0 rather than leaving
+    // it to hold a pause on
+    fn on_depth(&mut self, ofi: Option<f64>, now: Instant) {
+        match ofi {
+            Some(o) => {
+                self.ofi = o;
+                self.ofi_at = Some(now);
+            }
+            None => self.ofi *= OFI_EMPTY_DECAY,
+        }
+    }
+    
+    // V10.15: OFI, or 0 if no depth has been seen for `max_age` (zero = no limit)
+    fn fresh_ofi(&self, now: Instant, max_age: Duration) -> f64 {
+        match self.ofi_at {
+            Some(at) if max_age.is_zero() || now.duration_since(at) <= max_age => self.ofi,
+            _ => 0.0,
         }
     }
     
     fn update(&mut self) {
-        let now = Instant::now();
-        
+        self.update_at(Instant::now());
+    }
+    
+    fn update_at(&mut self, now: Instant) {
         // V10: Track actual update interval
         if let Some(last) = self.last_update {
             let elapsed_ms = now.duration_since(last).as_secs_f64() * 1000.0;
-            // EWMA of update interval for stable estimate
-            self.update_interval_ms = 0.9 * self.update_interval_ms + 0.1 * elapsed_ms;
+            // EWMA of update interval for stable estimate; V10.15: seeded with
+            // the first gap instead of decaying up from 0
+            self.update_interval_ms = if self.update_interval_ms > 0.0 {
+                0.9 * self.update_interval_ms + 0.1 * elapsed_ms
+            } else {
+                elapsed_ms
+            };
         }
         self.last_update = Some(now);
         
@@ -276,22 +1501,24 @@ impl MarketData {
             self.ewma_var = VOL_EWMA_LAMBDA * self.ewma_var + (1.0 - VOL_EWMA_LAMBDA) * ret * ret;
         }
         self.last_mid = self.mid;
-        self.price_history.push_back((now, self.mid));
-        let cutoff = now - Duration::from_secs(MOMENTUM_WINDOW_SECS);
-        while let Some((t, _)) = self.price_history.front() {
-            if *t < cutoff { self.price_history.pop_front(); } else { break; }
-        }
+        self.price_history.push(now, self.mid);
+        self.anchor_ema.update(self.mid, now);
     }
+    // V10: Annualized vol from the per-update EWMA variance. Assumes updates
+    // are evenly spaced at the average interval and their log returns are
+    // independent, so variance scales with the update count: var * updates/day
+    // * 365. Uses 100ms until an interval is measured; V10.15: and never less
+    // than SIGMA_MIN_INTERVAL_MS, so a burst of near-simultaneous ticks can't
+    // blow up the updates-per-day factor.
     fn sigma(&self) -> f64 { 
-        // V10: Correct annualization based on actual update interval
-        // Default to 100ms if not yet calibrated
         let interval_ms = if self.update_interval_ms > 0.0 { self.update_interval_ms } else { 100.0 };
+        let interval_ms = interval_ms.max(SIGMA_MIN_INTERVAL_MS);
         let updates_per_day = 86400.0 * 1000.0 / interval_ms;
         (self.ewma_var * updates_per_day * 365.0).sqrt().max(SIGMA_FLOOR) 
     }
     fn momentum(&self) -> f64 {
-        if let Some((_, p)) = self.price_history.front() {
-            if *p > 0.0 && self.mid > 0.0 { return (self.mid - p) / p; }
+        if let Some(p) = self.price_history.oldest() {
+            if p > 0.0 && self.mid > 0.0 { return (self.mid - p) / p; }
         }
         0.0
     }
@@ -300,10 +1527,26 @@ impl MarketData {
 // ═══════════════════════════════════════════════════════════════════
 // BINANCE FEED
 // ═══════════════════════════════════════════════════════════════════
+// Depth-weighted (bid - ask) / total over a depth5 message; None if empty
+fn depth5_ofi(d: &serde_json::Value) -> Option<f64> {
+    let side = |key: &str| -> f64 {
+        d[key].as_array().map_or(0.0, |levels| levels.iter().enumerate()
+            .filter_map(|(i, l)| l.as_array().filter(|arr| arr.len() >= 2).map(|arr| {
+                let q: f64 = arr[1].as_str().unwrap_or("0").parse().unwrap_or(0.0);
+                q * (-0.5 * i as f64).exp()
+            }))
+            .sum())
+    };
+    let (bv, av) = (side("b"), side("a"));
+    let t = bv + av;
+    (t > 0.0).then(|| (bv - av) / t)
+}
+
 async fn binance_feed(data: Arc<RwLock<MarketData>>) {
     loop {
-        let url = "wss://fstream.binance.com/stream?streams=solusdt@bookTicker/solusdt@depth5@100ms";
-        if let Ok((ws, _)) = connect_async(url).await {
+        let url = format!("wss://fstream.binance.com/stream?streams={s}@bookTicker/{s}@depth5@100ms/{s}@aggTrade",
+            s = symbol().binance_stream());
+        if let Ok((ws, _)) = connect_async(&url).await {
             info!("[BN] Connected");
             let (_, mut r) = ws.split();
             while let Some(Ok(tokio_tungstenite::tungstenite::Message::Text(t))) = r.next().await {
@@ -315,29 +1558,14 @@ async fn binance_feed(data: Arc<RwLock<MarketData>>) {
                         let a: f64 = d["a"].as_str().unwrap_or("0").parse().unwrap_or(0.0);
                         if b > 0.0 && a > 0.0 { let mut m = data.write().await; m.mid = (b + a) / 2.0; m.update(); }
                     } else if stream.contains("depth5") {
-                        let (mut bv, mut av) = (0.0_f64, 0.0_f64);
-                        if let Some(bids) = d["b"].as_array() {
-                            for (i, b) in bids.iter().enumerate() {
-                                if let Some(arr) = b.as_array() {
-                                    if arr.len() >= 2 {
-                                        let q: f64 = arr[1].as_str().unwrap_or("0").parse().unwrap_or(0.0);
-                                        bv += q * (-0.5 * i as f64).exp();
-                                    }
-                                }
-                            }
-                        }
-                        if let Some(asks) = d["a"].as_array() {
-                            for (i, a) in asks.iter().enumerate() {
-                                if let Some(arr) = a.as_array() {
-                                    if arr.len() >= 2 {
-                                        let q: f64 = arr[1].as_str().unwrap_or("0").parse().unwrap_or(0.0);
-                                        av += q * (-0.5 * i as f64).exp();
-                                    }
-                                }
-                            }
+                        data.write().await.on_depth(depth5_ofi(d), Instant::now());
+                    } else if stream.contains("aggTrade") {
+                        // V10.15: "m" = buyer is maker, i.e. the seller was the aggressor
+                        let q: f64 = d["q"].as_str().unwrap_or("0").parse().unwrap_or(0.0);
+                        if q > 0.0 {
+                            let buyer_aggressor = !d["m"].as_bool().unwrap_or(false);
+                            data.write().await.trade_flow.record(buyer_aggressor, q, Instant::now());
                         }
-                        let t = bv + av;
-                        if t > 0.0 { data.write().await.ofi = (bv - av) / t; }
                     }
                 }
             }
@@ -346,14 +1574,154 @@ async fn binance_feed(data: Arc<RwLock<MarketData>>) {
     }
 }
 
+// ═══════════════════════════════════════════════════════════════════
+// KUCOIN BOOK SIGNALS
+// ═══════════════════════════════════════════════════════════════════
+// V10.15: Periodically sample KuCoin depth imbalance / spread into MarketData
+async fn kucoin_book_sampler(book: Arc<RwLock<OrderBook>>, data: Arc<RwLock<MarketData>>, cfg: SignalsConfig) {
+    let mut every = tokio::time::interval(Duration::from_millis(cfg.kucoin_sample_ms.max(50)));
+    loop {
+        every.tick().await;
+        let (imb, spread, mid, touch, at) = {
+            let b = book.read().await;
+            if b.is_stale(cfg.kucoin_stale_ms) || b.mid_price().is_none() {
+                (None, None, None, None, None)
+            } else {
+                (Some(b.depth_imbalance(cfg.kucoin_depth_levels)), b.spread_bps(), b.mid_price(),
+                    b.best_bid().zip(b.best_ask()), Some(b.last_update))
+            }
+        };
+        let mut md = data.write().await;
+        md.kc_imbalance = imb;
+        md.kc_spread_bps = spread;
+        md.kc_book_mid = mid;
+        md.kc_touch = touch;
+        md.kc_book_at = at;
+    }
+}
+
+// V10.15: Compare the WS book's top levels with a REST snapshot; a gap beyond
+// tolerance counts as a divergence and forces the feed to resubscribe
+async fn kucoin_book_checker(
+    book: Arc<RwLock<OrderBook>>, resync: Arc<Notify>, cfg: BookCheckConfig,
+    status: status::SharedStatus, events: event_log::SharedEventLog,
+) {
+    let mut every = tokio::time::interval(Duration::from_secs(cfg.interval_secs.max(1)));
+    let (mut checks, mut divergence_count) = (0u64, 0u64);
+    loop {
+        every.tick().await;
+        let Some((bids, asks)) = poll_kucoin_book().await else { continue };
+        let Some(bps) = book.read().await.divergence_bps(&bids, &asks, cfg.top_n) else { continue };
+        checks += 1;
+        if bps > cfg.tolerance_bps {
+            divergence_count += 1;
+            warn!("[KC-BOOK] WS book {:.1}bps off REST snapshot in top {} (#{}), resyncing",
+                bps, cfg.top_n, divergence_count);
+            resync.notify_one();
+            event_log::record(&events, "book", format!("KuCoin book {:.1}bps off snapshot, resync", bps)).await;
+        }
+        status::publish(&status, "kc_book_check", serde_json::json!({
+            "checks": checks, "divergence_count": divergence_count, "last_divergence_bps": bps,
+        })).await;
+    }
+}
+
 // ═══════════════════════════════════════════════════════════════════
 // REST API FUNCTIONS
 // ═══════════════════════════════════════════════════════════════════
 
+// V10.15: One pooled client with a timeout for every REST helper (was a
+// fresh, timeout-less client per call). Built at startup with the same
+// keepalive / nodelay tuning as KucoinRestClient.
+const REST_TIMEOUT: Duration = Duration::from_secs(5);
+static HTTP: std::sync::OnceLock<reqwest::Client> = std::sync::OnceLock::new();
+
+fn build_rest_client() -> reqwest::Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .timeout(REST_TIMEOUT)
+        .tcp_keepalive(Duration::from_secs(30))
+        .pool_idle_timeout(Duration::from_secs(60))
+        .pool_max_idle_per_host(10)
+        .tcp_nodelay(true)
+        .build()
+}
+
+fn http() -> &'static reqwest::Client {
+    HTTP.get_or_init(|| build_rest_client().expect("reqwest client"))
+}
+
+// V10.15: Shared cap on REST requests in flight (`rest_max_concurrent`),
+// also handed to KucoinRestClient. Set at startup; unset (tests) = no cap.
+static REST_LIMIT: std::sync::OnceLock<Arc<RestLimiter>> = std::sync::OnceLock::new();
+
+fn rest_limit() -> &'static Arc<RestLimiter> {
+    REST_LIMIT.get_or_init(|| Arc::new(RestLimiter::new(0)))
+}
+
+// V10.15: Why a signed REST poll failed. Timeouts and transport errors are
+// transient (retry next cycle); auth failures won't fix themselves.
+#[derive(Debug, PartialEq)]
+enum RestError {
+    Timeout,
+    Transport(String),
+    Auth(String),
+    Api(String),
+    Parse(String),
+}
+
+impl RestError {
+    fn is_transient(&self) -> bool { matches!(self, RestError::Timeout | RestError::Transport(_)) }
+    
+    fn from_reqwest(e: reqwest::Error) -> Self {
+        if e.is_timeout() { RestError::Timeout } else { RestError::Transport(e.to_string()) }
+    }
+}
+
+impl std::fmt::Display for RestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RestError::Timeout => write!(f, "timeout after {:?}", REST_TIMEOUT),
+            RestError::Transport(e) => write!(f, "transport: {}", e),
+            RestError::Auth(e) => write!(f, "auth: {}", e),
+            RestError::Api(e) => write!(f, "api: {}", e),
+            RestError::Parse(e) => write!(f, "parse: {}", e),
+        }
+    }
+}
+
+// KuCoin: HTTP 401 / codes 4000xx are key, signature, passphrase or timestamp problems
+fn classify_response(status: u16, body: &str) -> Result<serde_json::Value, RestError> {
+    let v: serde_json::Value = serde_json::from_str(body)
+        .map_err(|e| RestError::Parse(format!("HTTP {}: {}", status, e)))?;
+    let code = v["code"].as_str().unwrap_or("");
+    if code == "200000" {
+        return Ok(v);
+    }
+    let msg = format!("HTTP {} code {} {}", status, code, v["msg"].as_str().unwrap_or(""));
+    if status == 401 || status == 403 || code.starts_with("4000") {
+        Err(RestError::Auth(msg))
+    } else {
+        Err(RestError::Api(msg))
+    }
+}
+
+async fn signed_get(auth: &KucoinAuth, ep: &str) -> Result<serde_json::Value, RestError> {
+    let _permit = rest_limit().acquire().await;
+    let (ts, sig, pw, ver) = auth.sign("GET", ep, "");
+    let r = http().get(format!("https://api.kucoin.com{}", ep))
+        .header("KC-API-KEY", auth.api_key()).header("KC-API-SIGN", &sig)
+        .header("KC-API-TIMESTAMP", &ts).header("KC-API-PASSPHRASE", &pw)
+        .header("KC-API-KEY-VERSION", &ver).send().await.map_err(RestError::from_reqwest)?;
+    let status = r.status().as_u16();
+    let body = r.text().await.map_err(RestError::from_reqwest)?;
+    classify_response(status, &body)
+}
+
 // V10.5c: Fetch KuCoin spot ticker for weighted mid calculation
 async fn poll_kucoin_ticker() -> f64 {
-    if let Ok(r) = reqwest::Client::new()
-        .get("https://api.kucoin.com/api/v1/market/orderbook/level1?symbol=SOL-USDT")
+    let _permit = rest_limit().acquire().await;
+    if let Ok(r) = http()
+        .get(format!("https://api.kucoin.com/api/v1/market/orderbook/level1?symbol={}", symbol().name))
         .send().await
     {
         if let Ok(v) = r.json::<serde_json::Value>().await {
@@ -369,89 +1737,267 @@ async fn poll_kucoin_ticker() -> f64 {
     0.0
 }
 
-async fn poll_balances(auth: &KucoinAuth) -> Balances {
-    let ep = "/api/v1/accounts?type=trade";
-    let (ts, sig, pw, ver) = auth.sign("GET", ep, "");
+type BookLevels = Vec<(f64, f64)>;
+
+// V10.15: Top-20 REST snapshot for the book integrity check
+async fn poll_kucoin_book() -> Option<(BookLevels, BookLevels)> {
+    let _permit = rest_limit().acquire().await;
+    let v: serde_json::Value = http()
+        .get(format!("https://api.kucoin.com/api/v1/market/orderbook/level2_20?symbol={}", symbol().name))
+        .send().await.ok()?
+        .json().await.ok()?;
+    parse_rest_book(&v)
+}
+
+fn parse_rest_book(v: &serde_json::Value) -> Option<(BookLevels, BookLevels)> {
+    let side = |key: &str| -> Option<BookLevels> {
+        v["data"][key].as_array()?.iter().map(|l| {
+            Some((l.get(0)?.as_str()?.parse().ok()?, l.get(1)?.as_str()?.parse().ok()?))
+        }).collect()
+    };
+    Some((side("bids")?, side("asks")?))
+}
+
+// V10.14: Price/size increments for rounding (public endpoint)
+async fn poll_symbol_info() -> Option<SymbolInfo> {
+    let _permit = rest_limit().acquire().await;
+    let r = http()
+        .get(format!("https://api.kucoin.com/api/v2/symbols/{}", symbol().name))
+        .send().await.ok()?;
+    let v = r.json::<serde_json::Value>().await.ok()?;
+    let d = &v["data"];
+    let num = |k: &str| d[k].as_str().and_then(|s| s.parse::<f64>().ok()).filter(|x| *x > 0.0);
+    Some(SymbolInfo {
+        price_increment: num("priceIncrement")?,
+        base_increment: num("baseIncrement")?,
+        base_min_size: num("baseMinSize")?,
+    })
+}
+
+// V10.15: `enableTrading` from the symbol endpoint, false once the symbol is
+// gone; None if the response doesn't say (no news, not a halt)
+fn parse_symbol_trading(v: &serde_json::Value) -> Option<bool> {
+    if v["code"].as_str() == Some(SYMBOL_NOT_EXIST_CODE) {
+        return Some(false);
+    }
+    v["data"]["enableTrading"].as_bool()
+}
+
+async fn poll_symbol_trading() -> Option<bool> {
+    let _permit = rest_limit().acquire().await;
+    let r = http()
+        .get(format!("https://api.kucoin.com/api/v2/symbols/{}", symbol().name))
+        .send().await.ok()?;
+    parse_symbol_trading(&r.json::<serde_json::Value>().await.ok()?)
+}
+
+// V10.15: Endpoints per trade mode - balances, orders and fills must all
+// look at the same account
+fn balances_endpoint(mode: TradeMode) -> String {
+    match mode {
+        TradeMode::Trade => "/api/v1/accounts?type=trade".to_string(),
+        TradeMode::MarginTrade => "/api/v1/accounts?type=margin".to_string(),
+        TradeMode::MarginIsolatedTrade => format!("/api/v3/isolated/accounts?symbol={}", symbol().name),
+    }
+}
+// V10.15: The key permission a trade mode needs to place orders, checked
+// against `permission` from /api/v1/user/api-key (e.g. "General,Spot")
+fn check_trade_permission(permission: &str, mode: TradeMode) -> Result<(), String> {
+    let needed = match mode {
+        TradeMode::Trade => "Spot",
+        TradeMode::MarginTrade | TradeMode::MarginIsolatedTrade => "Margin",
+    };
+    if permission.split(',').any(|p| p.trim().eq_ignore_ascii_case(needed)) {
+        Ok(())
+    } else {
+        Err(format!("API key permissions [{}] lack {} trading, required for {:?} - every order would be rejected",
+            permission, needed, mode))
+    }
+}
+
+// V10.15: Refuse to start with a key that can't trade. A transient failure
+// of the lookup only warns - the key may well be fine.
+async fn preflight(auth: &KucoinAuth, mode: TradeMode) -> Result<()> {
+    match signed_get(auth, "/api/v1/user/api-key").await {
+        Ok(v) => {
+            let permission = v["data"]["permission"].as_str().unwrap_or("");
+            check_trade_permission(permission, mode).map_err(anyhow::Error::msg)?;
+            info!("[PREFLIGHT] API key ok for {:?}: {}", mode, permission);
+        }
+        Err(RestError::Auth(e)) => anyhow::bail!("API key rejected: {}", e),
+        Err(e) => warn!("[PREFLIGHT] Could not check key permissions ({}) - continuing", e),
+    }
+    Ok(())
+}
+
+fn active_orders_endpoint(mode: TradeMode) -> String {
+    format!("/api/v1/orders?symbol={}&status=active&tradeType={}", symbol().name, mode.trade_type())
+}
+fn fills_endpoint(mode: TradeMode) -> String {
+    format!("/api/v1/fills?symbol={}&pageSize=20&tradeType={}", symbol().name, mode.trade_type())
+}
+
+fn parse_balances(mode: TradeMode, v: &serde_json::Value) -> Balances {
+    let num = |x: &serde_json::Value| x.as_str().unwrap_or("0").parse().unwrap_or(0.0);
     let mut bal = Balances::default();
-    if let Ok(r) = reqwest::Client::new().get(format!("https://api.kucoin.com{}", ep))
-        .header("KC-API-KEY", auth.api_key()).header("KC-API-SIGN", &sig)
-        .header("KC-API-TIMESTAMP", &ts).header("KC-API-PASSPHRASE", &pw)
-        .header("KC-API-KEY-VERSION", &ver).send().await {
-        if let Ok(t) = r.text().await {
-            if let Ok(v) = serde_json::from_str::<serde_json::Value>(&t) {
-                if let Some(items) = v["data"].as_array() {
-                    for i in items {
-                        let cur = i["currency"].as_str().unwrap_or("");
-                        let avail: f64 = i["available"].as_str().unwrap_or("0").parse().unwrap_or(0.0);
-                        match cur { "SOL" => bal.sol = avail, "USDT" => bal.usdt = avail, _ => {} }
-                    }
-                }
+    if mode == TradeMode::MarginIsolatedTrade {
+        // One asset pair per symbol: base + quote
+        if let Some(pair) = v["data"]["assets"].as_array().and_then(|a| a.first()) {
+            bal.sol = num(&pair["baseAsset"]["available"]);
+            bal.usdt = num(&pair["quoteAsset"]["available"]);
+            bal.sol_holds = num(&pair["baseAsset"]["holds"]);
+            bal.usdt_holds = num(&pair["quoteAsset"]["holds"]);
+        }
+    } else if let Some(items) = v["data"].as_array() {
+        for i in items {
+            let cur = i["currency"].as_str().unwrap_or("");
+            let (avail, holds): (f64, f64) = (num(&i["available"]), num(&i["holds"]));
+            // Base / quote of the traded symbol (fields keep their SOL / USDT names)
+            if cur == symbol().base {
+                (bal.sol, bal.sol_holds) = (avail, holds);
+            } else if cur == symbol().quote {
+                (bal.usdt, bal.usdt_holds) = (avail, holds);
             }
         }
     }
     bal
 }
 
-async fn poll_active_orders(auth: &KucoinAuth) -> Vec<ActiveOrder> {
-    let ep = "/api/v1/orders?symbol=SOL-USDT&status=active";
-    let (ts, sig, pw, ver) = auth.sign("GET", ep, "");
+async fn poll_balances(auth: &KucoinAuth, mode: TradeMode) -> Result<Balances, RestError> {
+    Ok(parse_balances(mode, &signed_get(auth, &balances_endpoint(mode)).await?))
+}
+
+async fn poll_active_orders(auth: &KucoinAuth, mode: TradeMode) -> Result<Vec<ActiveOrder>, RestError> {
+    let v = signed_get(auth, &active_orders_endpoint(mode)).await?;
+    Ok(parse_active_orders(&v))
+}
+
+fn parse_active_orders(v: &serde_json::Value) -> Vec<ActiveOrder> {
     let mut orders = Vec::new();
-    if let Ok(r) = reqwest::Client::new().get(format!("https://api.kucoin.com{}", ep))
-        .header("KC-API-KEY", auth.api_key()).header("KC-API-SIGN", &sig)
-        .header("KC-API-TIMESTAMP", &ts).header("KC-API-PASSPHRASE", &pw)
-        .header("KC-API-KEY-VERSION", &ver).send().await {
-        if let Ok(t) = r.text().await {
-            if let Ok(v) = serde_json::from_str::<serde_json::Value>(&t) {
-                if let Some(items) = v["data"]["items"].as_array() {
-                    for i in items {
-                        let id = i["id"].as_str().unwrap_or("").to_string();
-                        let side = i["side"].as_str().unwrap_or("").to_string();
-                        let price: f64 = i["price"].as_str().unwrap_or("0").parse().unwrap_or(0.0);
-                        let size: f64 = i["size"].as_str().unwrap_or("0").parse().unwrap_or(0.0);
-                        if !id.is_empty() {
-                            orders.push(ActiveOrder { order_id: id, side, price, size });
-                        }
-                    }
-                }
+    if let Some(items) = v["data"]["items"].as_array() {
+        for i in items {
+            let id = i["id"].as_str().unwrap_or("").to_string();
+            let client_oid = i["clientOid"].as_str().unwrap_or("").to_string();
+            let Ok(side) = i["side"].as_str().unwrap_or("").parse::<Side>() else {
+                warn!("[RECON] Skipping order {} with side {}", id, i["side"]);
+                continue;
+            };
+            let price: f64 = i["price"].as_str().unwrap_or("0").parse().unwrap_or(0.0);
+            let size: f64 = i["size"].as_str().unwrap_or("0").parse().unwrap_or(0.0);
+            let deal: f64 = i["dealSize"].as_str().unwrap_or("0").parse().unwrap_or(0.0);
+            // V10.15: Track the unfilled remainder, not the original size
+            let size = (size - deal).max(0.0);
+            let cancel_exist = i["cancelExist"].as_bool().unwrap_or(false);
+            // Done orders can still be listed briefly - treat them as gone
+            let is_active = i["isActive"].as_bool().unwrap_or(true);
+            if !id.is_empty() && is_active {
+                orders.push(ActiveOrder { order_id: id, client_oid, side, price, size, deal, cancel_exist });
             }
         }
     }
     orders
 }
 
-async fn poll_fills(auth: &KucoinAuth, seen: &mut HashSet<String>) -> Vec<(String, f64, f64)> {
-    let ep = "/api/v1/fills?symbol=SOL-USDT&pageSize=20";
-    let (ts, sig, pw, ver) = auth.sign("GET", ep, "");
-    let mut out = Vec::new();
-    if let Ok(r) = reqwest::Client::new().get(format!("https://api.kucoin.com{}", ep))
-        .header("KC-API-KEY", auth.api_key()).header("KC-API-SIGN", &sig)
-        .header("KC-API-TIMESTAMP", &ts).header("KC-API-PASSPHRASE", &pw)
-        .header("KC-API-KEY-VERSION", &ver).send().await {
-        if let Ok(t) = r.text().await {
-            if let Ok(v) = serde_json::from_str::<serde_json::Value>(&t) {
-                if let Some(items) = v["data"]["items"].as_array() {
-                    for i in items {
-                        let tid = i["tradeId"].as_str().unwrap_or("").to_string();
-                        if seen.contains(&tid) { continue; }
-                        seen.insert(tid);
-                        let side = i["side"].as_str().unwrap_or("").to_string();
-                        let sz: f64 = i["size"].as_str().unwrap_or("0").parse().unwrap_or(0.0);
-                        let px: f64 = i["price"].as_str().unwrap_or("0").parse().unwrap_or(0.0);
-                        if sz > 0.0 { out.push((side, sz, px)); }
-                    }
-                }
+struct PolledFill {
+    order_id: String,
+    side: Side,
+    size: f64,
+    price: f64,
+    liquidity: Liquidity,
+    /// V10.15: Exchange-reported fee, signed (< 0 = rebate paid to us); None if absent
+    fee: Option<f64>,
+    fee_currency: String,
+}
+
+impl PolledFill {
+    // V10.15: Reported fee in the quote (USDT); base-asset fees are converted at the fill price.
+    // None when missing or in a currency we can't value.
+    fn fee_usdt(&self) -> Option<f64> {
+        let cur = self.fee_currency.as_str();
+        if cur.is_empty() || cur == symbol().quote {
+            self.fee
+        } else if cur == symbol().base {
+            self.fee.map(|f| f * self.price)
+        } else {
+            None
+        }
+    }
+}
+
+// V10.15: One poll's fills - new ones to book, and the real fees for trades
+// the feed booked first on an estimate
+#[derive(Default)]
+struct FillPage {
+    fills: Vec<PolledFill>,
+    fee_updates: Vec<PolledFill>,
+}
+
+async fn poll_fills(auth: &KucoinAuth, seen: &mut SeenTrades, mode: TradeMode) -> Result<FillPage, RestError> {
+    let v = signed_get(auth, &fills_endpoint(mode)).await?;
+    Ok(parse_fills(&v, seen))
+}
+
+fn parse_fills(v: &serde_json::Value, seen: &mut SeenTrades) -> FillPage {
+    let mut out = FillPage::default();
+    if let Some(items) = v["data"]["items"].as_array() {
+        for i in items {
+            let trade_id = i["tradeId"].as_str().unwrap_or("");
+            let fee_update = seen.take_estimated(trade_id);
+            if !fee_update && !seen.insert(trade_id) { continue; }
+            let oid = i["orderId"].as_str().unwrap_or("").to_string();
+            let Ok(side) = i["side"].as_str().unwrap_or("").parse::<Side>() else {
+                warn!("[FILL] Skipping fill on {} with side {}", oid, i["side"]);
+                continue;
+            };
+            let sz: f64 = i["size"].as_str().unwrap_or("0").parse().unwrap_or(0.0);
+            let px: f64 = i["price"].as_str().unwrap_or("0").parse().unwrap_or(0.0);
+            let liquidity = Liquidity::parse(i["liquidity"].as_str().unwrap_or(""));
+            let fee: Option<f64> = i["fee"].as_str().and_then(|f| f.parse().ok());
+            let fee_currency = i["feeCurrency"].as_str().unwrap_or("").to_string();
+            let fill = PolledFill { order_id: oid, side, size: sz, price: px, liquidity, fee, fee_currency };
+            if fee_update {
+                out.fee_updates.push(fill);
+            } else if sz > 0.0 {
+                out.fills.push(fill);
             }
         }
     }
     out
 }
 
+// V10.15: Fill from a private-feed match, deduped against `seen` by tradeId
+// like the REST poll, so whichever source sees a trade first books it and the
+// other skips it. The feed carries no fee - PnL estimates it from liquidity
+// until the poll's row brings the real one.
+fn ws_fill(u: &OrderUpdate, seen: &mut SeenTrades) -> Option<PolledFill> {
+    let OrderUpdateKind::Match { trade_id, side, price, size, liquidity } = &u.kind else { return None };
+    // Without a tradeId there is nothing to dedup on - leave it to the poll
+    if trade_id.is_empty() || trade_id == "unknown" || !seen.insert_estimated(trade_id) {
+        return None;
+    }
+    Some(PolledFill {
+        order_id: u.order_id.clone(),
+        side: match side { OrderSide::Buy => Side::Buy, OrderSide::Sell => Side::Sell },
+        size: *size,
+        price: *price,
+        liquidity: *liquidity,
+        fee: None,
+        fee_currency: String::new(),
+    })
+}
+
 // V10: REST cancel all orders
 async fn cancel_all_orders(auth: &KucoinAuth) {
+    let _permit = rest_limit().acquire().await;
+    send_cancel_all(auth).await
+}
+
+// V10.15: The bulk DELETE itself, outside the REST limiter (`panic_cancel`)
+async fn send_cancel_all(auth: &KucoinAuth) {
     let ep = "/api/v1/orders";
-    let body = r#"{"symbol":"SOL-USDT"}"#;
-    let (ts, sig, pw, ver) = auth.sign("DELETE", ep, body);
-    let _ = reqwest::Client::new().delete(format!("https://api.kucoin.com{}", ep))
+    let body = serde_json::json!({ "symbol": symbol().name }).to_string();
+    let (ts, sig, pw, ver) = auth.sign("DELETE", ep, &body);
+    let _ = http().delete(format!("https://api.kucoin.com{}", ep))
         .header("KC-API-KEY", auth.api_key()).header("KC-API-SIGN", &sig)
         .header("KC-API-TIMESTAMP", &ts).header("KC-API-PASSPHRASE", &pw)
         .header("KC-API-KEY-VERSION", &ver).header("Content-Type", "application/json")
@@ -460,9 +2006,15 @@ async fn cancel_all_orders(auth: &KucoinAuth) {
 
 // V10.3: REST cancel single order (fallback for stuck WS cancels)
 async fn rest_cancel_order(auth: &KucoinAuth, order_id: &str) -> bool {
+    let _permit = rest_limit().acquire().await;
+    send_cancel_order(auth, order_id).await
+}
+
+// V10.15: The single-order DELETE itself, outside the REST limiter (`panic_cancel`)
+async fn send_cancel_order(auth: &KucoinAuth, order_id: &str) -> bool {
     let ep = format!("/api/v1/orders/{}", order_id);
     let (ts, sig, pw, ver) = auth.sign("DELETE", &ep, "");
-    if let Ok(r) = reqwest::Client::new().delete(format!("https://api.kucoin.com{}", ep))
+    if let Ok(r) = http().delete(format!("https://api.kucoin.com{}", ep))
         .header("KC-API-KEY", auth.api_key()).header("KC-API-SIGN", &sig)
         .header("KC-API-TIMESTAMP", &ts).header("KC-API-PASSPHRASE", &pw)
         .header("KC-API-KEY-VERSION", &ver).send().await {
@@ -478,125 +2030,648 @@ async fn rest_cancel_order(auth: &KucoinAuth, order_id: &str) -> bool {
     false
 }
 
+// V10.15: Emergency fast path - WS cancelAll, REST bulk DELETE and one REST
+// DELETE per known order, all at once. Deliberately ignores the per-tick
+// action budget and the shared REST limiter: a full ladder is ~50 orders,
+// so this bursts ~50 REST requests inside a second, which can exceed
+// KuCoin's spot REST quota and draw 429s (and a short cool-off) for the rest
+// of the window. By then the cancels are out; recon keeps retrying whatever
+// is left. Not for routine stops - SIGINT does the orderly sweep. Returns
+// (WS ok, REST cancels ok).
+async fn panic_cancel(ws: &WsOrderClientV2, auth: &KucoinAuth, order_ids: &[String]) -> (bool, usize) {
+    let (ws_res, _, individual) = tokio::join!(
+        ws.cancel_all(&symbol().name),
+        send_cancel_all(auth),
+        futures_util::future::join_all(order_ids.iter().map(|id| send_cancel_order(auth, id))),
+    );
+    (ws_res.is_ok_and(|r| r.success), individual.into_iter().filter(|ok| *ok).count())
+}
+
+// V10.15: REST cancel by our clientOid (when the orderId cancel is refused)
+async fn rest_cancel_by_client_oid(auth: &KucoinAuth, client_oid: &str) -> bool {
+    let _permit = rest_limit().acquire().await;
+    let ep = format!("/api/v1/order/client-order/{}", client_oid);
+    let (ts, sig, pw, ver) = auth.sign("DELETE", &ep, "");
+    match http().delete(format!("https://api.kucoin.com{}", ep))
+        .header("KC-API-KEY", auth.api_key()).header("KC-API-SIGN", &sig)
+        .header("KC-API-TIMESTAMP", &ts).header("KC-API-PASSPHRASE", &pw)
+        .header("KC-API-KEY-VERSION", &ver).send().await {
+        Ok(r) if r.status().is_success() => true,
+        Ok(r) => { warn!("[REST-CANCEL] Failed clientOid {}: HTTP {}", client_oid, r.status()); false }
+        Err(_) => { warn!("[REST-CANCEL] Request error for clientOid {}", client_oid); false }
+    }
+}
+
+// V10.15: Live REST backend for the shutdown cancel sweep
+struct RestCancel<'a> {
+    auth: &'a KucoinAuth,
+    mode: TradeMode,
+}
+
+#[async_trait::async_trait]
+impl CancelApi for RestCancel<'_> {
+    async fn cancel_all(&self) { cancel_all_orders(self.auth).await }
+    async fn open_orders(&self) -> Option<Vec<(String, String)>> {
+        match poll_active_orders(self.auth, self.mode).await {
+            Ok(orders) => Some(orders.into_iter().map(|o| (o.order_id, o.client_oid)).collect()),
+            Err(e) => { warn!("[SHUTDOWN] Open-order poll failed: {}", e); None }
+        }
+    }
+    async fn cancel_by_id(&self, order_id: &str) -> bool { rest_cancel_order(self.auth, order_id).await }
+    async fn cancel_by_client_oid(&self, client_oid: &str) -> bool { rest_cancel_by_client_oid(self.auth, client_oid).await }
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     tracing_subscriber::fmt().with_max_level(tracing::Level::INFO).with_target(false).init();
     info!("═══ V10.5: Partial Fill Tracking + FIFO Persistence ═══");
     
+    let mut cfg = config::Config::load()?;
+    let env_symbol = std::env::var("TRADE_SYMBOL").ok();
+    // V10.15: `analyze --input trades.jsonl [--csv out.csv]` - offline report, no trading
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("analyze") {
+        let _ = SYMBOL.set(TradeSymbol::parse(&symbol_from(None, env_symbol))?);
+        return analyze::run(&args[2..], cfg.fees.taker_bps);
+    }
+    // V10.15: Flags and strategy overrides (see `cli`); out-of-range values stop here
+    let cli = cli::Args::parse();
+    let strategy = cli::StrategyConfig::from_args(&cli)?;
+    let strat = &strategy;
+    // V10.15: `--mid-source kucoin|binance` overrides the config's mid and OFI sources
+    if let Some(feed) = cli.mid_source {
+        feed.apply(&mut cfg);
+        info!("[START] --mid-source {:?}", feed);
+    }
+    info!("[START] Strategy: ${} per order | max inv {} | gamma {} | OFI pause {} resume {} | momentum {}",
+        strat.order_usd, strat.max_inv_sol, strat.gamma, strat.ofi_pause_threshold, strat.ofi_resume_threshold, strat.momentum_threshold);
+    // V10.15: `--symbol ETH-USDT` or TRADE_SYMBOL; endpoints, balances and the Binance feed follow it
+    let _ = SYMBOL.set(TradeSymbol::parse(&symbol_from(cli.symbol.clone(), env_symbol))?);
+    // V10.15: `--drain` - take over resting orders and let them run out, no placing
+    let drain_on_start = cli.drain;
+    info!("[START] Trading {} (base {}, quote {}), Binance reference {}",
+        symbol().name, symbol().base, symbol().quote, symbol().binance_stream());
+    let _ = HTTP.set(build_rest_client()?);
+    let _ = REST_LIMIT.set(Arc::new(RestLimiter::new(cfg.rest_max_concurrent)));
+    let _ = LADDER.set(config::LadderConfig::load(&cfg.ladder_file, &DEFAULT_LEVELS)?.levels);
+    
+    // Status endpoint - quote gate and other observability sections
+    let status = status::new_shared_status();
+    // V10.15: Recent significant events, served by /status?events=N
+    let recent_events = event_log::new_shared_event_log(cfg.event_log.capacity);
+    // V10.15: Panic cancel - POST /panic or SIGUSR2
+    let panic_trigger = Arc::new(Notify::new());
+    if !cfg.status_addr.is_empty() {
+        let (addr, st, ev, pt) = (cfg.status_addr.clone(), status.clone(), recent_events.clone(), panic_trigger.clone());
+        tokio::spawn(async move {
+            if let Err(e) = status::serve(addr, st, ev, pt).await { warn!("[STATUS] Server stopped: {}", e); }
+        });
+    }
+    let mut usr2 = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::user_defined2())?;
+    let pt = panic_trigger.clone();
+    tokio::spawn(async move {
+        while usr2.recv().await.is_some() {
+            warn!("[PANIC] SIGUSR2 received");
+            pt.notify_one();
+        }
+    });
+    
     let auth = KucoinAuth::new(
         std::env::var("KUCOIN_API_KEY")?, std::env::var("KUCOIN_API_SECRET")?,
         std::env::var("KUCOIN_PASSPHRASE")?, true
     );
+    // V10.15: Trade-permission preflight; `--skip-preflight` to bypass
+    if cfg.startup.skip_preflight || cli.skip_preflight {
+        warn!("[PREFLIGHT] Skipped - key permissions not checked");
+    } else if let Err(e) = preflight(&auth, cfg.account.trade_mode).await {
+        error!("[PREFLIGHT] {}", e);
+        return Err(e.context("preflight failed (--skip-preflight to bypass)"));
+    }
     let auth2 = auth.clone();
     let auth3 = auth.clone();
     let auth4 = auth.clone();
+    // V10.15: HF batch REST placement fallback - the HF endpoint is spot (trade) only
+    let rest_batch = match (cfg.levels.rest_batch_queue_threshold, cfg.account.trade_mode) {
+        (0, _) => None,
+        (_, TradeMode::Trade) => Some(KucoinRestClient::new(&KucoinEndpoints::standard(), auth.clone())?
+            .with_limiter(rest_limit().clone())),
+        (_, mode) => {
+            warn!("[BATCH] rest_batch_queue_threshold ignored in {:?} mode", mode);
+            None
+        }
+    };
+    // V10.15: Reprice by modify goes through the HF alter endpoint - spot (trade) only
+    let reprice_rest = match (cfg.levels.reprice, cfg.account.trade_mode) {
+        (RepriceMode::CancelReplace, _) => None,
+        (_, TradeMode::Trade) => Some(KucoinRestClient::new(&KucoinEndpoints::standard(), auth.clone())?
+            .with_limiter(rest_limit().clone())),
+        (mode, trade_mode) => {
+            warn!("[REPRICE] {:?} needs the trade account - cancel/replace in {:?} mode", mode, trade_mode);
+            None
+        }
+    };
+    let reprice_mode = if reprice_rest.is_some() { cfg.levels.reprice } else { RepriceMode::CancelReplace };
     let auth_shutdown = auth.clone();
+    let auth_private = auth.clone();
     
     // V10: Remove unnecessary RwLock - WsOrderClientV2 uses internal Arc
+    // V10.15: WS op set for the same account the REST polls read
+    let order_entry = cfg.account.order_entry()?;
+    info!("[ACCOUNT] {:?}: orders via {}/{}, polls tradeType={}", cfg.account.trade_mode,
+        order_entry.place_op(), order_entry.cancel_op(), cfg.account.trade_mode.trade_type());
     let ws = Arc::new(WsOrderClientV2::new(
         auth, "https://api.kucoin.com".into(), "wss://wsapi.kucoin.com/v1/private".into()
-    ));
+    ).with_order_entry(order_entry));
     { 
         // Note: connect() takes &mut self, we need a workaround
         // Actually looking at ws_order_client_v2.rs, connect() -> start() which takes &self
         // The signature is: pub async fn connect(&mut self) -> Result<JoinHandle<()>>
         // We need to call start() directly which takes &self
-        let _ = ws.start().await?; 
+        let _monitor = ws.start().await?;
     }
     info!("[WS] OK");
+    // V10.15: Order-session lifecycle (the initial Connected already fired above)
+    let mut ws_events = ws.events();
+    
+    // V10.15: SIGINT is caught here, not in the loop, so a tick already in
+    // flight sees it: placement closes at once, cancel-all runs after the tick
+    let shutdown = Shutdown::new();
+    let (sd, ws_sd) = (shutdown.clone(), ws.clone());
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            begin_shutdown(&ws_sd, &sd);
+        }
+    });
+    
+    let alerter = Alerter::new(&cfg.alert_webhook, Duration::from_secs(cfg.alert_cooldown_secs));
     
-    let data = Arc::new(RwLock::new(MarketData::default()));
+    // V10.14: Private order feed - lifecycle events (open/match/done) for consumers
+    let private_ws = Arc::new(KucoinPrivateWs::new(
+        auth_private, "https://api.kucoin.com".into(), String::new(),
+        exchange::new_shared_order_manager(-REBATE / 10000.0, cfg.fees.taker_bps / 10000.0), symbol().name.clone()
+    ).with_backoff(exchange::kucoin_ws_private::Backoff {
+        base: Duration::from_millis(cfg.private_ws.backoff_base_ms),
+        max: Duration::from_millis(cfg.private_ws.backoff_max_ms),
+        jitter: cfg.private_ws.backoff_jitter_pct / 100.0,
+    }));
+    private_ws.start().await?;
+    // V10.15: Cancel confirmations from the private feed clear CancelPending early
+    let mut order_updates = private_ws.subscribe();
+    
+    let data = Arc::new(RwLock::new(MarketData::new(PriceHistory::new(
+        Duration::from_secs(MOMENTUM_WINDOW_SECS),
+        Duration::from_millis(cfg.market_data.history_sample_ms),
+        cfg.market_data.history_max_len,
+    ), FillFlow::new(Duration::from_secs(cfg.signals.trade_flow_window_secs)))
+        .with_anchor_half_life(Duration::from_millis(cfg.quoting.anchor_half_life_ms))));
     let balances = Arc::new(RwLock::new(Balances::default()));
     let active_orders = Arc::new(RwLock::new(Vec::<ActiveOrder>::new()));
     
     // Initial fetches
-    let bal = poll_balances(&auth2).await;
+    let initial_bal = poll_balances(&auth2, cfg.account.trade_mode).await
+        .map_err(|e| warn!("[BAL] Initial poll failed ({}), waiting for recon", e)).ok();
+    let bal = initial_bal.clone().unwrap_or_default();
     info!("[BAL] {:.4} SOL, {:.2} USDT", bal.sol, bal.usdt);
+    if cfg.account.reserve_base > 0.0 || cfg.account.reserve_quote > 0.0 {
+        info!("[BAL] Reserved from quoting: {:.4} SOL, {:.2} USDT (tradable {:.4} SOL, {:.2} USDT)",
+            cfg.account.reserve_base, cfg.account.reserve_quote,
+            tradable(bal.sol, cfg.account.reserve_base), tradable(bal.usdt, cfg.account.reserve_quote));
+    }
     *balances.write().await = bal;
     
+    let sym = match poll_symbol_info().await {
+        Some(info) => info,
+        None => { warn!("[SYMBOL] Failed to fetch increments, using defaults"); SymbolInfo::default() }
+    };
+    info!("[SYMBOL] tick={} lot={} min={}", sym.price_increment, sym.base_increment, sym.base_min_size);
+    
     // Cancel all orders on startup
-    cancel_all_orders(&auth3).await;
-    info!("[STARTUP] Cancelled all existing orders");
-    tokio::time::sleep(Duration::from_secs(1)).await;
-    let orders = poll_active_orders(&auth3).await;
-    info!("[ORDERS] {} active", orders.len());
-    *active_orders.write().await = orders;
+    if cfg.startup.mode == StartupMode::CancelAll && !drain_on_start {
+        info!("[STARTUP] Path: cancel_all");
+        cancel_all_orders(&auth3).await;
+        info!("[STARTUP] Cancelled all existing orders");
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        let orders = poll_active_orders(&auth3, cfg.account.trade_mode).await.unwrap_or_else(|e| {
+            warn!("[ORDERS] Poll failed: {}", e);
+            Vec::new()
+        });
+        info!("[ORDERS] {} active", orders.len());
+        *active_orders.write().await = orders;
+    }
     
     let d2 = data.clone();
     tokio::spawn(async move { binance_feed(d2).await; });
     
+    // V10.15: KuCoin level2 depth feed - only when a KuCoin signal, the mid source or VWAP far layers need it
+    let far_vwap = cfg.levels.far_mode == FarLayerMode::BookVwap;
+    let mut kc_book: Option<Arc<RwLock<OrderBook>>> = None;
+    if cfg.signals.toxicity_source != ToxicitySource::BinanceOfi || far_vwap || cfg.quoting.mid_source.needs_local()
+        || cfg.sizing.book_boost_coeff > 0.0 || cfg.quoting.own_depth_k > 0.0 {
+        let mut public_ws = KucoinPublicWs::new("https://api.kucoin.com".into(), symbol().name.clone())
+            .with_max_levels(cfg.market_data.book_max_levels);
+        if far_vwap {
+            public_ws = public_ws.with_depth50();
+            info!("[LEVELS] Far layers from L{} at book VWAP, {} USDT steps",
+                cfg.levels.far_from_index + 1, cfg.levels.far_vwap_step_usd);
+        }
+        public_ws.start();
+        kc_book = Some(public_ws.orderbook());
+        let (book, d3, sc) = (public_ws.orderbook(), data.clone(), cfg.signals.clone());
+        tokio::spawn(async move { kucoin_book_sampler(book, d3, sc).await; });
+        if cfg.book_check.interval_secs > 0 {
+            let (book, resync, bc, st, ev) = (public_ws.orderbook(), public_ws.resync_handle(),
+                cfg.book_check.clone(), status.clone(), recent_events.clone());
+            info!("[KC-BOOK] Checking top {} against REST every {}s, tolerance {}bps",
+                bc.top_n, bc.interval_secs, bc.tolerance_bps);
+            tokio::spawn(async move { kucoin_book_checker(book, resync, bc, st, ev).await; });
+        }
+        info!("[SIGNAL] Toxicity source {:?} (KuCoin weight {:.2})",
+            cfg.signals.toxicity_source, cfg.signals.kucoin_weight);
+    }
+    info!("[START] Mid source {:?}", cfg.quoting.mid_source);
+    // V10.15: A Binance OFI this old reads neutral, so a dead depth stream can't hold a pause
+    let ofi_stale = Duration::from_secs(cfg.signals.ofi_stale_secs);
+    if cfg.quoting.anchor_half_life_ms > 0 {
+        info!("[START] Anchor on a {}ms half-life EMA of the reference mid (guards stay on the raw mid)",
+            cfg.quoting.anchor_half_life_ms);
+    }
+    
     loop { if data.read().await.mid > 0.0 { break; } tokio::time::sleep(Duration::from_millis(100)).await; }
     info!("[START] mid={:.2}", data.read().await.mid);
     
     // V10: Order state machine per level - key: level_bps*10, value: (bid_state, ask_state)
     let mut level_orders: HashMap<i32, (LevelOrderState, LevelOrderState)> = HashMap::new();
-    for (bps, _) in LEVELS.iter() {
+    for (bps, _) in levels().iter() {
         level_orders.insert((*bps * 10.0) as i32, (LevelOrderState::Empty, LevelOrderState::Empty));
     }
     
     // V10.5: Load FIFO state from disk (persistence across restarts)
     let mut pnl = PnL::load();
-    let mut seen: HashSet<String> = HashSet::new();
+    
+    // V10.15: Cold start - a pre-existing position counts from tick one
+    if let Some(baseline) = cfg.startup.baseline_balance {
+        match &initial_bal {
+            Some(b) => match cold_start_inventory(b.sol + b.sol_holds, baseline, pnl.inv()) {
+                Some(inv) => {
+                    let entry = cfg.startup.avg_entry.unwrap_or(data.read().await.mid);
+                    info!("[STARTUP] Seeding inventory {:+.4} SOL @ {:.4} ({:.4} SOL vs baseline {:.4}, saved FIFO had {:+.4})",
+                        inv, entry, b.sol + b.sol_holds, baseline, pnl.inv());
+                    pnl.seed_inventory(inv, entry);
+                    pnl.save();
+                }
+                None => info!("[STARTUP] Saved FIFO matches balance over baseline: inv {:+.4} SOL", pnl.inv()),
+            },
+            None => warn!("[STARTUP] No initial balance to seed inventory from, keeping inv {:+.4} SOL", pnl.inv()),
+        }
+    }
+    
+    // V10.15: Adopt path - feeds and inventory are up; keep our resting orders
+    // protecting the position and cancel only what we can't track
+    let mut adopt_check = AdoptCheck::default();
+    if cfg.startup.mode == StartupMode::Adopt || drain_on_start {
+        // Nothing adoptable if the poll failed; recon treats leftovers as orphans
+        let orders = poll_active_orders(&auth3, cfg.account.trade_mode).await.unwrap_or_else(|e| {
+            warn!("[STARTUP] Active-order poll failed ({}), adopting nothing", e);
+            Vec::new()
+        });
+        let rejected = adopt_orders(&orders, &mut level_orders);
+        info!("[STARTUP] Path: adopt | inv {:.3} SOL | {} active, {} adopted, {} to cancel",
+            pnl.inv(), orders.len(), orders.len() - rejected.len(), rejected.len());
+        // Adopted prices may be stale - the first tick re-checks each against its level
+        if cfg.startup.verify_adopted {
+            adopt_check = AdoptCheck::new(level_orders.values()
+                .flat_map(|(b, a)| [b, a])
+                .filter_map(|s| match s { LevelOrderState::Live { order_id, .. } => Some(order_id.clone()), _ => None }));
+        }
+        for id in &rejected {
+            let o = orders.iter().find(|o| &o.order_id == id);
+            if !o.is_some_and(|o| o.client_oid.starts_with(latency_probe::PROBE_OID_PREFIX)) {
+                rest_cancel_order(&auth3, id).await;
+            }
+        }
+        *active_orders.write().await = orders;
+    }
+    // V10.15: tradeIds already booked, shared by the private feed and the fill poll
+    let mut seen_trade_ids = SeenTrades::new(cfg.fill_dedup_capacity);
+    // V10.15: Fills to book this pass, and how many each source contributed
+    let mut new_fills: Vec<PolledFill> = Vec::new();
+    let (mut ws_fills, mut rest_fills) = (0u64, 0u64);
     let start = Instant::now();
     
     // V10.3: Two-layer commitment tracker
     let mut commitments = CommitmentTracker::default();
+    let mut fill_ledger = FillLedger::default();
     
     // V10.3: Orphan cancel tracking (rate limiting)
     let mut recently_cancelled: HashMap<String, Instant> = HashMap::new();
     
-    let mut tick = tokio::time::interval(Duration::from_millis(500));
-    let mut log = tokio::time::interval(Duration::from_secs(30));
-    let mut fp = tokio::time::interval(Duration::from_secs(5));
-    let mut recon = tokio::time::interval(Duration::from_secs(1));
-    let mut n: u64 = 0;
+    // V10.15: Configurable loop cadence
+    let cadence = &cfg.cadence;
+    let mut tick = tokio::time::interval(Duration::from_millis(cadence.tick_ms));
+    let mut log = tokio::time::interval(Duration::from_secs(cadence.log_secs));
+    let mut fp = tokio::time::interval(Duration::from_millis(cadence.fill_poll_ms));
+    let mut recon = tokio::time::interval(Duration::from_millis(cadence.recon_ms));
+    // V10.15: Recon runs at recon_fast_ms until this, after an order WS reconnect
+    let mut recon_fast_until: Option<Instant> = None;
+    info!("[CADENCE] tick {}ms | recon {}ms | fill poll {}ms | log {}s",
+        cadence.tick_ms, cadence.recon_ms, cadence.fill_poll_ms, cadence.log_secs);
+    if cadence.orphan_cancels_min != cadence.orphan_cancels_max {
+        info!("[CADENCE] Orphan cancels per recon {} flat to {} at the {:.1} SOL limit",
+            cadence.orphan_cancels_min, cadence.orphan_cancels_max, strat.max_inv_sol);
+    }
+    // Worst case per tick: the action budget, or one action per side per level
+    let tick_actions = match cfg.levels.max_actions_per_tick {
+        0 => levels().len() * 2,
+        n => n,
+    };
+    if tick_actions as f64 > cadence.sustainable_actions_per_tick() {
+        warn!("[CADENCE] Up to {} actions per {}ms tick exceeds the {:.0}/s order rate limit ({:.0} per tick)",
+            tick_actions, cadence.tick_ms, cadence.order_rate_limit_per_sec, cadence.sustainable_actions_per_tick());
+    }
+    let mut oid_seq = OidSeq::new(std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0));
+    
+    // V10.14: Synthetic order-entry latency probe (config-gated)
+    let mut probe = tokio::time::interval(Duration::from_secs(cfg.latency_probe.interval_secs.max(1)));
+    let probe_busy = Arc::new(AtomicBool::new(false));
+    let probe_stats = latency_probe::new_shared_probe_stats();
+    let mut probe_seq: u64 = 0;
+    
+    // V10.15: Level visit order under the per-tick action budget
+    let level_priority = level_priority::from_name(&cfg.levels.priority)?;
+    let mut deferred_levels: u64 = 0;
+    // V10.15: Minimum resting time before a refresh may cancel a live order
+    let min_rest = Duration::from_millis(cfg.levels.min_resting_ms);
+    let mut rest_deferred: u64 = 0;
+    // V10.15: Orders clamped to sizing.max_notional_per_level_usd
+    let mut notional_clamped: u64 = 0;
+    // V10.15: A refreshed level side waits to settle before the next refresh
+    let mut hysteresis = RequoteHysteresis::new(cfg.levels.refresh_inner_frac);
+    let mut hysteresis_held: u64 = 0;
+    if cfg.levels.refresh_inner_frac > 0.0 {
+        info!("[LEVELS] Requote hysteresis: re-arm within {:.0}% of the refresh trigger", cfg.levels.refresh_inner_frac * 100.0);
+    }
+    // V10.15: Level sides part way through a one-tick-per-tick walk to their price
+    let mut walking: HashSet<(i32, bool)> = HashSet::new();
+    // V10.15: Merged levels' worth of size each level side was last placed with
+    let mut placed_lots: HashMap<(i32, bool), u32> = HashMap::new();
+    // V10.15: Resting orders per side (0 = one per ladder level)
+    let side_cap = match cfg.levels.max_orders_per_side {
+        0 => levels().len(),
+        n => n.min(levels().len()),
+    };
+    if side_cap < levels().len() {
+        info!("[LEVELS] At most {} of {} levels per side{}", side_cap, levels().len(),
+            if cfg.levels.warm_cancel { ", warm cancel for inner ones" } else { "" });
+    } else if cfg.levels.warm_cancel {
+        warn!("[LEVELS] warm_cancel needs levels.max_orders_per_side below the ladder length - it never fires");
+    }
+    if reprice_mode != RepriceMode::CancelReplace {
+        info!("[LEVELS] Reprice: {:?} (cancel/replace beyond {} ticks, 0 = never)", reprice_mode, cfg.levels.walk_max_gap_ticks);
+    }
+    // V10.15: Level sides nudged / merged off a shared tick
+    let (mut collisions_nudged, mut collisions_merged) = (0u64, 0u64);
+    if cfg.levels.price_collision != PriceCollision::Stack {
+        info!("[LADDER] Levels sharing a tick: {:?}", cfg.levels.price_collision);
+    }
+    // V10.15: Outer layers re-quote on slower timers than the tick
+    let mut refresh_tiers = RefreshTiers::new(&cfg.levels.tiers);
+    if !refresh_tiers.is_empty() {
+        info!("[TIERS] Inner levels every {}ms tick, {}", cadence.tick_ms, refresh_tiers.describe());
+    }
+    if cfg.levels.min_resting_ms > 0 {
+        info!("[MIN-REST] Orders rest at least {}ms before a refresh cancel", cfg.levels.min_resting_ms);
+    }
+    if cfg.levels.max_actions_per_tick > 0 {
+        info!("[BUDGET] {} actions/tick, priority {}", cfg.levels.max_actions_per_tick, level_priority.name());
+    }
+    if cfg.latency_probe.enabled {
+        info!("[PROBE] Enabled every {}s at -{}bps, alert > {}ms",
+            cfg.latency_probe.interval_secs, cfg.latency_probe.offset_bps, cfg.latency_probe.alert_ms);
+    }
+    
+    // V10.15: UTC stand-down windows
+    let schedule = Schedule::parse(&cfg.schedule.pause_windows_utc)?;
+    let mut schedule_paused = false;
+    if !schedule.is_empty() {
+        info!("[SCHEDULE] Pause windows (UTC): {}", schedule.describe());
+    }
+    
+    // V10.15: Max hold time hard stop - once tripped, nothing quotes until restart
+    let mut hold_guard = HoldGuard::default();
+    let mut halted = false;
+    let mut rest_auth_failures: u32 = 0;
+    let mut recon_latency = LatencyStats::new();
+    if cfg.risk.max_hold_secs > 0 {
+        info!("[RISK] Max hold {}s outside ±{} SOL", cfg.risk.max_hold_secs, cfg.risk.hold_band_sol);
+    }
+    
+    // V10.15: Operator on/off switch per symbol, no restart needed
+    let mut symbol_control = SymbolControl::new(&cfg.control_file);
+    let mut symbol_disabled = false;
+    // V10.15: Ladder indices switched off (config, or the control file at runtime)
+    let mut levels_off: BTreeSet<usize> = cfg.levels.disabled_levels.iter().copied().collect();
+    if !levels_off.is_empty() {
+        info!("[CONTROL] Levels disabled: {}", levels_off.iter().map(|i| format!("L{}", i + 1)).collect::<Vec<_>>().join(" "));
+    }
+    if !cfg.control_file.is_empty() {
+        info!("[CONTROL] Watching {} for disabled symbols", cfg.control_file);
+    }
+    
+    // V10.15: Delisted / suspended symbol - halt on not-trading rejects or the
+    // symbol check, resume when the check sees trading again
+    let mut market_status = MarketStatus::new(cfg.market_status.reject_threshold, Duration::from_secs(cfg.market_status.min_halt_secs));
+    let mut market_halted = false;
+    // V10.15: Placement gate after an order WS reconnect
+    let mut resync = Resync::default();
+    let mut symbol_check = tokio::time::interval(Duration::from_secs(cfg.market_status.check_secs.max(1)));
+    
+    // V10.15: Base ladder bps - the table, or re-spaced by fill probability
+    let mut spacing: Vec<f64> = levels().iter().map(|(bps, _)| *bps).collect();
+    let mut last_respace: Option<Instant> = None;
+    // V10.15: Rebate-only per-side widening after would-cross rejects
+    let mut cross_widen: HashMap<(i32, bool), f64> = HashMap::new();
+    if cfg.quoting.rebate_only {
+        info!("[MODE] Rebate-only: post-only everywhere, no taker flatten, would-cross widens a level by {}bps",
+            cfg.quoting.rebate_only_widen_bps);
+    }
+    let qc = &cfg.quoting;
+    if (qc.bid_spread_mult, qc.ask_spread_mult, qc.bid_size_mult, qc.ask_size_mult) != (1.0, 1.0, 1.0, 1.0) {
+        info!("[MODE] Per-side multipliers: spread bid x{} ask x{}, size bid x{} ask x{}",
+            qc.bid_spread_mult, qc.ask_spread_mult, qc.bid_size_mult, qc.ask_size_mult);
+    }
+    if qc.own_depth_k > 0.0 {
+        info!("[MODE] Own-depth spread: {}bps per unit own/natural past {} within {}bps of touch, +{}/-{}bps",
+            qc.own_depth_k, qc.own_depth_neutral, qc.own_depth_window_bps, qc.own_depth_max_widen_bps, qc.own_depth_max_tighten_bps);
+    }
+    if qc.post_only_backoff_ticks > 0 {
+        info!("[MODE] Post-only backoff: up to {} ticks inside the KuCoin touch (when its book is on), one retry per would-cross",
+            qc.post_only_backoff_ticks);
+    }
+    if cfg.sizing.book_boost_coeff > 0.0 {
+        info!("[SIZING] Book-imbalance boost x{} past |imb| {}, capped x{}",
+            cfg.sizing.book_boost_coeff, cfg.sizing.book_boost_threshold, cfg.sizing.book_boost_max_mult);
+    }
+    if cfg.sizing.max_notional_per_level_usd > 0.0 {
+        info!("[SIZING] Each order capped at ${} notional", cfg.sizing.max_notional_per_level_usd);
+    }
+    if cfg.levels.spacing == SpacingMode::EqualFillProb {
+        info!("[LEVELS] Equal-fill-probability spacing, {}s horizon, re-spaced every {}s",
+            cfg.levels.fill_horizon_secs, cfg.levels.respace_secs);
+    }
+    
+    // V10.15: Rolling buy/sell fill volume for flow-based sizing
+    let mut fill_flow = FillFlow::new(Duration::from_secs(cfg.sizing.fill_imbalance_window_secs));
+    // V10.15: Per-level adverse markout and the min-edge re-quote gate
+    let mut level_edge = LevelEdge::new(
+        Duration::from_secs_f64(cfg.levels.edge_markout_secs.max(0.0)), cfg.levels.edge_adverse_alpha);
+    let mut edge_suppressed: Vec<(i32, bool)> = Vec::new();
+    if let Some(floor) = cfg.levels.min_edge_bps {
+        info!("[EDGE] Levels need {:.1}bps expected edge to re-quote (markout {}s)", floor, cfg.levels.edge_markout_secs);
+    }
+    // V10.15: Per-fill JSONL log for `analyze`
+    let mut trade_log = TradeLog::open(&cfg.trade_log)?;
+    if !cfg.trade_log.is_empty() {
+        info!("[TRADE-LOG] Appending fills to {}", cfg.trade_log);
+    }
+    // V10.15: Traded volume, maker/taker, rolling hour and since start
+    let mut turnover = Turnover::new(Duration::from_secs(3600));
+    // V10.15: Decaying skew toward unwinding the latest fills
+    let mut post_fill_skew = PostFillSkew::new(cfg.quoting.post_fill_skew_bps,
+        Duration::from_secs_f64(cfg.quoting.post_fill_half_life_secs.max(0.0)));
+    
+    // V10.15: Systemic placement-error circuit breaker
+    let mut entry_breaker = cfg.entry_breaker.enabled.then(|| {
+        let ec = &cfg.entry_breaker;
+        info!("[ENTRY] Breaker: freeze {}s when > {:.0}% of placements fail over {}s (min {})",
+            ec.cooldown_secs, ec.max_error_rate * 100.0, ec.window_secs, ec.min_samples);
+        EntryBreaker::new(Duration::from_secs(ec.window_secs), ec.min_samples, ec.max_error_rate,
+            Duration::from_secs(ec.cooldown_secs))
+    });
+    
+    // V10.15: Markout watch for scratching toxic fills
+    let mut scratch_watch = ScratchWatch::new(
+        Duration::from_secs_f64(cfg.quoting.scratch_markout_secs.max(0.0)), cfg.quoting.scratch_toxic_bps);
+    if cfg.quoting.scratch_on_toxic {
+        info!("[SCRATCH] Fills {}bps against us after {}s are scratched {} tick(s) past the fill",
+            cfg.quoting.scratch_toxic_bps, cfg.quoting.scratch_markout_secs, cfg.quoting.scratch_exit_ticks);
+    }
     
     let mut ofi_paused = false;
+    // V10.15: Optional hedge on a second venue, rebalanced every recon
+    let mut hedge = cfg.hedge.enabled.then(|| {
+        let hc = &cfg.hedge;
+        info!("[HEDGE] Hedging {:.0}% of inventory as {} on the log-only venue, band {}",
+            hc.ratio * 100.0, hc.symbol, hc.band);
+        HedgeManager::new(Arc::new(LoggingHedgeVenue), hc.symbol.clone(), hc.ratio, hc.band, hc.min_size)
+    });
+    // V10.15: Resume needs OFI and aggTrade flow calm for a minimum time
+    let mut ofi_resume = ResumeGate::new(strat.ofi_resume_threshold, cfg.signals.resume_max_trade_imbalance,
+        Duration::from_secs(cfg.signals.resume_min_calm_secs));
     let mut mom_paused = false;
+    let mut gate = QuoteGate::default();
     
     // V10: Graceful shutdown flag
     let mut shutting_down = false;
     
+    // V10.15: Drain - set by --drain or SIGUSR1 on a running instance
+    let drain_deadline = Duration::from_secs(cfg.drain.deadline_secs);
+    let mut drain = drain_on_start.then(|| Drain::new(drain_deadline, Instant::now()));
+    let mut usr1 = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::user_defined1())?;
+    if drain.is_some() {
+        info!("[DRAIN] Started with --drain, deadline {}s", cfg.drain.deadline_secs);
+    }
+    
     loop {
         tokio::select! {
             // V10: Graceful shutdown on Ctrl+C
-            _ = tokio::signal::ctrl_c(), if !shutting_down => {
+            _ = shutdown.notified(), if !shutting_down => {
                 info!("[SHUTDOWN] Received SIGINT, initiating graceful shutdown...");
                 shutting_down = true;
                 
                 // Stop placing new orders (flag is set)
-                // Cancel all orders via REST
-                cancel_all_orders(&auth_shutdown).await;
-                info!("[SHUTDOWN] Cancelled all orders");
-                
-                // Final reconciliation
-                tokio::time::sleep(Duration::from_millis(500)).await;
-                let final_orders = poll_active_orders(&auth_shutdown).await;
-                info!("[SHUTDOWN] Final order count: {}", final_orders.len());
+                // V10.15: Bulk DELETE, then per-order fallback until clear or 10s
+                let api = RestCancel { auth: &auth_shutdown, mode: cfg.account.trade_mode };
+                let r = cancel_sweep::cancel_all_with_fallback(&api, Duration::from_secs(10), Duration::from_millis(500)).await;
+                info!("[SHUTDOWN] Cancelled bulk:{} by_id:{} by_client_oid:{} rounds:{} | Final order count: {}",
+                    r.bulk, r.by_order_id, r.by_client_oid, r.rounds, r.remaining);
                 
                 // Log final PnL
-                let inv = pnl.inv();
-                let m = data.read().await.mid;
-                info!("═══════════════════════════════════════════════════════════════");
-                info!("[SHUTDOWN] FINAL PnL REPORT");
-                info!("Runtime: {}s | Buys:{} Sells:{} | Matches:{}", 
-                    start.elapsed().as_secs(), pnl.buys, pnl.sells, pnl.matched);
-                info!("Inventory: {:.4} SOL (${:.2})", inv, inv * m);
-                info!("SPREAD: ${:.4} | REBATE: ${:.4} | NET: ${:.4}", pnl.spread, pnl.reb, pnl.net());
-                info!("═══════════════════════════════════════════════════════════════");
+                log_final_report(&pnl, data.read().await.mid, start.elapsed());
                 
                 // V10.5: Save FIFO state for next restart
                 pnl.save();
                 info!("[SHUTDOWN] FIFO state saved to disk");
                 
-                break;
+                // V10.15: Orders are cleared over REST - stop the order WS reconnecting
+                ws.shutdown().await;
+            }
+            _ = panic_trigger.notified(), if !shutting_down => {
+                error!("[PANIC] Emergency cancel-all: WS + REST in parallel, halting");
+                halted = true;
+                let mut ids: HashSet<String> = active_orders.read().await.iter().map(|o| o.order_id.clone()).collect();
+                ids.extend(level_orders.values().flat_map(|(b, a)| [b, a])
+                    .filter_map(|s| s.order_id().map(String::from)));
+                let ids: Vec<String> = ids.into_iter().collect();
+                let panic_start = Instant::now();
+                let (ws_ok, rest_ok) = panic_cancel(&ws, &auth_shutdown, &ids).await;
+                let msg = format!("panic cancel in {:?}: WS cancelAll {} | REST bulk sent | {}/{} per-order REST ok - halted, restart required",
+                    panic_start.elapsed(), if ws_ok { "ok" } else { "failed" }, rest_ok, ids.len());
+                error!("[PANIC] {}", msg);
+                alerter.alert("panic", &msg);
+                event_log::record(&recent_events, "halt", msg).await;
+                status::publish(&status, "risk", serde_json::json!({ "halted": true, "reason": "panic" })).await;
+                recon.reset_immediately();
+            }
+            _ = usr1.recv(), if !shutting_down && drain.is_none() => {
+                info!("[DRAIN] SIGUSR1 - no new orders, letting {} resting orders run out (deadline {}s)",
+                    active_orders.read().await.len(), cfg.drain.deadline_secs);
+                drain = Some(Drain::new(drain_deadline, Instant::now()));
+                event_log::record(&recent_events, "drain", "started by SIGUSR1").await;
+                recon.reset_immediately();
             }
             _ = recon.tick(), if !shutting_down => {
+                // V10.15: Post-reconnect window over - relax to the normal cadence
+                let now = Instant::now();
+                if recon_fast_until.is_some_and(|until| now >= until) {
+                    recon_fast_until = None;
+                    let period = Duration::from_millis(cadence.recon_ms);
+                    recon = tokio::time::interval_at(tokio::time::Instant::from_std(now + period), period);
+                    info!("[RECON] Post-reconnect window over, back to {}ms", cadence.recon_ms);
+                }
                 // ═══ V10.3: ORDER RECONCILIATION (Institutional Grade) ═══
-                let orders = poll_active_orders(&auth4).await;
-                let new_bal = poll_balances(&auth3).await;
+                // V10.15: A failed poll is not an empty book - skip the cycle instead of
+                // reconciling against nothing. Transient errors retry next second;
+                // repeated auth failures halt quoting.
+                // V10.15: Both polls in parallel on the pooled client; RTT is the slower one
+                let recon_start = Instant::now();
+                let (orders_res, bal_res) = tokio::join!(
+                    poll_active_orders(&auth4, cfg.account.trade_mode),
+                    poll_balances(&auth3, cfg.account.trade_mode));
+                recon_latency.record(recon_start.elapsed());
+                let polled = match (orders_res, bal_res) {
+                    (Ok(orders), Ok(bal)) => { rest_auth_failures = 0; Some((orders, bal)) }
+                    (Err(e), _) | (_, Err(e)) => {
+                        if e.is_transient() {
+                            warn!("[RECON] REST {} - skipping cycle", e);
+                        } else if let RestError::Auth(_) = e {
+                            rest_auth_failures += 1;
+                            error!("[RECON] REST {} ({}/{})", e, rest_auth_failures, MAX_REST_AUTH_FAILURES);
+                            if rest_auth_failures >= MAX_REST_AUTH_FAILURES && !halted {
+                                alerter.alert("rest_auth", &format!("{} - halting, restart required", e));
+                                event_log::record(&recent_events, "halt", format!("REST auth: {}", e)).await;
+                                halted = true;
+                            }
+                        } else {
+                            warn!("[RECON] REST {} - skipping cycle", e);
+                        }
+                        None
+                    }
+                };
+                let Some((orders, new_bal)) = polled else { continue };
                 *balances.write().await = new_bal.clone();
                 *active_orders.write().await = orders.clone();
+                resync.on_recon();
                 
                 // V10.5c: Update KuCoin mid for weighted fair price
                 let kc_mid = poll_kucoin_ticker().await;
@@ -607,6 +2682,9 @@ async fn main() -> Result<()> {
                 // V10.3: Reset inflight commitments (anything not confirmed is orphan)
                 commitments.reset_inflight();
                 
+                // V10.15: Fills the poll's dealSize already covers don't come off a level twice
+                fill_ledger.on_recon(orders.iter().map(|o| (o.order_id.as_str(), o.deal)));
+                
                 // Build set of order IDs active on exchange
                 let active_ids: HashSet<String> = orders.iter().map(|o| o.order_id.clone()).collect();
                 
@@ -619,23 +2697,16 @@ async fn main() -> Result<()> {
                 for (_, (bid_state, ask_state)) in level_orders.iter_mut() {
                     // Handle bid state
                     match bid_state {
-                        LevelOrderState::Live { order_id, price, remaining_size } => {
+                        LevelOrderState::Live { order_id, .. } => {
                             if !active_ids.contains(order_id) {
-                                // Order filled or cancelled externally
+                                // Order filled, cancelled externally or GTT-expired
                                 *bid_state = LevelOrderState::Empty;
                             } else {
                                 tracked_ids.insert(order_id.clone());
                                 // V10.5: Update remaining size from exchange and recalc commitment
                                 if let Some(o) = orders.iter().find(|o| &o.order_id == order_id) {
-                                    // Update remaining_size if partial fill occurred
-                                    if (o.size - *remaining_size).abs() > 0.001 {
-                                        *bid_state = LevelOrderState::Live { 
-                                            order_id: order_id.clone(), 
-                                            price: *price, 
-                                            remaining_size: o.size 
-                                        };
-                                    }
-                                    commitments.live_usdt += o.size * o.price;
+                                    // V10.15: Always sync - the post-recon commitment check relies on it
+                                    commitments.live_usdt += sync_live(bid_state, o) * o.price;
                                 }
                             }
                         }
@@ -677,21 +2748,15 @@ async fn main() -> Result<()> {
                     
                     // Handle ask state
                     match ask_state {
-                        LevelOrderState::Live { order_id, price, remaining_size } => {
+                        LevelOrderState::Live { order_id, .. } => {
                             if !active_ids.contains(order_id) {
                                 *ask_state = LevelOrderState::Empty;
                             } else {
                                 tracked_ids.insert(order_id.clone());
                                 // V10.5: Update remaining size from exchange
                                 if let Some(o) = orders.iter().find(|o| &o.order_id == order_id) {
-                                    if (o.size - *remaining_size).abs() > 0.001 {
-                                        *ask_state = LevelOrderState::Live { 
-                                            order_id: order_id.clone(), 
-                                            price: *price, 
-                                            remaining_size: o.size 
-                                        };
-                                    }
-                                    commitments.live_sol += o.size;
+                                    // V10.15: Always sync - the post-recon commitment check relies on it
+                                    commitments.live_sol += sync_live(ask_state, o);
                                 }
                             }
                         }
@@ -730,22 +2795,30 @@ async fn main() -> Result<()> {
                 }
                 
                 // V10.3: Rate-limited orphan cancellation
-                let mut orphan_budget = MAX_ORPHAN_CANCELS_PER_TICK;
                 // Clean up stale entries from recently_cancelled
                 recently_cancelled.retain(|_, t| t.elapsed().as_secs() < 10);
                 
-                for order in &orders {
-                    if !tracked_ids.contains(&order.order_id) && orphan_budget > 0 {
-                        if !recently_cancelled.contains_key(&order.order_id) {
-                            info!("[ORPHAN] Cancelling untracked order: {} {} @ ${:.2}", 
-                                order.side, order.order_id, order.price);
-                            let _ = ws.cancel_order(WsCancelRequest {
-                                symbol: SYM.into(), order_id: Some(order.order_id.clone()), client_oid: None
-                            }).await;
-                            recently_cancelled.insert(order.order_id.clone(), Instant::now());
-                            orphan_budget -= 1;
-                        }
+                // V10.15: Budget widens with |inventory| - cleanup frees risk and capital
+                let orphan_budget = cadence.orphan_cancel_budget(pnl.inv(), strat.max_inv_sol);
+                let orphans = detect_orphans(&orders, &tracked_ids, &recently_cancelled, orphan_budget);
+                if !orphans.is_empty() {
+                    info!("[ORPHAN] Budget {} this recon (inv {:.3} / {:.1})", orphan_budget, pnl.inv(), strat.max_inv_sol);
+                }
+                for order_id in orphans {
+                    if let Some(order) = orders.iter().find(|o| o.order_id == order_id) {
+                        info!("[ORPHAN] Cancelling untracked order: {} {} @ ${:.2}", 
+                            order.side, order.order_id, order.price);
                     }
+                    let _ = ws.cancel_order(WsCancelRequest {
+                        symbol: symbol().name.clone(), order_id: Some(order_id.clone()), client_oid: None
+                    }).await;
+                    recently_cancelled.insert(order_id, Instant::now());
+                }
+                
+                // V10.15: Verify live commitments against the final level states
+                if let Some((du, ds)) = commitments.repair_from_levels(&level_orders) {
+                    warn!("[RECON] Commitment drift USDT:{:+.2} SOL:{:+.4} - reset from level state", du, ds);
+                    event_log::record(&recent_events, "drift", format!("commitment USDT:{:+.2} SOL:{:+.4}", du, ds)).await;
                 }
                 
                 // Log mismatch if any
@@ -753,66 +2826,399 @@ async fn main() -> Result<()> {
                     info!("[RECON] Active:{} Tracked:{} LiveUSDT:{:.2} LiveSOL:{:.3}", 
                         orders.len(), tracked_ids.len(), commitments.live_usdt, commitments.live_sol);
                 }
+                
+                if let Some(h) = hedge.as_mut() {
+                    if let Err(e) = h.update(pnl.inv()).await {
+                        warn!("[HEDGE] Rebalance failed: {}", e);
+                    }
+                }
+                
+                // V10.15: Drain progress - exit once the book is empty or time is up
+                if let Some(d) = drain.as_mut() {
+                    let left = orders.iter()
+                        .filter(|o| !o.client_oid.starts_with(latency_probe::PROBE_OID_PREFIX)).count();
+                    let step = d.check(left, Instant::now());
+                    match step {
+                        DrainStep::Waiting { changed: true } => {
+                            info!("[DRAIN] {} orders resting, {}s to deadline", left, d.remaining(Instant::now()).as_secs());
+                        }
+                        DrainStep::Waiting { changed: false } => {}
+                        DrainStep::Done => info!("[DRAIN] All orders gone, exiting"),
+                        DrainStep::Expired => {
+                            warn!("[DRAIN] Deadline passed with {} orders resting - cancelling", left);
+                            let api = RestCancel { auth: &auth_shutdown, mode: cfg.account.trade_mode };
+                            let r = cancel_sweep::cancel_all_with_fallback(&api, Duration::from_secs(10), Duration::from_millis(500)).await;
+                            info!("[DRAIN] Cancelled bulk:{} by_id:{} by_client_oid:{} | Final order count: {}",
+                                r.bulk, r.by_order_id, r.by_client_oid, r.remaining);
+                        }
+                    }
+                    if matches!(step, DrainStep::Done | DrainStep::Expired) {
+                        event_log::record(&recent_events, "drain", format!("finished: {:?}, {} left", step, left)).await;
+                        // Catch fills since the last poll before the final report
+                        let page = poll_fills(&auth2, &mut seen_trade_ids, cfg.account.trade_mode).await.unwrap_or_default();
+                        for fill in &page.fee_updates {
+                            pnl.correct_fee(fill, cfg.fees.taker_bps);
+                        }
+                        for fill in &page.fills {
+                            pnl.record_fill(fill, cfg.fees.taker_bps);
+                        }
+                        log_final_report(&pnl, data.read().await.mid, start.elapsed());
+                        pnl.save();
+                        shutting_down = true;
+                    }
+                }
+            }
+            upd = order_updates.recv(), if !shutting_down => {
+                match upd {
+                    Ok(u) if matches!(u.kind, OrderUpdateKind::Canceled | OrderUpdateKind::Done) => {
+                        if confirm_cancel(&mut level_orders, &u.order_id) {
+                            debug!("[CANCEL] {} confirmed via private feed", u.order_id);
+                        }
+                    }
+                    // V10.15: The feed is the primary fill source; the poll only reconciles
+                    Ok(u) if matches!(u.kind, OrderUpdateKind::Match { .. }) => {
+                        if let Some(fill) = ws_fill(&u, &mut seen_trade_ids) {
+                            ws_fills += 1;
+                            new_fills.push(fill);
+                        }
+                    }
+                    Ok(_) => {}
+                    // Missed confirmations fall back to recon
+                    Err(broadcast::error::RecvError::Lagged(k)) => warn!("[KUCOIN-WS] Missed {} order updates", k),
+                    Err(broadcast::error::RecvError::Closed) => {}
+                }
+            }
+            ev = ws_events.recv(), if !shutting_down => {
+                match ev {
+                    Ok(ConnectionEvent::Connected) => {
+                        // Anything sent while down may or may not have landed - reconcile now
+                        info!("[WS] Order session reconnected, reconciling immediately");
+                        event_log::record(&recent_events, "ws", "order session connected").await;
+                        recon.reset_immediately();
+                        // V10.15: ...and keep reconciling faster while state settles
+                        if cadence.recon_fast_secs > 0 {
+                            let now = Instant::now();
+                            recon_fast_until = Some(now + Duration::from_secs(cadence.recon_fast_secs));
+                            recon = tokio::time::interval(cadence.recon_period(recon_fast_until, now));
+                            info!("[RECON] Tightened to {}ms for {}s after reconnect", cadence.recon_fast_ms, cadence.recon_fast_secs);
+                        }
+                        // V10.15: Fills may have been missed too - poll now, no placing until both are back
+                        if cfg.reconnect.resync_before_placing {
+                            resync.on_reconnect(pnl.inv(), Instant::now());
+                            fp.reset_immediately();
+                        }
+                    }
+                    Ok(ConnectionEvent::Disconnected) => {
+                        warn!("[WS] Order session disconnected");
+                        event_log::record(&recent_events, "ws", "order session disconnected").await;
+                    }
+                    Ok(ConnectionEvent::ReconnectExhausted) => {
+                        alerter.alert("ws_order", "order WS reconnect attempts exhausted, no WS order entry");
+                        event_log::record(&recent_events, "ws", "reconnect attempts exhausted").await;
+                    }
+                    Err(broadcast::error::RecvError::Lagged(k)) => warn!("[WS] Missed {} connection events", k),
+                    Err(broadcast::error::RecvError::Closed) => {}
+                }
+            }
+            _ = probe.tick(), if cfg.latency_probe.enabled && !shutting_down && !halted && !schedule_paused && !symbol_disabled && !market_halted && drain.is_none() => {
+                let m = data.read().await.mid;
+                // One probe at a time; skip the slot if the last is still waiting
+                if m > 0.0 && !probe_busy.swap(true, Ordering::SeqCst) {
+                    probe_seq += 1;
+                    let pc = cfg.latency_probe.clone();
+                    let req = WsOrderRequest {
+                        symbol: symbol().name.clone(), side: "buy".into(),
+                        price: sym.price_str(m * (1.0 - pc.offset_bps / 10000.0)),
+                        size: sym.size_str(sym.base_min_size),
+                        client_oid: format!("{}{}", latency_probe::PROBE_OID_PREFIX, probe_seq),
+                        order_type: "limit".into(), time_in_force: Some("GTC".into()),
+                        post_only: Some(true), stp: cfg.quoting.stp, cancel_after: None
+                    };
+                    let (ws, events, stats, busy, alerter) =
+                        (ws.clone(), private_ws.subscribe(), probe_stats.clone(), probe_busy.clone(), alerter.clone());
+                    tokio::spawn(async move {
+                        latency_probe::run_probe(ws, events, req, pc, stats, alerter).await;
+                        busy.store(false, Ordering::SeqCst);
+                    });
+                }
+            }
+            // V10.15: Runs while halted too - it's how a market halt resumes
+            _ = symbol_check.tick(), if cfg.market_status.enabled && !shutting_down => {
+                match poll_symbol_trading().await {
+                    Some(enable_trading) => market_status.on_symbol_status(enable_trading, Instant::now()),
+                    None => debug!("[MARKET] Symbol check failed"),
+                }
             }
             _ = fp.tick(), if !shutting_down => {
-                for (side, sz, px) in poll_fills(&auth2, &mut seen).await {
-                    let r = sz * px * REBATE / 10000.0;
-                    if side == "buy" { pnl.buy(px, sz, r); } else { pnl.sell(px, sz, r); }
+                let page = match poll_fills(&auth2, &mut seen_trade_ids, cfg.account.trade_mode).await {
+                    Ok(page) => {
+                        resync.on_fills();
+                        page
+                    }
+                    Err(e) => {
+                        warn!("[FILL] Poll failed: {}", e);
+                        FillPage::default()
+                    }
+                };
+                // Anything the feed already booked was skipped by tradeId - only its fee is taken
+                for fill in &page.fee_updates {
+                    pnl.correct_fee(fill, cfg.fees.taker_bps);
                 }
+                rest_fills += page.fills.len() as u64;
+                new_fills.extend(page.fills);
             }
-            _ = tick.tick(), if !shutting_down => {
-                n += 1;
-                let md = data.read().await;
-                // V10.5c: Use weighted fair mid (0.8 Binance + 0.2 KuCoin)
-                let m = md.fair_mid();
+            // V10.15: Draining leaves resting orders exactly as they are - no refresh, no place
+            _ = tick.tick(), if !shutting_down && !halted && drain.is_none() => {
+                let mut md = data.write().await;
+                // V10.15: Ladder mid per `quoting.mid_source` (Binance, KuCoin or a blend)
+                let m = cfg.quoting.mid_source.mid(md.mid, md.local_mid());
                 let binance_mid = md.mid;  // V10.11: For refresh check
+                // V10.15: Smoothed reference for the ladder anchor and refresh targets
+                let anchor_ref = md.anchor_ema.value_at(Instant::now()).filter(|_| cfg.quoting.anchor_half_life_ms > 0);
+                let local_mid = md.local_mid();
                 let kucoin_mid = md.kucoin_mid;  // V10.9: For BBO safety check
-                let ofi = md.ofi;
+                let kc_touch = md.kc_touch;
+                // V10.15: Binance OFI, KuCoin imbalance or a blend, per config
+                let ofi = cfg.signals.toxicity(md.fresh_ofi(Instant::now(), ofi_stale), md.kc_imbalance);
+                let kc_imb = md.kc_imbalance;
+                let trade_imb = md.trade_flow.imbalance(Instant::now());
                 let sigma = md.sigma();
                 let momentum = md.momentum();
+                let mid_at = md.mid_time(cfg.quoting.mid_source);
                 drop(md);
                 
                 let bal = balances.read().await.clone();
+                let bal = effective_balances(&bal, own_order_holds(&active_orders.read().await, &level_orders));
+                
+                gate.clear();
+                
+                // V10.15: Max hold time - checked first, overrides everything below
+                let rc = &cfg.risk;
+                let held = hold_guard.update(pnl.inv() - rc.inv_target, rc.hold_band_sol, Instant::now());
+                if let Some(held) = held.filter(|d| rc.max_hold_secs > 0 && d.as_secs() >= rc.max_hold_secs) {
+                    let inv = pnl.inv();
+                    let msg = format!("inventory {:.4} SOL outside {}±{} for {}s (mid {:.4}) - flattening and halting",
+                        inv, rc.inv_target, rc.hold_band_sol, held.as_secs(), m);
+                    error!("[RISK] Max hold breached: {}", msg);
+                    alerter.alert("max_hold", &msg);
+                    event_log::record(&recent_events, "halt", format!("max hold: {}", msg)).await;
+                    halted = true;
+                    
+                    cancel_all_orders(&auth_shutdown).await;
+                    let anchor = current_exit_anchor(&kc_book, cfg.signals.kucoin_stale_ms, m, binance_mid).await;
+                    match flatten_inventory(&ws, &sym, &cfg, inv, anchor).await {
+                        Ok(sent) => warn!("[RISK] {}", sent),
+                        Err(e) => { alerter.alert("max_hold_flatten", &e); }
+                    }
+                    status::publish(&status, "risk", serde_json::json!({
+                        "halted": true, "reason": "max_hold", "inventory": inv,
+                        "held_secs": held.as_secs(), "mid": m,
+                    })).await;
+                    error!("[RISK] HALTED - restart required");
+                    continue;
+                }
+                
+                // V10.15: Symbol switched off - cancel once, then sit idle until re-enabled
+                symbol_control.refresh();
+                if symbol_control.is_enabled(&symbol().name) == symbol_disabled {
+                    symbol_disabled = !symbol_disabled;
+                    event_log::record(&recent_events, "control", format!("{} {}", symbol().name, if symbol_disabled { "disabled" } else { "enabled" })).await;
+                    if symbol_disabled {
+                        info!("[CONTROL] {} disabled, cancelling all orders", symbol().name);
+                        cancel_all_orders(&auth_shutdown).await;
+                    } else {
+                        info!("[CONTROL] {} re-enabled, resuming", symbol().name);
+                    }
+                }
+                // V10.15: Layers switched on/off - disabled ones are skipped and their orders cancelled below
+                let want_off: BTreeSet<usize> = symbol_control.disabled_levels()
+                    .unwrap_or(&cfg.levels.disabled_levels).iter().copied().collect();
+                if want_off != levels_off {
+                    for (i, off) in want_off.difference(&levels_off).map(|i| (i, true))
+                        .chain(levels_off.difference(&want_off).map(|i| (i, false))) {
+                        let at = levels().get(*i).map_or("not in the ladder".to_string(), |l| format!("{}bps", l.0));
+                        info!("[CONTROL] Level L{} ({}) {}", i + 1, at, if off { "disabled, cancelling its orders" } else { "re-enabled" });
+                        event_log::record(&recent_events, "control", format!("level L{} {}", i + 1, if off { "disabled" } else { "enabled" })).await;
+                    }
+                    levels_off = want_off;
+                }
+                if symbol_disabled {
+                    gate.set(GateReason::SymbolDisabled, GateScope::Both, format!("{} off in {}", symbol().name, cfg.control_file));
+                    gate.publish();
+                    status::publish(&status, "quote_gate", gate.to_json()).await;
+                    continue;
+                }
                 
-                if m <= 0.0 { continue; }
+                // V10.15: Reconnect resync - no placing on a risk view from before the outage
+                if let Some(r) = resync.take_done(Instant::now()) {
+                    let msg = format!("resynced in {}ms: inventory {:+.4} -> {:+.4} SOL, balances USDT {:.2} SOL {:.4}",
+                        r.took.as_millis(), r.pre_inv, pnl.inv(), bal.usdt, bal.sol);
+                    info!("[RESYNC] {}", msg);
+                    event_log::record(&recent_events, "ws", msg).await;
+                }
+                if resync.is_pending() {
+                    gate.set(GateReason::Resync, GateScope::Both, "waiting on recon and fill poll after reconnect");
+                    gate.publish();
+                    status::publish(&status, "quote_gate", gate.to_json()).await;
+                    continue;
+                }
+                
+                // V10.15: Symbol not trading - cancel and try to flatten once, then
+                // sit idle until the symbol check reports it trading again. Off
+                // when market_status is disabled: nothing could resume it.
+                let market_halt = market_status.halted().filter(|_| cfg.market_status.enabled);
+                if market_halt.is_some() != market_halted {
+                    market_halted = !market_halted;
+                    match market_halt {
+                        Some(reason) => {
+                            let msg = format!("{} not trading ({}) - quoting halted", symbol().name, reason.describe());
+                            error!("[MARKET] {}", msg);
+                            alerter.alert("not_trading", &msg);
+                            event_log::record(&recent_events, "market", msg).await;
+                            cancel_all_orders(&auth_shutdown).await;
+                            if cfg.market_status.flatten {
+                                let anchor = current_exit_anchor(&kc_book, cfg.signals.kucoin_stale_ms, m, binance_mid).await;
+                                match flatten_inventory(&ws, &sym, &cfg, pnl.inv(), anchor).await {
+                                    Ok(sent) => warn!("[MARKET] {}", sent),
+                                    Err(e) => { alerter.alert("not_trading_flatten", &e); }
+                                }
+                            }
+                        }
+                        None => {
+                            info!("[MARKET] {} trading again - resuming", symbol().name);
+                            alerter.alert("not_trading", &format!("{} trading again - quoting resumed", symbol().name));
+                            event_log::record(&recent_events, "market", "resume").await;
+                        }
+                    }
+                    status::publish(&status, "market_status", serde_json::json!({
+                        "halted": market_halted,
+                        "reason": market_halt.map(|r| r.describe()),
+                    })).await;
+                }
+                if let Some(reason) = market_halt {
+                    gate.set(GateReason::NotTrading, GateScope::Both, reason.describe());
+                    gate.publish();
+                    status::publish(&status, "quote_gate", gate.to_json()).await;
+                    continue;
+                }
+                
+                // V10.15: Scheduled stand-down - cancel once on entry, then sit idle
+                let unix_secs = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+                let window = schedule.active(unix_secs);
+                if window.is_some() != schedule_paused {
+                    schedule_paused = window.is_some();
+                    event_log::record(&recent_events, "schedule", window.map_or("resume".to_string(), |w| format!("pause {} UTC", w))).await;
+                    if let Some(w) = window {
+                        info!("[SCHEDULE] Pause: entering {} UTC window, cancelling all orders", w);
+                        cancel_all_orders(&auth_shutdown).await;
+                    } else {
+                        info!("[SCHEDULE] Resume: pause window over");
+                    }
+                }
+                if let Some(w) = window {
+                    gate.set(GateReason::Schedule, GateScope::Both, format!("window {} UTC", w));
+                    gate.publish();
+                    status::publish(&status, "quote_gate", gate.to_json()).await;
+                    continue;
+                }
+                
+                if m <= 0.0 {
+                    gate.set(GateReason::NoMid, GateScope::Both, "waiting for reference mid");
+                    gate.publish();
+                    status::publish(&status, "quote_gate", gate.to_json()).await;
+                    continue;
+                }
+                
+                level_edge.update(m, Instant::now());
+                
+                // V10.15: Scratch toxic fills - reduce by the fill size (at most what's
+                // still open that way) near the fill price, post-only, expiring GTT
+                if cfg.quoting.scratch_on_toxic {
+                    for fill in scratch_watch.poll(m, Instant::now()) {
+                        let inv = pnl.inv();
+                        // V10.15: Only what's beyond the inventory target band that way
+                        let excess = reduction_to_target(inv, cfg.risk.inv_target, cfg.risk.flatten_band_sol);
+                        let open = if fill.is_buy { excess.max(0.0) } else { (-excess).max(0.0) };
+                        let size = truncate_to_increment(fill.size.min(open), sym.base_increment);
+                        let px = sym.round_price(scratch::scratch_price(&fill, cfg.quoting.scratch_exit_ticks, sym.price_increment, m));
+                        let side = if fill.is_buy { "sell" } else { "buy" };
+                        warn!("[SCRATCH] Toxic {} {} @ {:.4} ({:+.1}bps) - {} {} @ {:.4}",
+                            if fill.is_buy { "buy" } else { "sell" }, fill.size, fill.price, fill.markout_bps, side, size, px);
+                        if size <= 0.0 {
+                            continue;
+                        }
+                        let r = place_order_checked(&ws, &sym, WsOrderRequest {
+                            symbol: symbol().name.clone(), side: side.into(),
+                            price: sym.price_str(px), size: sym.size_str(size),
+                            client_oid: format!("{}{}", scratch::SCRATCH_OID_PREFIX, uuid::Uuid::new_v4().simple()),
+                            order_type: "limit".into(), time_in_force: Some("GTT".into()),
+                            post_only: Some(true), stp: cfg.quoting.stp, cancel_after: Some(cfg.quoting.scratch_ttl_secs.max(1)),
+                        }).await;
+                        match r {
+                            Ok(r) if r.success => {}
+                            Ok(r) => warn!("[SCRATCH] Exit rejected: {:?} {:?}", r.code, r.msg),
+                            Err(e) => warn!("[SCRATCH] Exit failed: {}", e),
+                        }
+                    }
+                }
                 
                 // V10: Count orders from local state (race-free)
-                let local_bid_count = level_orders.values()
+                // V10.15: ...plus this tick's placements, so one tick can't overshoot the cap
+                let mut local_bid_count = level_orders.values()
                     .filter(|(b, _)| !b.is_empty()).count();
-                let local_ask_count = level_orders.values()
+                let mut local_ask_count = level_orders.values()
                     .filter(|(_, a)| !a.is_empty()).count();
                 
                 // ═══ QUANT 1: OFI ═══
-                let (mut skip_bids, mut skip_asks) = if ofi_paused {
-                    if ofi.abs() < OFI_RESUME_THRESHOLD { ofi_paused = false; info!("[OFI] Resume"); (false, false) }
-                    else { (ofi < 0.0, ofi > 0.0) }
-                } else {
-                    if ofi.abs() > OFI_PAUSE_THRESHOLD { ofi_paused = true; info!("[OFI] Pause: {:.3}", ofi); }
-                    (ofi < -OFI_PAUSE_THRESHOLD, ofi > OFI_PAUSE_THRESHOLD)
-                };
+                if ofi_paused {
+                    let now = Instant::now();
+                    if ofi_resume.ready(ofi, trade_imb, now) {
+                        let inputs = format!("OFI {:.3} | trade imb {:.3} | calm {:.1}s",
+                            ofi, trade_imb, ofi_resume.calm_for(now).as_secs_f64());
+                        ofi_paused = false; info!("[OFI] Resume: {}", inputs);
+                        event_log::record(&recent_events, "resume", inputs).await;
+                    } else {
+                        debug!("[OFI] Holding pause: OFI {:.3} | trade imb {:.3} | calm {:.1}s",
+                            ofi, trade_imb, ofi_resume.calm_for(now).as_secs_f64());
+                    }
+                } else if ofi.abs() > strat.ofi_pause_threshold {
+                    ofi_resume.reset();
+                    ofi_paused = true; info!("[OFI] Pause: {:.3} (trade imb {:.3})", ofi, trade_imb);
+                    event_log::record(&recent_events, "pause", format!("OFI {:.3}", ofi)).await;
+                }
+                if ofi_paused {
+                    // Paused: skip the side the flow is running into
+                    let scope = if ofi < 0.0 { GateScope::Bids } else { GateScope::Asks };
+                    gate.set(GateReason::OfiPaused, scope, format!("OFI {:.3}", ofi));
+                }
                 
                 // ═══ QUANT 2: Smart Trend Filter ═══
-                let downtrend = momentum < -MOMENTUM_THRESHOLD;
-                let uptrend = momentum > MOMENTUM_THRESHOLD;
+                let downtrend = momentum < -strat.momentum_threshold;
+                let uptrend = momentum > strat.momentum_threshold;
                 let inv = pnl.inv();
                 
                 // Downtrend: skip BIDS only (not asks) when not holding long
                 // V10.5b: Fixed - was using continue which skipped asks too!
                 if downtrend {
-                    if !mom_paused { info!("[TREND] DOWN {:.2}% - selling only", momentum * 100.0); mom_paused = true; }
-                    if inv <= 0.05 { skip_bids = true; }  // Only skip bids, let asks continue
+                    if !mom_paused {
+                        info!("[TREND] DOWN {:.2}% - selling only", momentum * 100.0); mom_paused = true;
+                        event_log::record(&recent_events, "pause", format!("downtrend {:.2}%", momentum * 100.0)).await;
+                    }
+                    // Only skip bids, let asks continue
+                    gate.set(GateReason::Downtrend, GateScope::Bids,
+                        format!("Mom {:.2}% inv {:.3}", momentum * 100.0, inv));
                 } else if !uptrend && mom_paused { 
                     info!("[TREND] Normal"); 
                     mom_paused = false; 
+                    event_log::record(&recent_events, "resume", "trend normal").await;
                 }
                 
-                // Uptrend: keep quoting but widen spreads to capture momentum
-                let uptrend_multiplier = if uptrend {
-                    if !mom_paused { info!("[TREND] UP {:.2}% - widening spreads 1.5x", momentum * 100.0); mom_paused = true; }
-                    1.5  // Widen asks by 50% to capture more during rally
-                } else { 1.0 };
-                
-                skip_bids = skip_bids || downtrend;
+                // Uptrend: keep quoting both sides; logged once, "Normal" when it ends
+                if uptrend && !mom_paused {
+                    info!("[TREND] UP {:.2}% - quoting both sides", momentum * 100.0);
+                    mom_paused = true;
+                }
                 
                 // ═══ V10.13: Inventory-Aware Trend Protection ═══
                 // Cancel existing orders that would INCREASE adverse position
@@ -826,34 +3232,223 @@ async fn main() -> Result<()> {
                 let cancel_adverse_bids = strong_down && !inv_short;
                 // Cancel asks during strong uptrend, UNLESS we're long (want to unload)
                 let cancel_adverse_asks = strong_up && !inv_long;
+                if cancel_adverse_bids {
+                    gate.set(GateReason::TrendProtect, GateScope::Bids, format!("OFI {:.2} inv {:.3}", ofi, inv));
+                }
+                if cancel_adverse_asks {
+                    gate.set(GateReason::TrendProtect, GateScope::Asks, format!("OFI {:.2} inv {:.3}", ofi, inv));
+                }
                 
                 // ═══ QUANT 3: Inventory Skew ═══
-                let skew_bps = inv * GAMMA * sigma * sigma * 10000.0;
+                let skew_bps = inv * strat.gamma * sigma * sigma * 10000.0
+                    + basis_skew_bps(kucoin_mid, binance_mid, cfg.quoting.basis_sensitivity, cfg.quoting.basis_max_bps)
+                    + post_fill_skew.boost_bps(Instant::now());
                 
                 // ═══ QUANT 4: Dynamic Sizing ═══
-                let base_sz = ((ORDER_USD / m) / 0.01).round() * 0.01;
+                let base_sz = sym.round_size(strat.order_usd / m);
                 let (bid_sz, ask_sz) = if inv > 0.0 {
-                    ((base_sz * (ETA * inv).exp()).max(0.01), base_sz)
-                } else { (base_sz, (base_sz * (ETA * inv.abs()).exp()).max(0.01)) };
+                    (inventory_size(base_sz, inv, &cfg.sizing, &sym), base_sz)
+                } else { (base_sz, inventory_size(base_sz, inv.abs(), &cfg.sizing, &sym)) };
+                // V10.15: On top of inventory sizing, shrink the side recent fills keep hitting
+                let (bid_mult, ask_mult) = fill_flow::size_multipliers(fill_flow.imbalance(Instant::now()),
+                    cfg.sizing.fill_imbalance_sensitivity, cfg.sizing.fill_imbalance_min_mult);
+                let (bid_sz, ask_sz) = (scaled_size(bid_sz, bid_mult, &sym), scaled_size(ask_sz, ask_mult, &sym));
+                let (bid_sz, ask_sz) = (side_size(bid_sz, cfg.quoting.bid_size_mult, &sym),
+                    side_size(ask_sz, cfg.quoting.ask_size_mult, &sym));
+                // V10.15: Lean size into the side the live book favours
+                let (bid_boost, ask_boost) = book_boost_multipliers(kc_imb, &cfg.sizing);
+                let (bid_sz, ask_sz) = (side_size(bid_sz, bid_boost, &sym), side_size(ask_sz, ask_boost, &sym));
                 
-                // Process each level
-                for (bps, thresh) in LEVELS.iter() {
-                    let key = (*bps * 10.0) as i32;
-                    let (bid_state, ask_state) = level_orders.get(&key).cloned()
-                        .unwrap_or((LevelOrderState::Empty, LevelOrderState::Empty));
-                    
-                    let max_skew = bps * 0.5;
+                if !can_place_bid(inv, bid_sz, strat.max_inv_sol) {
+                    gate.set(GateReason::InventoryLimit, GateScope::Bids, format!("inv {:.3} / {:.1}", inv, strat.max_inv_sol));
+                } else if !can_place_ask(inv, ask_sz, strat.max_inv_sol) {
+                    gate.set(GateReason::InventoryLimit, GateScope::Asks, format!("inv {:.3} / {:.1}", inv, strat.max_inv_sol));
+                }
+                
+                let skip_bids = gate.blocks_bids();
+                let skip_asks = gate.blocks_asks();
+                gate.publish();
+                status::publish(&status, "quote_gate", gate.to_json()).await;
+                
+                // V10.15: Reservation shift moves the anchor for every level (and the
+                // refresh target with it); the per-level skew above still tilts on top
+                let qc = &cfg.quoting;
+                let band_mid = m;  // V10.15: Price band is measured from the unshifted mid
+                // V10.15: Ladder and refresh targets off the smoothed reference when set;
+                // the guards above and the price band keep the raw mid
+                let (m, refresh_mid) = match anchor_ref {
+                    Some(r) => (qc.mid_source.mid(r, local_mid), r),
+                    None => (m, binance_mid),
+                };
+                let (res_shift_bps, m) = reservation_mid(m, inv, qc.reservation_gamma, sigma, qc.reservation_max_bps);
+                let (_, refresh_mid) = reservation_mid(refresh_mid, inv, qc.reservation_gamma, sigma, qc.reservation_max_bps);
+                if res_shift_bps.abs() > 0.0 {
+                    debug!("[RESERVATION] Anchor shifted {:.2}bps (inv {:.3})", res_shift_bps, inv);
+                }
+                // V10.15: Then lean it along the short-horizon drift
+                let spot_m = m;
+                let (fc_shift_bps, m) = forecast_mid(m, momentum, qc.forecast_k, qc.forecast_max_bps);
+                let (_, refresh_mid) = forecast_mid(refresh_mid, momentum, qc.forecast_k, qc.forecast_max_bps);
+                if fc_shift_bps.abs() > 0.0 {
+                    debug!("[FORECAST] Spot {:.4} -> forecast {:.4} ({:+.2}bps, mom {:.3}%)",
+                        spot_m, m, fc_shift_bps, momentum * 100.0);
+                }
+                
+                // V10.15: Re-space the ladder from current vol every respace_secs
+                if cfg.levels.spacing == SpacingMode::EqualFillProb
+                    && last_respace.is_none_or(|t| t.elapsed().as_secs() >= cfg.levels.respace_secs) {
+                    spacing = equal_fill_prob_spacing(sigma, cfg.levels.fill_horizon_secs,
+                        levels()[0].0, levels()[levels().len() - 1].0, levels().len());
+                    last_respace = Some(Instant::now());
+                    debug!("[LEVELS] Re-spaced (σ {:.3}): {:?}", sigma,
+                        spacing.iter().map(|d| format!("{:.2}", d)).collect::<Vec<_>>());
+                }
+                
+                // V10.15: Per-level base distances (spacing bps, or book VWAP for far layers)
+                let distances = match &kc_book {
+                    Some(b) if far_vwap => {
+                        let b = b.read().await;
+                        let fresh = !b.is_stale(cfg.signals.kucoin_stale_ms);
+                        layer_distances(&cfg.levels, &spacing, fresh.then_some(&*b))
+                    }
+                    _ => layer_distances(&cfg.levels, &spacing, None),
+                };
+                
+                // V10.15: Widen a side where we're most of the near-touch depth, tighten where the book is deep
+                let depth_adj = match &kc_book {
+                    Some(b) if qc.own_depth_k > 0.0 => {
+                        let b = b.read().await;
+                        let live = |bid: bool| level_orders.values()
+                            .map(|(b, a)| if bid { b } else { a })
+                            .filter_map(|s| match s {
+                                LevelOrderState::Live { price, remaining_size, .. } => Some((*price, *remaining_size)),
+                                _ => None,
+                            }).collect::<Vec<_>>();
+                        if b.is_stale(cfg.signals.kucoin_stale_ms) {
+                            (0.0, 0.0)
+                        } else {
+                            own_depth::side_adjustments(&b, &live(true), &live(false), qc)
+                        }
+                    }
+                    _ => (0.0, 0.0),
+                };
+                if depth_adj != (0.0, 0.0) {
+                    debug!("[DEPTH] Own-depth adjust bid {:+.2}bps ask {:+.2}bps", depth_adj.0, depth_adj.1);
+                }
+                
+                // Target (bp, ap) and refresh (refresh_bp, refresh_ap) prices for a level
+                let level_prices = |index: usize| {
+                    let (bid_base, ask_base) = side_spreads(distances[index].0, distances[index].1, qc);
+                    let (bid_base, ask_base) = ((bid_base + depth_adj.0).max(0.0), (ask_base + depth_adj.1).max(0.0));
+                    let max_skew = bid_base.min(ask_base) * 0.5;
                     let capped_skew = skew_bps.clamp(-max_skew, max_skew);
-                    let bid_bps = bps + capped_skew;
+                    let key = (levels()[index].0 * 10.0) as i32;
+                    let widen = |is_bid: bool| cross_widen.get(&(key, is_bid)).copied().unwrap_or(0.0);
+                    let bid_bps = bid_base + capped_skew + widen(true);
                     // Apply uptrend multiplier to asks (widen during rallies)
-                    let ask_bps = bps - capped_skew;  // V10.6: Removed uptrend_multiplier to prevent instant cancel bug
+                    let ask_bps = ask_base - capped_skew + widen(false);  // V10.6: Removed uptrend_multiplier to prevent instant cancel bug
                     
-                    let bp = ((m * (1.0 - bid_bps / 10000.0)) / 0.01).round() * 0.01;
-                    let ap = ((m * (1.0 + ask_bps / 10000.0)) / 0.01).round() * 0.01;
+                    let bp = sym.round_price(m * (1.0 - bid_bps / 10000.0));
+                    let ap = sym.round_price(m * (1.0 + ask_bps / 10000.0));
                     
                     // V10.11: Use Binance mid for refresh target (faster signal)
-                    let refresh_bp = ((binance_mid * (1.0 - bid_bps / 10000.0)) / 0.01).round() * 0.01;
-                    let refresh_ap = ((binance_mid * (1.0 + ask_bps / 10000.0)) / 0.01).round() * 0.01;
+                    let refresh_bp = sym.round_price(refresh_mid * (1.0 - bid_bps / 10000.0));
+                    let refresh_ap = sym.round_price(refresh_mid * (1.0 + ask_bps / 10000.0));
+                    (bp, ap, refresh_bp, refresh_ap)
+                };
+                
+                // V10.15: Levels rounding onto one tick - stacked, nudged apart or merged
+                let (mut bid_quotes, mut ask_quotes): (Vec<SideQuote>, Vec<SideQuote>) = (0..levels().len()).map(|index| {
+                    let (bp, ap, refresh_bp, refresh_ap) = level_prices(index);
+                    (SideQuote::new(bp, refresh_bp), SideQuote::new(ap, refresh_ap))
+                }).unzip();
+                let collision = cfg.levels.price_collision;
+                let bid_res = price_collision::resolve(&mut bid_quotes, true, sym.price_increment, collision);
+                let ask_res = price_collision::resolve(&mut ask_quotes, false, sym.price_increment, collision);
+                if bid_res != Default::default() || ask_res != Default::default() {
+                    debug!("[LADDER] Price collisions ({:?}): bids {} nudged {} merged, asks {} nudged {} merged",
+                        collision, bid_res.nudged, bid_res.merged, ask_res.nudged, ask_res.merged);
+                    collisions_nudged += (bid_res.nudged + ask_res.nudged) as u64;
+                    collisions_merged += (bid_res.merged + ask_res.merged) as u64;
+                }
+                
+                // V10.15: Snapshot levels so the priority policy can order them - only
+                // the tiers due this tick, though trend protect reaches every level
+                refresh_tiers.begin_tick(Instant::now());
+                let any_adverse = cancel_adverse_bids || cancel_adverse_asks;
+                let views: Vec<LevelView> = levels().iter().enumerate()
+                    .filter(|(index, _)| !levels_off.contains(index))
+                    .filter(|(index, _)| any_adverse || refresh_tiers.is_due(*index))
+                    .map(|(index, (bps, _))| {
+                    let key = (*bps * 10.0) as i32;
+                    let trigger = refresh_trigger_bps(index, &cfg.levels);
+                    let (bid_state, ask_state) = level_orders.get(&key).cloned()
+                        .unwrap_or((LevelOrderState::Empty, LevelOrderState::Empty));
+                    let (bq, aq) = (bid_quotes[index], ask_quotes[index]);
+                    let off_band = |state: &LevelOrderState, q: SideQuote, adverse: bool| match state {
+                        LevelOrderState::Live { price, .. }
+                        | LevelOrderState::CancelPending { price, .. }
+                        | LevelOrderState::CancelStuck { price, .. } =>
+                            adverse || q.merged() || ((price - q.refresh).abs() / q.refresh) * 10000.0 > trigger,
+                        LevelOrderState::Empty => false,
+                    };
+                    LevelView {
+                        index,
+                        out_of_band: off_band(&bid_state, bq, cancel_adverse_bids)
+                            || off_band(&ask_state, aq, cancel_adverse_asks),
+                        has_gap: (bid_state.is_empty() && !bq.merged()) || (ask_state.is_empty() && !aq.merged()),
+                    }
+                }).collect();
+                
+                // V10.15: Plan every level first - sequential, reads and reserves state -
+                // then send with up to `levels.concurrency` ops in flight and apply the
+                // results once all are back. Budget is counted at planning time.
+                let mut actions = 0usize;
+                let mut ops: Vec<LevelOp> = Vec::new();
+                // V10.15: (key, is_bid) with a refresh or warm cancel planned this tick
+                let mut cancel_planned: HashSet<(i32, bool)> = HashSet::new();
+                // V10.15: Empty level sides held back by the min-edge gate
+                let mut suppressed: Vec<(i32, bool)> = Vec::new();
+                let edge_ok = |key: i32, is_bid: bool, px: f64| cfg.levels.min_edge_bps.is_none_or(|floor| {
+                    let distance = (if is_bid { band_mid - px } else { px - band_mid }) / band_mid * 10000.0;
+                    level_edge::expected_edge_bps(distance, REBATE, level_edge.adverse_bps(key, is_bid)) >= floor
+                });
+                // V10.15: A walk ends when its order fills, is cancelled or is replaced
+                walking.retain(|(key, is_bid)| level_orders.get(key)
+                    .is_some_and(|(b, a)| if *is_bid { b.is_live() } else { a.is_live() }));
+                // V10.15: Disabled layers get nothing but a cancel for what still rests there
+                for (index, (bps, _)) in levels().iter().enumerate().filter(|(index, _)| levels_off.contains(index)) {
+                    let key = (*bps * 10.0) as i32;
+                    let Some((bid_state, ask_state)) = level_orders.get(&key) else { continue };
+                    for (is_bid, state) in [(true, bid_state), (false, ask_state)] {
+                        if let LevelOrderState::Live { order_id, price, .. } = state {
+                            debug!("[CONTROL] Cancelling {} on disabled level L{}", order_id, index + 1);
+                            actions += 1;
+                            cancel_planned.insert((key, is_bid));
+                            ops.push(LevelOp::Cancel { key, is_bid, order_id: order_id.clone(), price: *price });
+                        }
+                    }
+                }
+                for (pos, index) in level_priority.order(&views).into_iter().enumerate() {
+                    let budget = cfg.levels.max_actions_per_tick;
+                    if budget > 0 && actions >= budget {
+                        deferred_levels += (views.len() - pos) as u64;
+                        debug!("[BUDGET] {} actions used, deferring {} levels", actions, views.len() - pos);
+                        break;
+                    }
+                    let (bps, _) = &levels()[index];
+                    let thresh = refresh_threshold(index, cfg.levels.min_refresh_bps);
+                    let refresh_over = refresh_trigger_bps(index, &cfg.levels);
+                    let (tif, cancel_after) = level_time_in_force(index, &cfg.levels);
+                    let key = (*bps * 10.0) as i32;
+                    let (bid_state, ask_state) = level_orders.get(&key).cloned()
+                        .unwrap_or((LevelOrderState::Empty, LevelOrderState::Empty));
+                    
+                    let (bq, aq) = (bid_quotes[index], ask_quotes[index]);
+                    let (bp, ap, refresh_bp, refresh_ap) = (bq.price, aq.price, bq.refresh, aq.refresh);
+                    // V10.15: A merged level side carries its neighbours' size; a merged-away one stays empty
+                    let (bid_sz, ask_sz) = (side_size(bid_sz, bq.lots.max(1) as f64, &sym), side_size(ask_sz, aq.lots.max(1) as f64, &sym));
+                    let (skip_bids, skip_asks) = (skip_bids || bq.merged(), skip_asks || aq.merged());
                     
                     // ═══ REFRESH CHECK: Cancel stale orders beyond threshold ═══
                     // V10.6: Aggressive cancel for ALL order states when severely stale
@@ -864,33 +3459,81 @@ async fn main() -> Result<()> {
                         LevelOrderState::Empty => None,
                     };
                     
-                    if let Some((order_id, price)) = bid_order_id {
+                    // V10.15: Unchanged level - no refresh message (trend protect still applies)
+                    let bid_matched = cfg.levels.skip_matched && !cancel_adverse_bids
+                        && is_matched(&bid_state, bp, sym.price_increment);
+                    let mut bid_cancelled = false;
+                    if bid_matched {
+                        hysteresis.settle(key, true);
+                        // V10.15: An adopted order still on its level price is kept
+                        if let Some((id, _)) = bid_order_id.as_ref().filter(|(id, _)| adopt_check.resolve(id, false)) {
+                            info!("[ADOPT] Bid {} kept, on the level price", id);
+                        }
+                    }
+                    if let Some((order_id, price)) = bid_order_id.filter(|_| !bid_matched && !cancel_planned.contains(&(key, true))) {
                         // V10.11: Compare against Binance-based refresh target
-                        let bps_diff = ((price - refresh_bp).abs() / refresh_bp) * 10000.0;
+                        let bps_diff = drift_bps(price, refresh_bp);
                         let severely_stale = bps_diff > thresh * 2.0;  // 2x threshold = emergency
-                        
-                        if bps_diff > *thresh || cancel_adverse_bids {
+                        // V10.15: An adopted order's first check skips min rest and hysteresis
+                        let adopted = adopt_check.is_pending(&order_id).then(|| order_id.clone());
+                        // V10.15: Mid-walk steps skip hysteresis; min rest still paces them, as
+                        // each step (KuCoin's alter) cancels the order and places a new one
+                        let walk_on = walking.contains(&(key, true)) && bid_state.is_live() && !cancel_adverse_bids;
+                        let reprice = reprice_step(reprice_mode, price, bp, sym.price_increment, cfg.levels.walk_max_gap_ticks);
+                        let verdict = RefreshCheck {
+                            bps_diff, refresh_over, adopted: adopted.is_some(), walk_on, risk_cancel: cancel_adverse_bids,
+                            hysteresis_ok: hysteresis.allows(key, true, bps_diff, refresh_over),
+                        }.verdict(&bid_state, min_rest, Instant::now());
+                        if walk_on && reprice.is_none() {
+                            walking.remove(&(key, true));
+                            debug!("[REPRICE] Bid {} walked onto {:.4}", order_id, price);
+                        } else if let RefreshVerdict::Deferred(wait) = verdict {
+                            rest_deferred += 1;
+                            debug!("[MIN-REST] Bid {} refresh deferred {}ms ({:.1}bps off)", order_id, wait.as_millis(), bps_diff);
+                        } else if verdict == RefreshVerdict::Held {
+                            hysteresis_held += 1;
+                            debug!("[HYSTERESIS] Bid {} refresh held, not settled since last refresh ({:.1}bps off)", order_id, bps_diff);
+                        } else if verdict == RefreshVerdict::Refresh {
+                            if bps_diff > refresh_over && bid_state.is_live() {
+                                hysteresis.on_refresh(key, true);
+                            }
                             // V10.13: Log if canceling due to adverse trend protection
-                            if cancel_adverse_bids && bps_diff <= *thresh {
+                            if cancel_adverse_bids && bps_diff <= refresh_over {
                                 warn!("[TREND-PROTECT] Canceling bid {} due to strong downtrend (OFI:{:.2})", order_id, ofi);
                             }
                             // V10.12: Always transition to CancelPending - don't trust WS success alone
                             // Recon loop will confirm actual cancellation via active_ids check
-                            if let Ok(_r) = ws.cancel_order(WsCancelRequest {
-                                symbol: SYM.into(), order_id: Some(order_id.clone()), client_oid: None
-                            }).await {
-                                // WS cancel sent - transition to CancelPending regardless of r.success
-                                // Recon will confirm when order disappears from active_ids
-                                level_orders.entry(key).or_insert((LevelOrderState::Empty, LevelOrderState::Empty)).0 = 
-                                    LevelOrderState::CancelPending { order_id: order_id.clone(), price, sent_at: Instant::now(), attempts: 1 };
-                            }
-                            
-                            // V10.12: For severely stale, also fire REST cancel as backup
-                            if severely_stale {
-                                warn!("[STALE] Bid {} is {}bps off, firing REST cancel backup", order_id, bps_diff as i32);
-                                let _ = rest_cancel_order(&auth4, &order_id).await;
+                            actions += 1;
+                            bid_cancelled = true;
+                            cancel_planned.insert((key, true));
+                            // V10.15: Reprice mode - move the order in place instead (risk cancels still cancel)
+                            match reprice.filter(|_| bid_state.is_live() && !cancel_adverse_bids) {
+                                Some(Reprice::Modify(to)) => {
+                                    let size = match &bid_state { LevelOrderState::Live { remaining_size, .. } => *remaining_size, _ => 0.0 };
+                                    debug!("[REPRICE] Bid {} {:.4} -> {:.4} (level {:.4})", order_id, price, to, bp);
+                                    if reprice_step(reprice_mode, to, bp, sym.price_increment, 0).is_some() {
+                                        walking.insert((key, true));
+                                    } else {
+                                        walking.remove(&(key, true));
+                                    }
+                                    ops.push(LevelOp::Modify { key, is_bid: true, order_id, price: to, size });
+                                }
+                                _ => {
+                                    walking.remove(&(key, true));
+                                    ops.push(LevelOp::Cancel { key, is_bid: true, order_id: order_id.clone(), price });
+                                    
+                                    // V10.12: For severely stale, also fire REST cancel as backup
+                                    if severely_stale {
+                                        warn!("[STALE] Bid {} is {}bps off, firing REST cancel backup", order_id, bps_diff as i32);
+                                        ops.push(LevelOp::RestCancel { order_id });
+                                    }
+                                }
                             }
                         }
+                        if let Some(id) = adopted.filter(|id| adopt_check.resolve(id, bid_cancelled)) {
+                            info!("[ADOPT] Bid {} {} ({:.1}bps off, refresh over {:.1})",
+                                id, if bid_cancelled { "refreshed" } else { "kept" }, bps_diff, refresh_over);
+                        }
                     }
                     
                     let ask_order_id = match &ask_state {
@@ -900,123 +3543,303 @@ async fn main() -> Result<()> {
                         LevelOrderState::Empty => None,
                     };
                     
-                    if let Some((order_id, price)) = ask_order_id {
+                    let ask_matched = cfg.levels.skip_matched && !cancel_adverse_asks
+                        && is_matched(&ask_state, ap, sym.price_increment);
+                    let mut ask_cancelled = false;
+                    if ask_matched {
+                        hysteresis.settle(key, false);
+                        // V10.15: An adopted order still on its level price is kept
+                        if let Some((id, _)) = ask_order_id.as_ref().filter(|(id, _)| adopt_check.resolve(id, false)) {
+                            info!("[ADOPT] Ask {} kept, on the level price", id);
+                        }
+                    }
+                    if let Some((order_id, price)) = ask_order_id.filter(|_| !ask_matched && !cancel_planned.contains(&(key, false))) {
                         // V10.11: Compare against Binance-based refresh target
-                        let bps_diff = ((price - refresh_ap).abs() / refresh_ap) * 10000.0;
+                        let bps_diff = drift_bps(price, refresh_ap);
                         let severely_stale = bps_diff > thresh * 2.0;
-                        
-                        if bps_diff > *thresh || cancel_adverse_asks {
+                        // V10.15: An adopted order's first check skips min rest and hysteresis
+                        let adopted = adopt_check.is_pending(&order_id).then(|| order_id.clone());
+                        // V10.15: Mid-walk steps skip hysteresis; min rest still paces them, as
+                        // each step (KuCoin's alter) cancels the order and places a new one
+                        let walk_on = walking.contains(&(key, false)) && ask_state.is_live() && !cancel_adverse_asks;
+                        let reprice = reprice_step(reprice_mode, price, ap, sym.price_increment, cfg.levels.walk_max_gap_ticks);
+                        let verdict = RefreshCheck {
+                            bps_diff, refresh_over, adopted: adopted.is_some(), walk_on, risk_cancel: cancel_adverse_asks,
+                            hysteresis_ok: hysteresis.allows(key, false, bps_diff, refresh_over),
+                        }.verdict(&ask_state, min_rest, Instant::now());
+                        if walk_on && reprice.is_none() {
+                            walking.remove(&(key, false));
+                            debug!("[REPRICE] Ask {} walked onto {:.4}", order_id, price);
+                        } else if let RefreshVerdict::Deferred(wait) = verdict {
+                            rest_deferred += 1;
+                            debug!("[MIN-REST] Ask {} refresh deferred {}ms ({:.1}bps off)", order_id, wait.as_millis(), bps_diff);
+                        } else if verdict == RefreshVerdict::Held {
+                            hysteresis_held += 1;
+                            debug!("[HYSTERESIS] Ask {} refresh held, not settled since last refresh ({:.1}bps off)", order_id, bps_diff);
+                        } else if verdict == RefreshVerdict::Refresh {
+                            if bps_diff > refresh_over && ask_state.is_live() {
+                                hysteresis.on_refresh(key, false);
+                            }
                             // V10.13: Log if canceling due to adverse trend protection
-                            if cancel_adverse_asks && bps_diff <= *thresh {
+                            if cancel_adverse_asks && bps_diff <= refresh_over {
                                 warn!("[TREND-PROTECT] Canceling ask {} due to strong uptrend (OFI:{:.2})", order_id, ofi);
                             }
                             // V10.12: Always transition to CancelPending - don't trust WS success alone
-                            if let Ok(_r) = ws.cancel_order(WsCancelRequest {
-                                symbol: SYM.into(), order_id: Some(order_id.clone()), client_oid: None
-                            }).await {
-                                // WS cancel sent - transition to CancelPending regardless of r.success
-                                level_orders.entry(key).or_insert((LevelOrderState::Empty, LevelOrderState::Empty)).1 = 
-                                    LevelOrderState::CancelPending { order_id: order_id.clone(), price, sent_at: Instant::now(), attempts: 1 };
-                            }
-                            
-                            // V10.12: For severely stale, also fire REST cancel as backup
-                            if severely_stale {
-                                warn!("[STALE] Ask {} is {}bps off, firing REST cancel backup", order_id, bps_diff as i32);
-                                let _ = rest_cancel_order(&auth4, &order_id).await;
+                            actions += 1;
+                            ask_cancelled = true;
+                            cancel_planned.insert((key, false));
+                            // V10.15: Reprice mode - move the order in place instead (risk cancels still cancel)
+                            match reprice.filter(|_| ask_state.is_live() && !cancel_adverse_asks) {
+                                Some(Reprice::Modify(to)) => {
+                                    let size = match &ask_state { LevelOrderState::Live { remaining_size, .. } => *remaining_size, _ => 0.0 };
+                                    debug!("[REPRICE] Ask {} {:.4} -> {:.4} (level {:.4})", order_id, price, to, ap);
+                                    if reprice_step(reprice_mode, to, ap, sym.price_increment, 0).is_some() {
+                                        walking.insert((key, false));
+                                    } else {
+                                        walking.remove(&(key, false));
+                                    }
+                                    ops.push(LevelOp::Modify { key, is_bid: false, order_id, price: to, size });
+                                }
+                                _ => {
+                                    walking.remove(&(key, false));
+                                    ops.push(LevelOp::Cancel { key, is_bid: false, order_id: order_id.clone(), price });
+                                    
+                                    // V10.12: For severely stale, also fire REST cancel as backup
+                                    if severely_stale {
+                                        warn!("[STALE] Ask {} is {}bps off, firing REST cancel backup", order_id, bps_diff as i32);
+                                        ops.push(LevelOp::RestCancel { order_id });
+                                    }
+                                }
                             }
                         }
+                        if let Some(id) = adopted.filter(|id| adopt_check.resolve(id, ask_cancelled)) {
+                            info!("[ADOPT] Ask {} {} ({:.1}bps off, refresh over {:.1})",
+                                id, if ask_cancelled { "refreshed" } else { "kept" }, bps_diff, refresh_over);
+                        }
                     }
                     
-                    // Re-read after potential cancellation
-                    let (bid_state, ask_state) = level_orders.get(&key).cloned()
-                        .unwrap_or((LevelOrderState::Empty, LevelOrderState::Empty));
-                    
                     // ═══ BID ORDER ═══
                     // V10.3: Use CommitmentTracker with safety buffer
-                    let safety_buffer = bal.usdt * BALANCE_SAFETY_BUFFER_PCT;
-                    let available_usdt = bal.usdt - commitments.total_usdt() - safety_buffer;
-                    if bid_state.is_empty() && !skip_bids && can_place_bid(inv, bid_sz)
-                        && available_usdt >= bid_sz * bp && local_bid_count < MAX_ORDERS_PER_SIDE {
-                        if let Ok(r) = ws.place_order(WsOrderRequest {
-                            symbol: SYM.into(), side: "buy".into(),
-                            price: format!("{:.2}", bp), size: format!("{:.2}", bid_sz),
-                            client_oid: format!("b{}_{}", key, n),
-                            order_type: "limit".into(), time_in_force: Some("GTC".into()),
-                            post_only: Some(true)
-                        }).await {
-                            if r.success {
-                                if let Some(ref oid) = r.order_id {
-                                    level_orders.entry(key).or_insert((LevelOrderState::Empty, LevelOrderState::Empty)).0 = 
-                                        LevelOrderState::Live { order_id: oid.clone(), price: bp, remaining_size: bid_sz };
-                                    // V10.5: Track inflight commitment (don't reset until confirmed)
-                                    commitments.add_inflight_bid(bid_sz * bp);
-                                }
-                            }
+                    // V10.15: A side with a cancel planned above is pending - nothing more this tick
+                    let available_usdt = tradable(bal.usdt, cfg.account.reserve_quote) - commitments.total_usdt();
+                    // V10.15: Optional jitter (0 = exact) - checks below see the jittered order
+                    let (qbp, qbsz) = (jitter_price(true, bp, qc.price_jitter_bps, thresh, jitter_draw(), &sym),
+                        jitter_size(bid_sz, qc.size_jitter_pct, jitter_draw(), &sym));
+                    // V10.15: On or through the KuCoin best ask - step back inside, post-only would reject it
+                    let qbp = post_only_backoff(true, qbp, kc_touch.map(|(_, a)| a), sym.price_increment, qc.post_only_backoff_ticks)
+                        .unwrap_or(qbp);
+                    // V10.15: Per-level notional cap, after every size adjustment
+                    let (qbsz, bid_clamped) = cap_notional(qbsz, qbp, cfg.sizing.max_notional_per_level_usd, &sym);
+                    let bid_edge_ok = !bid_state.is_empty() || edge_ok(key, true, qbp);
+                    if !bid_edge_ok {
+                        suppressed.push((key, true));
+                    }
+                    let bid_placeable = bid_state.is_empty() && !skip_bids && can_place_bid(inv, qbsz, strat.max_inv_sol) && bid_edge_ok
+                        && within_band(true, qbp, band_mid, qc.min_distance_bps, qc.max_distance_bps);
+                    let bid_slot = side_slot(&level_orders, true, qbp, local_bid_count, side_cap, cfg.levels.warm_cancel, &cancel_planned);
+                    if bid_placeable && available_usdt >= qbsz * qbp && bid_slot == SideSlot::Free {
+                        actions += 1;
+                        local_bid_count += 1;
+                        if bid_clamped {
+                            notional_clamped += 1;
+                            debug!("[SIZING] L{} bid clamped to {} SOL (${:.2} cap)", key, sym.size_str(qbsz), cfg.sizing.max_notional_per_level_usd);
                         }
-                    } else if bid_state.is_live() && needs_cancel_bid(inv, bid_sz, skip_bids) {
-                        // Cancel bid due to skip or inventory
+                        // V10.15: Reserved now so later levels see it; released if the place fails
+                        commitments.add_inflight_bid(qbsz * qbp);
+                        placed_lots.insert((key, true), bq.lots);
+                        ops.push(LevelOp::Place { key, is_bid: true, price: qbp, size: qbsz, req: WsOrderRequest {
+                            symbol: symbol().name.clone(), side: "buy".into(),
+                            price: sym.price_str(qbp), size: sym.size_str(qbsz),
+                            client_oid: oid_seq.next(true, key),
+                            order_type: "limit".into(), time_in_force: Some(tif.into()),
+                            post_only: Some(true), stp: cfg.quoting.stp, cancel_after
+                        }});
+                    } else if let (true, SideSlot::WarmCancel(vkey, order_id, price)) = (bid_placeable, bid_slot) {
+                        // V10.15: At the cap - free the furthest slot; this level places once it's gone
+                        debug!("[WARM-CANCEL] Bid L{} @ {:.4} out for L{} @ {:.4}", vkey, price, key, qbp);
+                        actions += 1;
+                        cancel_planned.insert((vkey, true));
+                        ops.push(LevelOp::Cancel { key: vkey, is_bid: true, order_id, price });
+                    } else if !bid_cancelled && bid_state.is_live() && (needs_cancel_bid(inv, bid_sz, skip_bids, strat.max_inv_sol)
+                        || merge_resize_due(&placed_lots, key, true, &bq)) {
+                        // Cancel bid due to skip, inventory or a merge resize
                         if let LevelOrderState::Live { ref order_id, price, .. } = bid_state {
                             // V10.13c: Always use CancelPending - don't trust WS success alone
-                            if let Ok(_r) = ws.cancel_order(WsCancelRequest {
-                                symbol: SYM.into(), order_id: Some(order_id.clone()), client_oid: None
-                            }).await {
-                                level_orders.entry(key).or_insert((LevelOrderState::Empty, LevelOrderState::Empty)).0 = 
-                                    LevelOrderState::CancelPending { order_id: order_id.clone(), price, sent_at: Instant::now(), attempts: 1 };
-                            }
+                            actions += 1;
+                            ops.push(LevelOp::Cancel { key, is_bid: true, order_id: order_id.clone(), price });
                         }
                     }
                     
                     // ═══ ASK ORDER ═══
-                    let sol_safety_buffer = bal.sol * BALANCE_SAFETY_BUFFER_PCT;
-                    let available_sol = bal.sol - commitments.total_sol() - sol_safety_buffer;
+                    let available_sol = tradable(bal.sol, cfg.account.reserve_base) - commitments.total_sol();
                     // V10.9: BBO safety - don't place asks below KuCoin mid (would cross spread)
-                    let ask_safe = ap > kucoin_mid || kucoin_mid <= 0.0;
-                    if ask_state.is_empty() && !skip_asks && can_place_ask(inv, ask_sz)
-                        && available_sol >= ask_sz && local_ask_count < MAX_ORDERS_PER_SIDE && ask_safe {
-                        if let Ok(r) = ws.place_order(WsOrderRequest {
-                            symbol: SYM.into(), side: "sell".into(),
-                            price: format!("{:.2}", ap), size: format!("{:.2}", ask_sz),
-                            client_oid: format!("a{}_{}", key, n),
-                            order_type: "limit".into(), time_in_force: Some("GTC".into()),
-                            post_only: Some(true)
-                        }).await {
-                            if r.success {
-                                if let Some(ref oid) = r.order_id {
-                                    level_orders.entry(key).or_insert((LevelOrderState::Empty, LevelOrderState::Empty)).1 = 
-                                        LevelOrderState::Live { order_id: oid.clone(), price: ap, remaining_size: ask_sz };
-                                    // V10.5: Track inflight commitment (don't reset until confirmed)
-                                    // V10.3: Track inflight commitment
-                                    commitments.add_inflight_ask(ask_sz);
-                                }
-                            }
+                    let (qap, qasz) = (jitter_price(false, ap, qc.price_jitter_bps, thresh, jitter_draw(), &sym),
+                        jitter_size(ask_sz, qc.size_jitter_pct, jitter_draw(), &sym));
+                    let qap = post_only_backoff(false, qap, kc_touch.map(|(b, _)| b), sym.price_increment, qc.post_only_backoff_ticks)
+                        .unwrap_or(qap);
+                    let (qasz, ask_clamped) = cap_notional(qasz, qap, cfg.sizing.max_notional_per_level_usd, &sym);
+                    let ask_safe = qap > kucoin_mid || kucoin_mid <= 0.0;
+                    let ask_edge_ok = !ask_state.is_empty() || edge_ok(key, false, qap);
+                    if !ask_edge_ok {
+                        suppressed.push((key, false));
+                    }
+                    let ask_placeable = ask_state.is_empty() && !skip_asks && can_place_ask(inv, qasz, strat.max_inv_sol) && ask_edge_ok
+                        && ask_safe && within_band(false, qap, band_mid, qc.min_distance_bps, qc.max_distance_bps);
+                    let ask_slot = side_slot(&level_orders, false, qap, local_ask_count, side_cap, cfg.levels.warm_cancel, &cancel_planned);
+                    if ask_placeable && available_sol >= qasz && ask_slot == SideSlot::Free {
+                        actions += 1;
+                        local_ask_count += 1;
+                        if ask_clamped {
+                            notional_clamped += 1;
+                            debug!("[SIZING] L{} ask clamped to {} SOL (${:.2} cap)", key, sym.size_str(qasz), cfg.sizing.max_notional_per_level_usd);
                         }
-                    } else if ask_state.is_live() && needs_cancel_ask(inv, ask_sz) {
+                        commitments.add_inflight_ask(qasz);
+                        placed_lots.insert((key, false), aq.lots);
+                        ops.push(LevelOp::Place { key, is_bid: false, price: qap, size: qasz, req: WsOrderRequest {
+                            symbol: symbol().name.clone(), side: "sell".into(),
+                            price: sym.price_str(qap), size: sym.size_str(qasz),
+                            client_oid: oid_seq.next(false, key),
+                            order_type: "limit".into(), time_in_force: Some(tif.into()),
+                            post_only: Some(true), stp: cfg.quoting.stp, cancel_after
+                        }});
+                    } else if let (true, SideSlot::WarmCancel(vkey, order_id, price)) = (ask_placeable, ask_slot) {
+                        debug!("[WARM-CANCEL] Ask L{} @ {:.4} out for L{} @ {:.4}", vkey, price, key, qap);
+                        actions += 1;
+                        cancel_planned.insert((vkey, false));
+                        ops.push(LevelOp::Cancel { key: vkey, is_bid: false, order_id, price });
+                    } else if !ask_cancelled && ask_state.is_live() && (needs_cancel_ask(inv, ask_sz, strat.max_inv_sol) || aq.merged()
+                        || merge_resize_due(&placed_lots, key, false, &aq)) {
                         if let LevelOrderState::Live { ref order_id, price, .. } = ask_state {
                             // V10.13c: Always use CancelPending - don't trust WS success alone
-                            if let Ok(_r) = ws.cancel_order(WsCancelRequest {
-                                symbol: SYM.into(), order_id: Some(order_id.clone()), client_oid: None
-                            }).await {
-                                level_orders.entry(key).or_insert((LevelOrderState::Empty, LevelOrderState::Empty)).1 = 
-                                    LevelOrderState::CancelPending { order_id: order_id.clone(), price, sent_at: Instant::now(), attempts: 1 };
-                            }
+                            actions += 1;
+                            ops.push(LevelOp::Cancel { key, is_bid: false, order_id: order_id.clone(), price });
+                        }
+                    }
+                }
+                
+                suppressed.sort_unstable();
+                if suppressed != edge_suppressed {
+                    if !suppressed.is_empty() {
+                        info!("[EDGE] Holding back {}", suppressed.iter().map(|(k, is_bid)| format!("{}L{} ({:.1}bps adverse)",
+                            if *is_bid { "B" } else { "A" }, k, level_edge.adverse_bps(*k, *is_bid))).collect::<Vec<_>>().join(" "));
+                    } else {
+                        info!("[EDGE] All levels back above the edge floor");
+                    }
+                    edge_suppressed = suppressed;
+                }
+                
+                adopt_check.retain_live(|id| level_orders.values().any(|(b, a)| b.order_id() == Some(id) || a.order_id() == Some(id)));
+                if let Some((kept, refreshed)) = adopt_check.take_summary() {
+                    info!("[ADOPT] Verified adopted orders: {} kept, {} refreshed", kept, refreshed);
+                }
+                
+                // V10.15: Entry breaker - hold placements while frozen, probe with one after
+                let admit = entry_breaker.as_mut().map_or(Admit::All, |b| b.admit(Instant::now()));
+                let (ops, held) = hold_placements(ops, admit);
+                if admit == Admit::One && ops.iter().any(|op| matches!(op, LevelOp::Place { .. })) {
+                    info!("[ENTRY] Cooldown over - probing with one placement");
+                }
+                
+                // V10.15: Placements go REST batch while the WS queue is backed up
+                let queued = match &rest_batch {
+                    Some(_) => ws.send_queue_len().await.unwrap_or(0),
+                    None => 0,
+                };
+                let (batch_places, ops): (Vec<_>, Vec<_>) = match &rest_batch {
+                    Some(_) if queued >= cfg.levels.rest_batch_queue_threshold =>
+                        ops.into_iter().partition(|op| matches!(op, LevelOp::Place { .. })),
+                    _ => (Vec::new(), ops),
+                };
+                // V10.15: Latency budget - placements that would land on too old a mid are skipped
+                let mid_budget = match (cfg.quoting.mid_latency_budget_ms, mid_at) {
+                    (0, _) | (_, None) => None,
+                    (budget, Some(mid_at)) => Some(MidBudget {
+                        mid_at,
+                        est_latency: ws.place_latency_pct(cfg.quoting.place_latency_pct).await.unwrap_or_default(),
+                        budget: Duration::from_millis(budget),
+                    }),
+                };
+                let mut results = run_level_ops(ops, cfg.levels.concurrency,
+                    |op| execute_level_op(&ws, reprice_rest.as_ref(), &auth4, &sym, mid_budget, qc.post_only_backoff_ticks, op)).await;
+                let (batch_places, stale): (Vec<_>, Vec<_>) = batch_places.into_iter()
+                    .partition(|_| !mid_budget.is_some_and(|b| b.exceeded(Instant::now())));
+                results.extend(stale.into_iter().map(|op| (op, OpOutcome::Stale)));
+                let (batch_places, closed) = hold_batch_on_shutdown(batch_places, &shutdown);
+                results.extend(closed.into_iter().map(|op| (op, OpOutcome::Held)));
+                if let (Some(rest), false) = (&rest_batch, batch_places.is_empty()) {
+                    warn!("[BATCH] WS send queue at {} - {} placements via REST batch", queued, batch_places.len());
+                    results.extend(place_batch_via_rest(rest, &sym, batch_places).await);
+                }
+                results.extend(held.into_iter().map(|op| (op, OpOutcome::Held)));
+                let stale = results.iter().filter(|(_, o)| *o == OpOutcome::Stale).count();
+                if let (Some(b), true) = (mid_budget, stale > 0) {
+                    warn!("[STALE-MID] Skipped {} placements: mid {}ms old + p{:.0} place {}ms > budget {}ms", stale,
+                        b.mid_at.elapsed().as_millis(), cfg.quoting.place_latency_pct * 100.0, b.est_latency.as_millis(), b.budget.as_millis());
+                }
+                for (op, outcome) in &results {
+                    apply_level_op(&mut level_orders, &mut commitments, op, outcome);
+                    if let (LevelOp::Place { key, is_bid, .. }, OpOutcome::Placed(oid)) = (op, outcome) {
+                        level_edge.on_placed(oid.clone(), *key, *is_bid);
+                        market_status.on_placed();
+                    }
+                    match (op, outcome) {
+                        (LevelOp::Modify { key, is_bid, .. }, OpOutcome::Placed(oid)) => level_edge.on_placed(oid.clone(), *key, *is_bid),
+                        // V10.15: A refused step ends the walk; the next refresh check decides afresh
+                        (LevelOp::Modify { key, is_bid, .. }, _) => { walking.remove(&(*key, *is_bid)); }
+                        _ => {}
+                    }
+                    if cfg.market_status.enabled && *outcome == OpOutcome::NotTrading {
+                        market_status.on_reject(Instant::now());
+                    }
+                    if cfg.quoting.rebate_only {
+                        if let (LevelOp::Place { key, is_bid, price, .. }, OpOutcome::WouldCross) = (op, outcome) {
+                            warn!("[REBATE] {} L{} @ {:.4} would cross - widening", if *is_bid { "Bid" } else { "Ask" }, key, price);
+                        }
+                        update_cross_widen(&mut cross_widen, op, outcome, cfg.quoting.rebate_only_widen_bps);
+                    }
+                    let Some(breaker) = entry_breaker.as_mut() else { continue };
+                    if !matches!(op, LevelOp::Place { .. }) || matches!(outcome, OpOutcome::Held | OpOutcome::Stale) {
+                        continue;
+                    }
+                    match breaker.record(!matches!(outcome, OpOutcome::Failed | OpOutcome::NotTrading), Instant::now()) {
+                        Some(Transition::Froze { error_rate }) => {
+                            let msg = format!("{:.0}% of placements failing - freezing new orders for {}s",
+                                error_rate * 100.0, cfg.entry_breaker.cooldown_secs);
+                            warn!("[ENTRY] {}", msg);
+                            alerter.alert("entry_freeze", &msg);
+                            event_log::record(&recent_events, "entry", format!("freeze: {}", msg)).await;
+                        }
+                        Some(Transition::ProbeFailed) => {
+                            warn!("[ENTRY] Probe placement failed - frozen another {}s", cfg.entry_breaker.cooldown_secs);
+                        }
+                        Some(Transition::Resumed) => {
+                            info!("[ENTRY] Probe placed - resuming");
+                            event_log::record(&recent_events, "entry", "resume").await;
                         }
+                        None => {}
                     }
                 }
             }
             _ = log.tick(), if !shutting_down => {
                 let md = data.read().await;
                 let m = md.mid;
-                let ofi = md.ofi;
+                let ofi = md.fresh_ofi(Instant::now(), ofi_stale);
+                let kc_book = md.kc_imbalance.zip(md.kc_spread_bps);
+                let local_mid = md.local_mid();
                 let sigma = md.sigma();
                 let momentum = md.momentum();
                 let update_interval = md.update_interval_ms;
+                let anchor_ref = md.anchor_ema.value_at(Instant::now());
                 drop(md);
                 
                 let bal = balances.read().await.clone();
-                let orders = active_orders.read().await.len();
+                let (orders, eff) = {
+                    let ao = active_orders.read().await;
+                    (ao.len(), effective_balances(&bal, own_order_holds(&ao, &level_orders)))
+                };
                 let inv = pnl.inv();
                 let wr = if pnl.matched > 0 { (pnl.wins as f64 / pnl.matched as f64) * 100.0 } else { 0.0 };
-                let skew = inv * GAMMA * sigma * sigma * 10000.0;
+                let skew = inv * strat.gamma * sigma * sigma * 10000.0;
                 
                 // V10: Count local states
                 let local_bids = level_orders.values().filter(|(b, _)| !b.is_empty()).count();
@@ -1027,16 +3850,1403 @@ async fn main() -> Result<()> {
                     start.elapsed().as_secs(), pnl.buys, pnl.sells, pnl.matched, pnl.wins, pnl.losses, wr);
                 info!("ORDERS:{} (L:{}/{}) | Inv:{:.3} ${:.0} | OFI:{:.3} | σ:{:.3} | Mom:{:.2}%", 
                     orders, local_bids, local_asks, inv, inv * m, ofi, sigma, momentum * 100.0);
-                info!("BAL: {:.4} SOL, {:.2} USDT | Skew:{:.1}bps | Interval:{:.0}ms", 
-                    bal.sol, bal.usdt, skew, update_interval);
-                info!("SPREAD: ${:.4} | REBATE: ${:.4} | NET: ${:.4}", pnl.spread, pnl.reb, pnl.net());
+                info!("BAL: {:.4} SOL, {:.2} USDT (tradable {:.4} SOL, {:.2} USDT) | Skew:{:.1}bps | Interval:{:.0}ms", 
+                    bal.sol, bal.usdt, tradable(eff.sol, cfg.account.reserve_base), tradable(eff.usdt, cfg.account.reserve_quote),
+                    skew, update_interval);
+                info!("SPREAD: ${:.4} | REBATE: ${:.4} | FEES: ${:.4} | NET: ${:.4}", pnl.spread, pnl.reb, pnl.fees, pnl.net());
+                let (vol_1h, vol_all) = (turnover.windowed(Instant::now()), turnover.total());
+                info!("VOLUME: 1h {:.3} SOL ${:.0} ({:.0}% maker) | total {:.3} SOL ${:.0} ({:.0}% maker)",
+                    vol_1h.base(), vol_1h.quote(), vol_1h.maker_share() * 100.0,
+                    vol_all.base(), vol_all.quote(), vol_all.maker_share() * 100.0);
+                status::publish(&status, "turnover", serde_json::json!({ "last_hour": vol_1h, "total": vol_all })).await;
+                if cfg.quoting.mid_source.needs_local() {
+                    info!("MID: Ref:{:.4} Local:{} | Used:{:.4} ({:?})", m,
+                        local_mid.map_or("-".to_string(), |l| format!("{:.4}", l)),
+                        cfg.quoting.mid_source.mid(m, local_mid), cfg.quoting.mid_source);
+                }
+                if let Some(r) = anchor_ref.filter(|_| cfg.quoting.anchor_half_life_ms > 0) {
+                    info!("ANCHOR: Raw:{:.4} Smoothed:{:.4} ({:+.2}bps, half-life {}ms)", m, r,
+                        (r - m) / m * 10000.0, cfg.quoting.anchor_half_life_ms);
+                }
+                if cfg.quoting.forecast_k != 0.0 {
+                    let (shift, fc) = forecast_mid(m, momentum, cfg.quoting.forecast_k, cfg.quoting.forecast_max_bps);
+                    info!("FORECAST: Spot:{:.4} Forecast:{:.4} ({:+.2}bps, cap {}bps)", m, fc, shift, cfg.quoting.forecast_max_bps);
+                }
+                if let Some((imb, spread)) = kc_book {
+                    info!("KC BOOK: Imb:{:.3} Spread:{:.1}bps | Signal:{:.3}", imb, spread,
+                        cfg.signals.toxicity(ofi, Some(imb)));
+                }
+                if gate.blocks_bids() || gate.blocks_asks() {
+                    info!("GATE: {}", gate.describe());
+                }
+                let fill_imb = fill_flow.imbalance(Instant::now());
+                let (bid_mult, ask_mult) = fill_flow::size_multipliers(fill_imb,
+                    cfg.sizing.fill_imbalance_sensitivity, cfg.sizing.fill_imbalance_min_mult);
+                info!("FILL FLOW: Imb:{:+.2} over {}s | size x{:.2} bid x{:.2} ask",
+                    fill_imb, cfg.sizing.fill_imbalance_window_secs, bid_mult, ask_mult);
+                let boost = post_fill_skew.boost_bps(Instant::now());
+                if boost.abs() >= 0.01 {
+                    info!("POST-FILL SKEW: {:+.2}bps (half-life {}s)", boost, cfg.quoting.post_fill_half_life_secs);
+                }
+                if let Some(d) = &drain {
+                    let remaining = d.remaining(Instant::now()).as_secs();
+                    info!("DRAIN: {} orders resting | {}s to deadline", d.left().unwrap_or(orders), remaining);
+                    status::publish(&status, "drain", serde_json::json!({
+                        "orders_left": d.left(), "deadline_in_s": remaining,
+                    })).await;
+                }
+                if let Some(b) = &entry_breaker {
+                    status::publish(&status, "entry_breaker", serde_json::json!({ "state": b.state().as_str() })).await;
+                }
+                if let Some(h) = &hedge {
+                    info!("HEDGE: {:+.4} vs inv {:+.4}", h.hedged(), inv);
+                    status::publish(&status, "hedge", serde_json::json!({ "position": h.hedged(), "inventory": inv })).await;
+                }
+                if deferred_levels > 0 {
+                    info!("BUDGET: {} level visits deferred ({})", deferred_levels, level_priority.name());
+                }
+                if collisions_nudged + collisions_merged > 0 {
+                    info!("LADDER: {} level sides nudged, {} merged off shared ticks", collisions_nudged, collisions_merged);
+                }
+                if hysteresis_held > 0 {
+                    info!("HYSTERESIS: {} refreshes held for an unsettled level side", hysteresis_held);
+                }
+                let rest_waits = rest_limit().take_waits();
+                if rest_waits.count > 0 {
+                    info!("REST: {} calls queued for a slot (avg {:.1}ms, max {:.1}ms, limit {})", rest_waits.count,
+                        rest_waits.total.as_secs_f64() * 1000.0 / rest_waits.count as f64,
+                        rest_waits.max.as_secs_f64() * 1000.0, rest_limit().limit());
+                }
+                if ws_fills + rest_fills > 0 {
+                    info!("FILLS: {} booked from the private feed, {} from the REST poll ({} tradeIds held for dedup)",
+                        ws_fills, rest_fills, seen_trade_ids.len());
+                }
+                if notional_clamped > 0 {
+                    info!("SIZING: {} orders clamped to ${} per level", notional_clamped, cfg.sizing.max_notional_per_level_usd);
+                }
+                if rest_deferred > 0 {
+                    info!("MIN-REST: {} refresh cancels deferred (min {}ms)", rest_deferred, cfg.levels.min_resting_ms);
+                }
+                if cfg.latency_probe.enabled {
+                    let ps = probe_stats.read().await;
+                    info!("PROBE: {} | timeouts:{} errors:{}", ps.latency.summary(), ps.timeouts, ps.errors);
+                    status::publish(&status, "latency_probe", ps.to_json()).await;
+                }
+                ws.log_latency().await;
+                status::publish(&status, "ws_order", ws.get_reconnect_stats().await.to_json()).await;
+                info!("RECON RTT: {}", recon_latency.summary());
+                status::publish(&status, "recon", serde_json::json!({
+                    "rtt_avg_ms": recon_latency.avg_us() as f64 / 1000.0,
+                    "rtt_last_ms": recon_latency.last_us as f64 / 1000.0,
+                    "rtt_max_ms": recon_latency.max_us as f64 / 1000.0,
+                    "cycles": recon_latency.count,
+                })).await;
                 info!("═══════════════════════════════════════════════════════════════");
                 
                 // V10.5: Periodic FIFO save (every 30s log tick)
                 pnl.save();
             }
         }
+        
+        // V10.15: Fills from the private feed and the REST poll, each tradeId once
+        for fill in new_fills.drain(..) {
+            if fill.liquidity == Liquidity::Taker {
+                warn!("[FILL] Taker fill {} {} @ {:.4} (fee {:.4} {})", fill.side, fill.size, fill.price,
+                    fill.fee.unwrap_or(0.0), fill.fee_currency);
+            }
+            if fill.liquidity == Liquidity::Taker || fill.size * fill.price >= cfg.event_log.big_fill_usd {
+                event_log::record(&recent_events, "fill", format!("{:?} {} {} @ {:.4}", fill.liquidity, fill.side, fill.size, fill.price)).await;
+            }
+            trade_log.append(&TradeRecord {
+                ts_ms: event_log::now_ms(), order_id: fill.order_id.clone(), side: fill.side,
+                price: fill.price, size: fill.size, maker: fill.liquidity == Liquidity::Maker,
+                fee: fill.fee, fee_currency: fill.fee_currency.clone(),
+                mid: data.read().await.mid, level: level_edge.level_of(&fill.order_id),
+            });
+            pnl.record_fill(&fill, cfg.fees.taker_bps);
+            fill_flow.record(fill.side == Side::Buy, fill.size, Instant::now());
+            turnover.record(fill.liquidity, fill.size, fill.price, Instant::now());
+            level_edge.on_fill(&fill.order_id, fill.price, Instant::now());
+            if cfg.quoting.scratch_on_toxic {
+                scratch_watch.on_fill(fill.side == Side::Buy, fill.price, fill.size, Instant::now());
+            }
+            if cfg.quoting.post_fill_skew_bps > 0.0 {
+                post_fill_skew.on_fill(fill.side == Side::Buy, Instant::now());
+                info!("[SKEW] Post-fill boost {:+.2}bps after {} fill", post_fill_skew.boost_bps(Instant::now()), fill.side);
+            }
+            // V10.15: Free the filled part of the commitment now, not at next recon -
+            // unless recon's dealSize already took it off
+            let unapplied = fill_ledger.on_fill(&fill.order_id, fill.size);
+            if unapplied > 0.0 {
+                apply_level_fill(&mut level_orders, &mut commitments, &fill.order_id, unapplied);
+            }
+        }
+        
+        if shutting_down { break; }
     }
     
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_symbol_truncation() {
+        let sym = SymbolInfo { price_increment: 0.01, base_increment: 0.0001, base_min_size: 0.01 };
+        // Extra decimals are floored, never rounded up
+        assert_eq!(sym.size_str(0.123456), "0.1234");
+        assert_eq!(sym.price_str(187.239), "187.23");
+        // Values already on the grid survive float noise
+        assert_eq!(sym.price_str(187.23), "187.23");
+        assert_eq!(sym.size_str(0.3), "0.3000");
+        assert_eq!(increment_decimals(1.0), 0);
+        assert_eq!(increment_decimals(0.001), 3);
+    }
+
+    #[test]
+    fn test_degenerate_size_never_sent() {
+        let sym = SymbolInfo { price_increment: 0.01, base_increment: 0.01, base_min_size: 0.01 };
+        // A 0.004 decay result formats to "0.00" - rejected before the wire
+        assert!(sym.check_size(&sym.size_str(0.004)).is_err());
+        assert!(sym.check_size(&sym.size_str(-1.0)).is_err());
+        assert!(sym.check_size("NaN").is_err());
+        assert!(sym.check_size("").is_err());
+        assert!(sym.check_size(&sym.size_str(0.01)).is_ok());
+        let coarse = SymbolInfo { base_min_size: 0.1, ..sym };
+        assert!(coarse.check_size("0.09").is_err());
+    }
+
+    #[test]
+    fn test_partial_fill_reduces_commitment() {
+        let mut levels: HashMap<i32, (LevelOrderState, LevelOrderState)> = HashMap::new();
+        levels.insert(10, (
+            LevelOrderState::Live { order_id: "b1".into(), price: 100.0, remaining_size: 1.0, placed_at: Instant::now() },
+            LevelOrderState::Live { order_id: "a1".into(), price: 101.0, remaining_size: 2.0, placed_at: Instant::now() },
+        ));
+        // As recon would leave it: both orders confirmed live
+        let mut c = CommitmentTracker { live_usdt: 100.0, live_sol: 2.0, ..Default::default() };
+
+        assert!(apply_level_fill(&mut levels, &mut c, "b1", 0.4));
+        assert!((c.total_usdt() - 60.0).abs() < 1e-9);
+        match &levels[&10].0 {
+            LevelOrderState::Live { remaining_size, .. } => assert!((remaining_size - 0.6).abs() < 1e-9),
+            s => panic!("bid should still be live, got {:?}", s),
+        }
+
+        // Ask fully filled -> level freed, no SOL committed
+        assert!(apply_level_fill(&mut levels, &mut c, "a1", 2.0));
+        assert!(c.total_sol().abs() < 1e-9);
+        assert!(levels[&10].1.is_empty());
+
+        // Unknown order (orphan / probe) leaves everything alone
+        assert!(!apply_level_fill(&mut levels, &mut c, "x9", 1.0));
+        assert!((c.total_usdt() - 60.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_reservation_shift_moves_anchor_away_from_inventory() {
+        // Off by default
+        assert_eq!(reservation_mid(100.0, 10.0, 0.0, 0.5, 50.0), (0.0, 100.0));
+
+        // Long 10 SOL, gamma 0.01, sigma 0.5 -> 10*0.01*0.25 = 0.025 -> 250bps, capped at 50
+        let (bps, m) = reservation_mid(100.0, 10.0, 0.01, 0.5, 50.0);
+        assert_eq!(bps, 50.0);
+        assert!((m - 99.5).abs() < 1e-9);
+
+        // Short shifts up, uncapped
+        let (bps, m) = reservation_mid(100.0, -2.0, 0.01, 0.1, 50.0);
+        assert!((bps + 2.0).abs() < 1e-9);
+        assert!((m - 100.02).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_empty_depth_decays_ofi_and_old_ofi_reads_neutral() {
+        let mut md = MarketData::new(PriceHistory::new(Duration::from_secs(180), Duration::from_millis(100), 100),
+            FillFlow::new(Duration::from_secs(60)));
+        let t0 = Instant::now();
+        let stale = Duration::from_secs(10);
+        let book = serde_json::json!({ "b": [["100.0", "9"]], "a": [["100.1", "1"]] });
+        md.on_depth(depth5_ofi(&book), t0);
+        assert!((md.ofi - 0.8).abs() < 1e-9);
+        // Thin book: no depth either side (and a malformed one) - decays, doesn't hold
+        let empty = serde_json::json!({ "b": [], "a": [] });
+        assert_eq!(depth5_ofi(&empty), None);
+        assert_eq!(depth5_ofi(&serde_json::json!({ "b": [["100.0", "0"]] })), None);
+        for _ in 0..10 {
+            md.on_depth(depth5_ofi(&empty), t0);
+        }
+        assert!((md.ofi - 0.8 * 0.9f64.powi(10)).abs() < 1e-9 && md.ofi < OFI_PAUSE_THRESHOLD);
+        for _ in 0..50 {
+            md.on_depth(None, t0);
+        }
+        assert!(md.ofi.abs() < 0.01);
+        // No depth for longer than the limit: neutral, whatever is stored
+        md.on_depth(Some(-0.9), t0);
+        assert_eq!(md.fresh_ofi(t0 + Duration::from_secs(5), stale), -0.9);
+        assert_eq!(md.fresh_ofi(t0 + Duration::from_secs(11), stale), 0.0);
+        assert_eq!(md.fresh_ofi(t0 + Duration::from_secs(11), Duration::ZERO), -0.9);
+    }
+
+    #[test]
+    fn test_sigma_cold_start_burst_and_steady_state() {
+        let md = || MarketData::new(PriceHistory::new(Duration::from_secs(180), Duration::from_millis(100), 100),
+            FillFlow::new(Duration::from_secs(60)));
+        let annualized = |var: f64, interval_ms: f64| (var * 86400.0 * 1000.0 / interval_ms * 365.0).sqrt();
+        let t0 = Instant::now();
+        let ms = |n: f64| t0 + Duration::from_secs_f64(n / 1000.0);
+
+        // Cold start: no variance yet -> floor; the first gap seeds the interval
+        let mut cold = md();
+        assert_eq!(cold.sigma(), SIGMA_FLOOR);
+        cold.mid = 100.0;
+        cold.update_at(ms(0.0));
+        assert_eq!(cold.sigma(), SIGMA_FLOOR);
+        cold.update_at(ms(250.0));
+        assert!((cold.update_interval_ms - 250.0).abs() < 1e-6);
+        assert_eq!(cold.sigma(), SIGMA_FLOOR);
+
+        // Steady state: 100ms ticks alternating ±1bps
+        let mut steady = md();
+        for i in 0..200 {
+            steady.mid = if i % 2 == 0 { 100.0 } else { 100.01 };
+            steady.update_at(ms(i as f64 * 100.0));
+        }
+        assert!((steady.update_interval_ms - 100.0).abs() < 1e-6);
+        let expected = annualized(steady.ewma_var, 100.0);
+        assert!(expected > SIGMA_FLOOR && (steady.sigma() - expected).abs() < 1e-9);
+
+        // Burst: the same returns 0.1ms apart would annualize 1000x the updates;
+        // the interval clamp holds it to SIGMA_MIN_INTERVAL_MS
+        let mut burst = md();
+        for i in 0..200 {
+            burst.mid = if i % 2 == 0 { 100.0 } else { 100.01 };
+            burst.update_at(ms(i as f64 * 0.1));
+        }
+        assert!(burst.update_interval_ms < 1.0);
+        assert!((burst.sigma() - annualized(burst.ewma_var, SIGMA_MIN_INTERVAL_MS)).abs() < 1e-9);
+        assert!(burst.sigma() < annualized(burst.ewma_var, burst.update_interval_ms));
+    }
+
+    #[test]
+    fn test_forecast_mid_leans_with_drift_bounded() {
+        // Off by default
+        assert_eq!(forecast_mid(100.0, 0.002, 0.0, 5.0), (0.0, 100.0));
+
+        // +0.02% drift, k 1 -> +2bps, inside the cap
+        let (bps, m) = forecast_mid(100.0, 0.0002, 1.0, 5.0);
+        assert!((bps - 2.0).abs() < 1e-9);
+        assert!((m - 100.02).abs() < 1e-9);
+
+        // -0.2% drift, k 2 -> -40bps, capped at 5 either way
+        let (bps, m) = forecast_mid(100.0, -0.002, 2.0, 5.0);
+        assert_eq!(bps, -5.0);
+        assert!((m - 99.95).abs() < 1e-9);
+        assert_eq!(forecast_mid(100.0, 0.01, 1.0, 5.0).0, 5.0);
+    }
+
+    #[test]
+    fn test_commitment_place_confirm_fill() {
+        let mut c = CommitmentTracker::default();
+        c.add_inflight_bid(50.0);
+        c.add_inflight_ask(0.25);
+        assert_eq!((c.total_usdt(), c.total_sol()), (50.0, 0.25));
+
+        // Recon confirms: inflight dropped, live rebuilt from the level states
+        let mut levels: HashMap<i32, (LevelOrderState, LevelOrderState)> = HashMap::new();
+        levels.insert(10, (
+            LevelOrderState::Live { order_id: "b1".into(), price: 100.0, remaining_size: 0.5, placed_at: Instant::now() },
+            LevelOrderState::Live { order_id: "a1".into(), price: 101.0, remaining_size: 0.25, placed_at: Instant::now() },
+        ));
+        c.reset_inflight();
+        assert!(c.repair_from_levels(&levels).is_some());
+        assert_eq!((c.inflight_usdt, c.live_usdt), (0.0, 50.0));
+        assert_eq!((c.inflight_sol, c.live_sol), (0.0, 0.25));
+        assert_eq!((c.total_usdt(), c.total_sol()), (50.0, 0.25));
+
+        // Fill releases from live
+        c.fill_bid(50.0);
+        c.fill_ask(0.25);
+        assert_eq!((c.total_usdt(), c.total_sol()), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_commitment_orphan_reset_keeps_live() {
+        let mut c = CommitmentTracker { live_usdt: 30.0, live_sol: 1.0, ..Default::default() };
+        c.add_inflight_bid(20.0);
+        c.add_inflight_ask(0.5);
+
+        // Recon: nothing confirmed -> inflight dropped, live untouched until recomputed
+        c.reset_inflight();
+        assert_eq!((c.inflight_usdt, c.inflight_sol), (0.0, 0.0));
+        assert_eq!((c.total_usdt(), c.total_sol()), (30.0, 1.0));
+    }
+
+    #[test]
+    fn test_commitment_partial_confirm_and_clamping() {
+        let mut c = CommitmentTracker::default();
+        // Two bids in flight, one rejected
+        c.add_inflight_bid(60.0);
+        c.add_inflight_bid(40.0);
+        c.release_inflight_bid(40.0);
+        assert_eq!(c.total_usdt(), 60.0);
+
+        // Recon drops what it didn't confirm
+        c.reset_inflight();
+        assert_eq!(c.total_usdt(), 0.0);
+
+        // Over-release never goes negative
+        c.live_usdt = 60.0;
+        c.fill_bid(500.0);
+        c.fill_ask(1.0);
+        c.release_inflight_ask(1.0);
+        assert_eq!((c.live_usdt, c.live_sol, c.inflight_usdt, c.inflight_sol), (0.0, 0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_repair_from_levels_corrects_phantom_commitment() {
+        let mut levels: HashMap<i32, (LevelOrderState, LevelOrderState)> = HashMap::new();
+        levels.insert(10, (
+            LevelOrderState::Live { order_id: "b1".into(), price: 100.0, remaining_size: 0.5, placed_at: Instant::now() },
+            LevelOrderState::Empty,
+        ));
+        // Cancel-pending orders are not counted as live
+        levels.insert(20, (
+            LevelOrderState::CancelPending { order_id: "b2".into(), price: 99.0, sent_at: Instant::now(), attempts: 1 },
+            LevelOrderState::Live { order_id: "a2".into(), price: 101.0, remaining_size: 0.3, placed_at: Instant::now() },
+        ));
+
+        let mut c = CommitmentTracker { live_usdt: 50.0, live_sol: 0.3, inflight_usdt: 7.0, ..Default::default() };
+        assert_eq!(c.repair_from_levels(&levels), None);
+
+        // A level went Empty but its commitment stayed behind
+        c.live_usdt = 149.0;
+        c.live_sol = 0.8;
+        let (du, ds) = c.repair_from_levels(&levels).unwrap();
+        assert!((du - 99.0).abs() < 1e-9 && (ds - 0.5).abs() < 1e-9);
+        assert_eq!((c.live_usdt, c.live_sol), (50.0, 0.3));
+        // Inflight is not the repair's business
+        assert_eq!(c.inflight_usdt, 7.0);
+    }
+
+    #[test]
+    fn test_price_band_boundaries() {
+        // mid 100: 1bps = 0.01
+        let (min, max) = (0.5, 200.0);
+        // Inner boundary: exactly min is allowed, closer is not
+        assert!(within_band(true, 99.995, 100.0, min, max));
+        assert!(!within_band(true, 99.996, 100.0, min, max));
+        assert!(within_band(false, 100.005, 100.0, min, max));
+        assert!(!within_band(false, 100.004, 100.0, min, max));
+        // Outer boundary: exactly max is allowed, further is not
+        assert!(within_band(true, 98.0, 100.0, min, max));
+        assert!(!within_band(true, 97.99, 100.0, min, max));
+        assert!(within_band(false, 102.0, 100.0, min, max));
+        assert!(!within_band(false, 102.01, 100.0, min, max));
+        // Crossed quote and missing mid are always rejected
+        assert!(!within_band(true, 100.5, 100.0, 0.0, max));
+        assert!(!within_band(false, 99.5, 100.0, 0.0, max));
+        assert!(!within_band(true, 99.0, 0.0, min, max));
+    }
+
+    #[test]
+    fn test_recon_partial_fill_cancel_pending_order() {
+        let v: serde_json::Value = serde_json::from_str(r#"{"code":"200000","data":{"items":[
+            {"id":"o1","clientOid":"b3_7","side":"buy","price":"150.00","size":"0.30","dealSize":"0.10","isActive":true,"cancelExist":true},
+            {"id":"o2","clientOid":"a3_8","side":"sell","price":"151.00","size":"0.30","dealSize":"0.05","isActive":true,"cancelExist":false},
+            {"id":"o3","clientOid":"b4_9","side":"buy","price":"149.00","size":"0.30","dealSize":"0.30","isActive":false,"cancelExist":false}
+        ]}}"#).unwrap();
+        let orders = parse_active_orders(&v);
+        assert_eq!(orders.len(), 2);
+        assert!((orders[0].size - 0.2).abs() < 1e-9 && orders[0].cancel_exist);
+        assert!((orders[1].size - 0.25).abs() < 1e-9 && !orders[1].cancel_exist);
+
+        let live = |id: &str| LevelOrderState::Live { order_id: id.into(), price: 150.0, remaining_size: 0.3, placed_at: Instant::now() };
+        // Being cancelled: not counted, moved to CancelPending
+        let mut bid = live("o1");
+        assert_eq!(sync_live(&mut bid, &orders[0]), 0.0);
+        assert!(matches!(bid, LevelOrderState::CancelPending { .. }) && bid.order_id() == Some("o1"));
+        // Partially filled: remainder counted and tracked
+        let mut ask = live("o2");
+        assert!((sync_live(&mut ask, &orders[1]) - 0.25).abs() < 1e-9);
+        assert!(matches!(ask, LevelOrderState::Live { remaining_size, .. } if (remaining_size - 0.25).abs() < 1e-9));
+
+        // Restart adoption holds the slot but doesn't treat it as resting
+        let mut levels: HashMap<i32, (LevelOrderState, LevelOrderState)> = HashMap::new();
+        levels.insert(3, (LevelOrderState::Empty, LevelOrderState::Empty));
+        assert!(adopt_orders(&orders, &mut levels).is_empty());
+        assert!(matches!(levels[&3].0, LevelOrderState::CancelPending { .. }) && levels[&3].1.is_live());
+    }
+
+    #[test]
+    fn test_hold_placements_keeps_cancels() {
+        let cancel = LevelOp::Cancel { key: 9, is_bid: true, order_id: "old".into(), price: 99.0 };
+        let ops = || vec![place_op(1, true), cancel.clone(), place_op(2, false)];
+        let (send, held) = hold_placements(ops(), Admit::All);
+        assert_eq!((send.len(), held.len()), (3, 0));
+        let (send, held) = hold_placements(ops(), Admit::None);
+        assert!(matches!(send[..], [LevelOp::Cancel { .. }]) && held.len() == 2);
+        // Probe: first placement plus the cancel
+        let (send, held) = hold_placements(ops(), Admit::One);
+        assert!(matches!(send[..], [LevelOp::Place { key: 1, .. }, LevelOp::Cancel { .. }]));
+        assert!(matches!(held[..], [LevelOp::Place { key: 2, .. }]));
+    }
+
+    #[test]
+    fn test_mid_budget_skips_stale_placements() {
+        let t0 = Instant::now();
+        let ms = Duration::from_millis;
+        let b = MidBudget { mid_at: t0, est_latency: ms(40), budget: ms(300) };
+        assert!(!b.exceeded(t0 + ms(200)));
+        assert!(!b.exceeded(t0 + ms(260)));
+        // 261ms old + 40ms to land > 300ms
+        assert!(b.exceeded(t0 + ms(261)));
+        // Slower order entry eats into the budget
+        assert!(MidBudget { est_latency: ms(150), ..b }.exceeded(t0 + ms(200)));
+    }
+
+    #[test]
+    fn test_reduction_stops_at_target_band() {
+        // Target flat, no band - all the way to zero (the old behaviour)
+        assert_eq!(reduction_to_target(3.0, 0.0, 0.0), 3.0);
+        assert_eq!(reduction_to_target(-2.0, 0.0, 0.0), -2.0);
+        // Long 5 vs a 2 SOL target: sell 3, not 5
+        assert_eq!(reduction_to_target(5.0, 2.0, 0.5), 3.0);
+        // Below target: buy back up to it
+        assert_eq!(reduction_to_target(-1.0, 2.0, 0.5), -3.0);
+        // Inside the band - stop
+        assert_eq!(reduction_to_target(2.4, 2.0, 0.5), 0.0);
+        assert_eq!(reduction_to_target(1.5, 2.0, 0.5), 0.0);
+    }
+
+    #[test]
+    fn test_min_rest_defers_refresh_of_young_orders() {
+        let t0 = Instant::now();
+        let ms = Duration::from_millis;
+        let live = LevelOrderState::Live { order_id: "b1".into(), price: 100.0, remaining_size: 0.1, placed_at: t0 };
+        assert_eq!(rest_remaining(&live, ms(1500), t0 + ms(400)), Some(ms(1100)));
+        assert_eq!(rest_remaining(&live, ms(1500), t0 + ms(1500)), None);
+        // Off, or already being cancelled - never held
+        assert_eq!(rest_remaining(&live, Duration::ZERO, t0), None);
+        let pending = LevelOrderState::CancelPending { order_id: "b1".into(), price: 100.0, sent_at: t0, attempts: 1 };
+        assert_eq!(rest_remaining(&pending, ms(1500), t0), None);
+    }
+
+    fn orphan_fixture(n: usize) -> Vec<ActiveOrder> {
+        (0..n).map(|i| ActiveOrder {
+            order_id: format!("o{}", i), client_oid: format!("b2_{}", i), side: Side::Buy, price: 100.0, size: 0.1, deal: 0.0, cancel_exist: false,
+        }).collect()
+    }
+
+    #[test]
+    fn test_orphans_respect_budget() {
+        let orders = orphan_fixture(8);
+        let found = detect_orphans(&orders, &HashSet::new(), &HashMap::new(), 5);
+        assert_eq!(found, vec!["o0", "o1", "o2", "o3", "o4"]);
+        assert!(detect_orphans(&orders, &HashSet::new(), &HashMap::new(), 0).is_empty());
+    }
+
+    #[test]
+    fn test_recently_cancelled_orphans_suppressed() {
+        let orders = orphan_fixture(3);
+        let recent = HashMap::from([("o0".to_string(), Instant::now()), ("o2".to_string(), Instant::now())]);
+        assert_eq!(detect_orphans(&orders, &HashSet::new(), &recent, 5), vec!["o1"]);
+    }
+
+    #[test]
+    fn test_tracked_orders_never_orphans() {
+        let mut orders = orphan_fixture(4);
+        orders[3].client_oid = format!("{}1", latency_probe::PROBE_OID_PREFIX);
+        let tracked: HashSet<String> = ["o0", "o1", "o2"].iter().map(|s| s.to_string()).collect();
+        // Every order is tracked or a probe - nothing to cancel, whatever the budget
+        assert!(detect_orphans(&orders, &tracked, &HashMap::new(), 100).is_empty());
+        orders[3].client_oid = format!("{}1", scratch::SCRATCH_OID_PREFIX);
+        assert!(detect_orphans(&orders, &tracked, &HashMap::new(), 100).is_empty());
+        // A rebate-only flatten rests untracked while halted
+        orders[3].client_oid = format!("{}1", FLATTEN_OID_PREFIX);
+        assert!(detect_orphans(&orders, &tracked, &HashMap::new(), 100).is_empty());
+        let tracked: HashSet<String> = ["o1"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(detect_orphans(&orders, &tracked, &HashMap::new(), 1), vec!["o0"]);
+        assert_eq!(detect_orphans(&orders, &tracked, &HashMap::new(), 5), vec!["o0", "o2"]);
+    }
+
+    #[test]
+    fn test_private_feed_confirms_cancel() {
+        let mut levels: HashMap<i32, (LevelOrderState, LevelOrderState)> = HashMap::new();
+        levels.insert(10, (
+            LevelOrderState::CancelPending { order_id: "b1".into(), price: 99.0, sent_at: Instant::now(), attempts: 1 },
+            LevelOrderState::Live { order_id: "a1".into(), price: 101.0, remaining_size: 0.1, placed_at: Instant::now() },
+        ));
+        levels.insert(20, (LevelOrderState::Empty, LevelOrderState::CancelStuck { order_id: "a2".into(), price: 102.0 }));
+        assert!(confirm_cancel(&mut levels, "b1"));
+        assert!(levels[&10].0.is_empty());
+        assert!(confirm_cancel(&mut levels, "a2"));
+        assert!(levels[&20].1.is_empty());
+        // A done for a live order (filled) or an unknown id is not ours to clear
+        assert!(!confirm_cancel(&mut levels, "a1"));
+        assert!(levels[&10].1.is_live());
+        assert!(!confirm_cancel(&mut levels, "zz"));
+    }
+
+    #[test]
+    fn test_warm_cancel_picks_furthest() {
+        let live = |id: &str, price: f64| LevelOrderState::Live { order_id: id.into(), price, remaining_size: 0.1, placed_at: Instant::now() };
+        let mut levels: HashMap<i32, (LevelOrderState, LevelOrderState)> = HashMap::new();
+        levels.insert(10, (live("b10", 99.9), live("a10", 100.1)));
+        levels.insert(50, (live("b50", 99.5), live("a50", 100.5)));
+        levels.insert(90, (live("b90", 99.1), LevelOrderState::Empty));
+        let none = HashSet::new();
+        assert_eq!(furthest_live(&levels, true, 99.95, &none), Some((90, "b90".into(), 99.1)));
+        assert_eq!(furthest_live(&levels, false, 100.05, &none), Some((50, "a50".into(), 100.5)));
+        // Already being cancelled this tick -> next furthest
+        let planned = HashSet::from([(90, true)]);
+        assert_eq!(furthest_live(&levels, true, 99.95, &planned), Some((50, "b50".into(), 99.5)));
+        // Nothing further out than the new quote -> no victim
+        assert_eq!(furthest_live(&levels, true, 99.0, &none), None);
+
+        // Cap 3 with L10..L90 bids resting: an empty inner level at the cap
+        // cancels the furthest bid, below the cap it just places
+        levels.get_mut(&10).unwrap().0 = LevelOrderState::Empty;
+        levels.insert(70, (live("b70", 99.3), LevelOrderState::Empty));
+        let count = levels.values().filter(|(b, _)| !b.is_empty()).count();
+        assert_eq!(side_slot(&levels, true, 99.9, count, 3, true, &none), SideSlot::WarmCancel(90, "b90".into(), 99.1));
+        assert_eq!(side_slot(&levels, true, 99.9, count, 4, true, &none), SideSlot::Free);
+        // Warm cancel off, or nothing further out: wait for a slot
+        assert_eq!(side_slot(&levels, true, 99.9, count, 3, false, &none), SideSlot::Full);
+        assert_eq!(side_slot(&levels, true, 99.0, count, 3, true, &none), SideSlot::Full);
+    }
+
+    #[test]
+    fn test_matched_level_produces_no_refresh() {
+        let live = LevelOrderState::Live { order_id: "b1".into(), price: 187.23, remaining_size: 0.13, placed_at: Instant::now() };
+        // Same tick (float noise) -> matched, no cancel/replace
+        assert!(is_matched(&live, 187.23000000001, 0.01));
+        // One tick away -> genuinely changed
+        assert!(!is_matched(&live, 187.24, 0.01));
+        // Only live orders count; a pending cancel is not a resting quote
+        let pending = LevelOrderState::CancelPending { order_id: "b1".into(), price: 187.23, sent_at: Instant::now(), attempts: 1 };
+        assert!(!is_matched(&pending, 187.23, 0.01));
+        assert!(!is_matched(&LevelOrderState::Empty, 187.23, 0.01));
+    }
+
+    #[test]
+    fn test_pnl_mixed_maker_taker_fills() {
+        let fill = |side: Side, price: f64, liquidity: Liquidity, fee: Option<f64>| PolledFill {
+            order_id: "o".into(), side, size: 1.0, price, liquidity, fee, fee_currency: "USDT".into(),
+        };
+        let mut pnl = PnL::default();
+        // Maker buy @100, no fee reported -> modelled 1bps rebate
+        pnl.record_fill(&fill(Side::Buy, 100.0, Liquidity::Maker, None), 10.0);
+        // Taker sell @101 (amend crossed) -> no rebate, exchange fee 0.101
+        pnl.record_fill(&fill(Side::Sell, 101.0, Liquidity::Taker, Some(0.101)), 10.0);
+        // Taker buy without reported fee -> 10bps of 102
+        pnl.record_fill(&fill(Side::Buy, 102.0, Liquidity::Taker, None), 10.0);
+
+        assert!((pnl.reb - 0.01).abs() < 1e-12);
+        assert!((pnl.fees - (0.101 + 0.102)).abs() < 1e-12);
+        assert!((pnl.spread - 1.0).abs() < 1e-12);
+        assert!((pnl.net() - (1.0 + 0.01 - 0.203)).abs() < 1e-12);
+        assert!((pnl.inv() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_pnl_books_exchange_reported_fees() {
+        let v: serde_json::Value = serde_json::from_str(r#"{"code":"200000","data":{"items":[
+            {"tradeId":"t1","orderId":"o1","side":"buy","price":"187.25","size":"0.134","liquidity":"maker","fee":"-0.00250915","feeCurrency":"USDT"},
+            {"tradeId":"t2","orderId":"o2","side":"sell","price":"187.40","size":"0.134","liquidity":"maker","fee":"0.0025111","feeCurrency":"USDT"},
+            {"tradeId":"t3","orderId":"o3","side":"sell","price":"187.10","size":"0.2","liquidity":"taker","fee":"0.0002","feeCurrency":"SOL"},
+            {"tradeId":"t1","orderId":"o1","side":"buy","price":"187.25","size":"0.134","liquidity":"maker","fee":"-0.00250915","feeCurrency":"USDT"}
+        ]}}"#).unwrap();
+        let mut seen = SeenTrades::new(0);
+        let fills = parse_fills(&v, &mut seen).fills;
+        assert_eq!(fills.len(), 3);
+        assert_eq!(fills[0].fee, Some(-0.00250915));
+        assert!((fills[2].fee_usdt().unwrap() - 0.03742).abs() < 1e-9);
+
+        let mut pnl = PnL::default();
+        for f in &fills {
+            pnl.record_fill(f, 10.0);
+        }
+        // Rebate on the maker buy, maker fee on the sell, SOL taker fee at the fill price
+        assert!((pnl.reb - 0.00250915).abs() < 1e-12);
+        assert!((pnl.fees - (0.0025111 + 0.03742)).abs() < 1e-9);
+        assert!((pnl.spread - 0.134 * 0.15).abs() < 1e-9);
+
+        // Unknown fee currency falls back to the model
+        let odd = PolledFill { fee_currency: "KCS".into(), ..parse_fills(&v, &mut SeenTrades::new(0)).fills.remove(0) };
+        assert_eq!(odd.fee_usdt(), None);
+    }
+
+    #[test]
+    fn test_fill_seen_on_feed_not_rebooked_by_poll() {
+        let mut seen = SeenTrades::new(100);
+        let upd = |trade_id: &str| OrderUpdate {
+            order_id: "o1".into(), client_oid: "c1".into(), received_at: Instant::now(),
+            kind: OrderUpdateKind::Match { trade_id: trade_id.into(), side: OrderSide::Buy, price: 187.25, size: 0.134, liquidity: Liquidity::Maker },
+        };
+        let mut pnl = PnL::default();
+        let fill = ws_fill(&upd("t1"), &mut seen).unwrap();
+        assert_eq!((fill.side, fill.fee), (Side::Buy, None));
+        pnl.record_fill(&fill, 10.0);
+        // Replayed on the feed, or without a tradeId to dedup on
+        assert!(ws_fill(&upd("t1"), &mut seen).is_none());
+        assert!(ws_fill(&upd("unknown"), &mut seen).is_none());
+
+        // The poll later returns t1 alongside a trade the feed missed
+        let v: serde_json::Value = serde_json::from_str(r#"{"code":"200000","data":{"items":[
+            {"tradeId":"t1","orderId":"o1","side":"buy","price":"187.25","size":"0.134","liquidity":"maker","fee":"-0.00250915","feeCurrency":"USDT"},
+            {"tradeId":"t2","orderId":"o2","side":"buy","price":"187.20","size":"0.1","liquidity":"maker","fee":"-0.0018720","feeCurrency":"USDT"}
+        ]}}"#).unwrap();
+        let polled = parse_fills(&v, &mut seen);
+        assert_eq!(polled.fills.len(), 1);
+        assert_eq!(polled.fills[0].order_id, "o2");
+        for f in &polled.fills {
+            pnl.record_fill(f, 10.0);
+        }
+        assert!((pnl.inv() - 0.234).abs() < 1e-9);
+        // t1's row isn't booked again, but its real fee replaces the estimate
+        assert_eq!(polled.fee_updates.len(), 1);
+        for f in &polled.fee_updates {
+            pnl.correct_fee(f, 10.0);
+        }
+        assert!((pnl.reb - (0.00250915 + 0.0018720)).abs() < 1e-12);
+        assert!(parse_fills(&v, &mut seen).fee_updates.is_empty());
+        // ...and a poll-first trade is skipped when the feed delivers it late
+        assert!(ws_fill(&upd("t2"), &mut seen).is_none());
+    }
+
+    #[test]
+    fn test_same_trade_from_both_sources_books_once() {
+        let upd = OrderUpdate {
+            order_id: "o1".into(), client_oid: "c1".into(), received_at: Instant::now(),
+            kind: OrderUpdateKind::Match { trade_id: "t9".into(), side: OrderSide::Sell, price: 187.4, size: 0.2, liquidity: Liquidity::Maker },
+        };
+        let v: serde_json::Value = serde_json::from_str(r#"{"code":"200000","data":{"items":[
+            {"tradeId":"t9","orderId":"o1","side":"sell","price":"187.40","size":"0.2","liquidity":"maker","fee":"-0.0037","feeCurrency":"USDT"}
+        ]}}"#).unwrap();
+        // Feed first, then the poll - and the other way round
+        for ws_first in [true, false] {
+            let (mut seen, mut pnl) = (SeenTrades::new(100), PnL::default());
+            let mut fills = Vec::new();
+            if ws_first {
+                fills.extend(ws_fill(&upd, &mut seen));
+                fills.extend(parse_fills(&v, &mut seen).fills);
+            } else {
+                fills.extend(parse_fills(&v, &mut seen).fills);
+                fills.extend(ws_fill(&upd, &mut seen));
+            }
+            fills.extend(parse_fills(&v, &mut seen).fills);
+            assert_eq!(fills.len(), 1);
+            for f in &fills {
+                pnl.record_fill(f, 10.0);
+            }
+            assert!((pnl.inv() + 0.2).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_pnl_attribution_with_open_inventory() {
+        let mut pnl = PnL::default();
+        pnl.buy(100.0, 2.0, 0.02);
+        pnl.buy(102.0, 1.0, 0.01);
+        pnl.sell(103.0, 1.0, 0.01);   // closes 1 @100 -> +3 realized
+        pnl.fees = 0.05;
+
+        // Open: 1 @100 + 1 @102, marked at 105
+        assert!((pnl.spread - 3.0).abs() < 1e-12);
+        assert!((pnl.avg_entry().unwrap() - 101.0).abs() < 1e-12);
+        assert!((pnl.open_mtm(105.0) - 8.0).abs() < 1e-12);
+        assert!((pnl.net() - (3.0 + 0.04 - 0.05)).abs() < 1e-12);
+        assert!((pnl.total(105.0) - (pnl.net() + 8.0)).abs() < 1e-12);
+
+        // Short inventory gains when the mark falls
+        let mut short = PnL::default();
+        short.sell(100.0, 1.5, 0.0);
+        assert!((short.open_mtm(98.0) - 3.0).abs() < 1e-12);
+        assert_eq!(PnL::default().avg_entry(), None);
+    }
+
+    #[test]
+    fn test_cold_start_seeds_inventory_over_baseline() {
+        // 12.5 SOL held against a 10 SOL baseline, nothing saved
+        let mut pnl = PnL::default();
+        let inv = cold_start_inventory(12.5, 10.0, pnl.inv()).unwrap();
+        pnl.seed_inventory(inv, 150.0);
+        assert!((pnl.inv() - 2.5).abs() < 1e-12);
+        assert_eq!(pnl.avg_entry(), Some(150.0));
+        // The saved FIFO already agrees - keep its lots
+        assert_eq!(cold_start_inventory(12.5, 10.0, 2.5), None);
+
+        // Under the baseline seeds a short; covering below entry realizes the gap
+        pnl.seed_inventory(cold_start_inventory(9.0, 10.0, 2.5).unwrap(), 150.0);
+        assert!((pnl.inv() + 1.0).abs() < 1e-12);
+        pnl.buy(149.0, 1.0, 0.0);
+        assert!((pnl.spread - 1.0).abs() < 1e-12);
+        assert_eq!(pnl.inv(), 0.0);
+    }
+
+    #[test]
+    fn test_adopt_orders_maps_client_oids_to_levels() {
+        let order = |id: &str, oid: &str, side: &str| ActiveOrder {
+            order_id: id.into(), client_oid: oid.into(), side: side.parse().unwrap(), price: 100.0, size: 0.2, deal: 0.0, cancel_exist: false,
+        };
+        let mut levels: HashMap<i32, (LevelOrderState, LevelOrderState)> = HashMap::new();
+        levels.insert(2, (LevelOrderState::Empty, LevelOrderState::Empty));
+        levels.insert(7, (LevelOrderState::Empty, LevelOrderState::Empty));
+
+        let orders = [
+            order("1", "b2_41", "buy"),
+            order("2", "a7_41", "sell"),
+            order("3", "b2_40", "buy"),     // duplicate slot
+            order("4", "b99_1", "buy"),     // unknown level
+            order("5", "a2_3", "buy"),      // side mismatch
+            order("6", "manual-1", "sell"), // foreign
+        ];
+        let rejected = adopt_orders(&orders, &mut levels);
+        assert_eq!(rejected, vec!["3", "4", "5", "6"]);
+        assert_eq!(levels[&2].0.order_id(), Some("1"));
+        assert_eq!(levels[&7].1.order_id(), Some("2"));
+        assert!(levels[&2].1.is_empty());
+    }
+
+    #[test]
+    fn test_out_of_band_adopted_order_refreshed_on_first_check() {
+        let lc = LevelsConfig::default();
+        let (index, (bps, _)) = (1, levels()[1]);
+        let key = (bps * 10.0) as i32;
+        let order = |id: &str, oid: String, side: &str, price: f64| ActiveOrder {
+            order_id: id.into(), client_oid: oid, side: side.parse().unwrap(), price, size: 0.2, deal: 0.0, cancel_exist: false,
+        };
+        let mut levels: HashMap<i32, (LevelOrderState, LevelOrderState)> = HashMap::new();
+        levels.insert(key, (LevelOrderState::Empty, LevelOrderState::Empty));
+        // The bid was priced off a 100.00 mid before the restart, the ask
+        // off 100.49; the market is at 100.50 now
+        let mid = 100.5;
+        let orders = [
+            order("1", format!("b{}_1", key), "buy", 100.0 * (1.0 - bps / 10000.0)),
+            order("2", format!("a{}_1", key), "sell", 100.49 * (1.0 + bps / 10000.0)),
+        ];
+        assert!(adopt_orders(&orders, &mut levels).is_empty());
+        let mut check = AdoptCheck::new(["1".to_string(), "2".to_string()]);
+
+        // Adopted just now, so min rest would hold both and hysteresis isn't
+        // settled - the first check waives both and goes by price alone
+        let min_rest = Duration::from_secs(5);
+        let over = refresh_trigger_bps(index, &lc);
+        let targets = [mid * (1.0 - bps / 10000.0), mid * (1.0 + bps / 10000.0)];
+        let state = |o: &ActiveOrder| LevelOrderState::Live {
+            order_id: o.order_id.clone(), price: o.price, remaining_size: o.size, placed_at: Instant::now(),
+        };
+        let verdict = |o: &ActiveOrder, target: f64, adopted: bool| RefreshCheck {
+            bps_diff: drift_bps(o.price, target), refresh_over: over, adopted,
+            walk_on: false, risk_cancel: false, hysteresis_ok: false,
+        }.verdict(&state(o), min_rest, Instant::now());
+        assert!(matches!(verdict(&orders[0], targets[0], false), RefreshVerdict::Deferred(_)));
+        for (o, target) in orders.iter().zip(targets) {
+            let v = verdict(o, target, check.is_pending(&o.order_id));
+            assert!(check.resolve(&o.order_id, v == RefreshVerdict::Refresh));
+        }
+        // Bid 50bps behind the market is refreshed, the ask a cent off is kept
+        assert_eq!(verdict(&orders[0], targets[0], true), RefreshVerdict::Refresh);
+        assert_eq!(verdict(&orders[1], targets[1], true), RefreshVerdict::Keep);
+        assert_eq!(check.take_summary(), Some((1, 1)));
+        // Checked once - the next check is an ordinary one again
+        assert!(!check.is_pending("1"));
+    }
+
+    #[test]
+    fn test_inventory_size_exponential_at_high_inventory() {
+        let sym = SymbolInfo::default();
+        let sizing = SizingConfig::default();
+        assert_eq!(inventory_size(0.13, 0.0, &sizing, &sym), 0.13);
+        let sz = inventory_size(0.13, 14.0, &sizing, &sym);
+        assert!((sz - 0.13 * (ETA * 14.0).exp()).abs() < 1e-12);
+        // Smooth decay lands off the lot grid
+        assert!(((sz / sym.base_increment).round() - sz / sym.base_increment).abs() > 1e-6);
+    }
+
+    #[test]
+    fn test_inventory_size_steps_at_high_inventory() {
+        let sym = SymbolInfo { price_increment: 0.01, base_increment: 0.01, base_min_size: 0.1 };
+        let sizing = SizingConfig { policy: SizePolicy::Steps, ..Default::default() };
+        let on_lot = |sz: f64| ((sz / sym.base_increment).round() - sz / sym.base_increment).abs() < 1e-9;
+
+        assert!((inventory_size(0.5, 1.0, &sizing, &sym) - 0.5).abs() < 1e-9);
+        assert!((inventory_size(0.5, 4.0, &sizing, &sym) - 0.37).abs() < 1e-9);
+        assert!((inventory_size(0.5, 7.0, &sizing, &sym) - 0.25).abs() < 1e-9);
+        // Deepest tier would be 0.125 -> 0.12, still above min
+        let sz = inventory_size(0.5, 14.0, &sizing, &sym);
+        assert!((sz - 0.12).abs() < 1e-9 && on_lot(sz));
+        // Small base: tier falls below min, clamps up to it
+        let sz = inventory_size(0.2, 14.0, &sizing, &sym);
+        assert!((sz - 0.1).abs() < 1e-9 && on_lot(sz));
+    }
+
+    #[test]
+    fn test_client_oids_unique_across_ticks() {
+        let mut seq = OidSeq::new(1_700_000_000_000);
+        let mut seen = HashSet::new();
+        // 50 ticks; every level places both sides, half of them get cancelled
+        // and re-placed within the same tick
+        for tick in 0..50 {
+            for &(bps, _) in levels().iter() {
+                let key = (bps * 10.0) as i32;
+                let replaces = if tick % 2 == 0 { 2 } else { 1 };
+                for _ in 0..replaces {
+                    for is_bid in [true, false] {
+                        let oid = seq.next(is_bid, key);
+                        assert_eq!(level_key_from_oid(&oid), Some((is_bid, key)));
+                        assert!(seen.insert(oid.clone()), "duplicate clientOid {}", oid);
+                    }
+                }
+            }
+        }
+        // A restart a minute later seeds past everything the previous run
+        // issued (orders/sec stays far below 1000)
+        let mut restarted = OidSeq::new(1_700_000_060_000);
+        assert!(!seen.contains(&restarted.next(true, 2)));
+    }
+
+    #[test]
+    fn test_exit_anchor_falls_back_when_book_stale() {
+        let mut book = OrderBook::new(symbol().name.clone());
+        book.update_snapshot(vec![(187.40, 5.0)], vec![(187.60, 5.0)], 1);
+        // No book feed: ladder mid, as before
+        assert_eq!(exit_anchor(None, 2000, 187.45, 187.55), ExitAnchor::Mid(187.45));
+        // Fresh book: its mid
+        assert_eq!(exit_anchor(Some(&book), 2000, 187.45, 187.55), ExitAnchor::Mid(187.50));
+        // Stale or empty: reference mid, degraded
+        book.last_update = Instant::now() - Duration::from_secs(10);
+        assert_eq!(exit_anchor(Some(&book), 2000, 187.45, 187.55), ExitAnchor::Degraded(187.55));
+        let empty = OrderBook::new(symbol().name.clone());
+        assert_eq!(exit_anchor(Some(&empty), 2000, 187.45, 187.55), ExitAnchor::Degraded(187.55));
+    }
+
+    #[test]
+    fn test_marketable_price_crosses_mid() {
+        let sym = SymbolInfo::default();
+        // 30bps through 187.50 = 186.9375 / 188.0625, rounded away from mid
+        assert!((marketable_price(true, 187.50, 30.0, &sym) - 186.93).abs() < 1e-9);
+        assert!((marketable_price(false, 187.50, 30.0, &sym) - 188.07).abs() < 1e-9);
+        // Exactly on a tick stays there and survives the wire truncation
+        assert_eq!(sym.price_str(marketable_price(false, 100.0, 10.0, &sym)), "100.10");
+    }
+
+    #[test]
+    fn test_rebate_only_passive_price_and_widen() {
+        let sym = SymbolInfo::default();
+        // First tick strictly beyond mid on the resting side, never through it
+        assert!((passive_price(true, 187.505, &sym) - 187.51).abs() < 1e-9);
+        assert!((passive_price(false, 187.505, &sym) - 187.50).abs() < 1e-9);
+        assert!((passive_price(true, 187.50, &sym) - 187.51).abs() < 1e-9);
+        assert!((passive_price(false, 187.50, &sym) - 187.49).abs() < 1e-9);
+
+        let mut widen = HashMap::new();
+        let bid = place_op(25, true);
+        update_cross_widen(&mut widen, &bid, &OpOutcome::WouldCross, 1.5);
+        update_cross_widen(&mut widen, &bid, &OpOutcome::WouldCross, 1.5);
+        update_cross_widen(&mut widen, &place_op(25, false), &OpOutcome::Failed, 1.5);
+        assert_eq!(widen.get(&(25, true)), Some(&3.0));
+        assert_eq!(widen.get(&(25, false)), None);
+        update_cross_widen(&mut widen, &bid, &OpOutcome::Placed("o1".into()), 1.5);
+        assert!(widen.is_empty());
+    }
+
+    #[test]
+    fn test_layer_distances_book_vwap() {
+        let mut book = OrderBook::new(symbol().name.clone());
+        // 1000 USDT per ask tick from 100.01 up, thin bids
+        let asks: Vec<(f64, f64)> = (1..=50).map(|i| { let p = 100.0 + i as f64 * 0.01; (p, 1000.0 / p) }).collect();
+        book.update_snapshot(vec![(99.99, 5.0), (99.90, 5.0)], asks, 1);
+        let lc = LevelsConfig { far_mode: FarLayerMode::BookVwap, far_vwap_step_usd: 10_000.0, ..Default::default() };
+
+        let table: Vec<f64> = levels().iter().map(|(bps, _)| *bps).collect();
+        let d = layer_distances(&lc, &table, Some(&book));
+        let floor = levels()[lc.far_from_index - 1].0;
+        // Near layers untouched
+        for (i, (bps, _)) in levels().iter().enumerate().take(lc.far_from_index) {
+            assert_eq!(d[i], (*bps, *bps));
+        }
+        // First far layer: VWAP of 10k over ten 1k ticks ~ 100.055 -> ~5.5bps, below
+        // the near-layer floor, so it clamps there
+        assert!((d[lc.far_from_index].1 - floor).abs() < 1e-9);
+        // Deeper layers walk further out and stay ordered
+        let asks: Vec<f64> = d[lc.far_from_index..].iter().map(|x| x.1).collect();
+        assert!(asks.windows(2).all(|w| w[1] >= w[0]));
+        assert!((asks[4] - 25.5).abs() < 0.1);
+        // Past the visible 50k, deeper layers don't fall back inside that
+        assert_eq!(asks[5], asks[4]);
+        // Bids can't absorb 10k -> table bps
+        assert_eq!(d[lc.far_from_index].0, levels()[lc.far_from_index].0);
+
+        // Bps mode ignores the book
+        let d = layer_distances(&LevelsConfig::default(), &table, Some(&book));
+        assert_eq!(d[20], (levels()[20].0, levels()[20].0));
+    }
+
+    #[test]
+    fn test_classify_rest_response() {
+        assert!(classify_response(200, r#"{"code":"200000","data":[]}"#).is_ok());
+        let auth = classify_response(401, r#"{"code":"400005","msg":"Invalid KC-API-SIGN"}"#).unwrap_err();
+        assert!(matches!(auth, RestError::Auth(_)) && !auth.is_transient());
+        assert!(matches!(classify_response(200, r#"{"code":"400003","msg":"KC-API-KEY not exists"}"#), Err(RestError::Auth(_))));
+        assert!(matches!(classify_response(429, r#"{"code":"429000","msg":"Too many requests"}"#), Err(RestError::Api(_))));
+        assert!(matches!(classify_response(502, "<html>bad gateway</html>"), Err(RestError::Parse(_))));
+    }
+
+    #[tokio::test]
+    async fn test_rest_timeout_is_transient() {
+        // Accepts but never answers
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (_sock, _) = listener.accept().await.unwrap();
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        });
+        let client = reqwest::Client::builder().timeout(Duration::from_millis(50)).build().unwrap();
+        let e = client.get(format!("http://{}/", addr)).send().await.map_err(RestError::from_reqwest).unwrap_err();
+        assert_eq!(e, RestError::Timeout);
+        assert!(e.is_transient());
+    }
+
+    #[test]
+    fn test_trade_permission_check() {
+        assert!(check_trade_permission("General,Spot", TradeMode::Trade).is_ok());
+        assert!(check_trade_permission("General, Spot, Margin", TradeMode::MarginTrade).is_ok());
+        // Read-only key
+        let err = check_trade_permission("General", TradeMode::Trade).unwrap_err();
+        assert!(err.contains("Spot"));
+        assert!(check_trade_permission("General,Spot", TradeMode::MarginIsolatedTrade).is_err());
+        assert!(check_trade_permission("", TradeMode::Trade).is_err());
+    }
+
+    #[test]
+    fn test_trade_mode_endpoints() {
+        let cases = [
+            (TradeMode::Trade, "TRADE", "/api/v1/accounts?type=trade"),
+            (TradeMode::MarginTrade, "MARGIN_TRADE", "/api/v1/accounts?type=margin"),
+            (TradeMode::MarginIsolatedTrade, "MARGIN_ISOLATED_TRADE", "/api/v3/isolated/accounts?symbol=SOL-USDT"),
+        ];
+        for (mode, tt, bal_ep) in cases {
+            assert_eq!(fills_endpoint(mode), format!("/api/v1/fills?symbol=SOL-USDT&pageSize=20&tradeType={}", tt));
+            assert_eq!(active_orders_endpoint(mode), format!("/api/v1/orders?symbol=SOL-USDT&status=active&tradeType={}", tt));
+            assert_eq!(balances_endpoint(mode), bal_ep);
+        }
+    }
+
+    #[test]
+    fn test_trade_symbol_from_flag_or_env() {
+        assert_eq!(symbol_from(None, None), "SOL-USDT");
+        assert_eq!(symbol_from(None, Some("ETH-USDT".into())), "ETH-USDT");
+        assert_eq!(symbol_from(Some("btc-usdt".into()), Some("ETH-USDT".into())), "btc-usdt");
+
+        let eth = TradeSymbol::parse("eth-usdt").unwrap();
+        assert_eq!((eth.name.as_str(), eth.base.as_str(), eth.quote.as_str()), ("ETH-USDT", "ETH", "USDT"));
+        assert_eq!(eth.binance_stream(), "ethusdt");
+        assert!(TradeSymbol::parse("ETHUSDT").is_err());
+        assert!(TradeSymbol::parse("ETH-").is_err());
+        assert_eq!(symbol(), &TradeSymbol::parse(DEFAULT_SYMBOL).unwrap());
+    }
+
+    #[test]
+    fn test_parse_balances_per_mode() {
+        let spot = serde_json::json!({"data": [
+            {"currency": "SOL", "available": "1.5"}, {"currency": "USDT", "available": "250.25"}
+        ]});
+        let b = parse_balances(TradeMode::MarginTrade, &spot);
+        assert_eq!((b.sol, b.usdt), (1.5, 250.25));
+        assert_eq!((b.sol_holds, b.usdt_holds), (0.0, 0.0));
+
+        let isolated = serde_json::json!({"data": {"assets": [{
+            "symbol": "SOL-USDT",
+            "baseAsset": {"currency": "SOL", "available": "2", "holds": "0.5"},
+            "quoteAsset": {"currency": "USDT", "available": "100", "holds": "40"}
+        }]}});
+        let b = parse_balances(TradeMode::MarginIsolatedTrade, &isolated);
+        assert_eq!((b.sol, b.usdt), (2.0, 100.0));
+        assert_eq!((b.sol_holds, b.usdt_holds), (0.5, 40.0));
+    }
+
+    #[test]
+    fn test_effective_balance_adds_back_own_holds() {
+        let order = |id: &str, side: &str, price: f64, size: f64| ActiveOrder {
+            order_id: id.into(), client_oid: String::new(), side: side.parse().unwrap(), price, size, deal: 0.0, cancel_exist: false,
+        };
+        let orders = vec![order("b1", "buy", 100.0, 0.25), order("a1", "sell", 101.0, 0.3),
+            order("x1", "buy", 99.0, 1.0)];
+        let mut levels = HashMap::new();
+        levels.insert(10, (
+            LevelOrderState::Live { order_id: "b1".into(), price: 100.0, remaining_size: 0.25, placed_at: Instant::now() },
+            LevelOrderState::CancelPending { order_id: "a1".into(), price: 101.0, sent_at: Instant::now(), attempts: 1 },
+        ));
+        // Someone else's order (x1) stays excluded
+        let own = own_order_holds(&orders, &levels);
+        assert_eq!(own, (25.0, 0.3));
+
+        let bal = Balances { sol: 1.0, usdt: 50.0, sol_holds: 0.3, usdt_holds: 124.0 };
+        let eff = effective_balances(&bal, own);
+        assert!((eff.usdt - 75.0).abs() < 1e-9 && (eff.sol - 1.3).abs() < 1e-9);
+        // Capped at what the account reports as held (holds lagging)
+        let lagging = Balances { usdt_holds: 10.0, sol_holds: 0.0, ..bal };
+        let eff = effective_balances(&lagging, own);
+        assert!((eff.usdt - 60.0).abs() < 1e-9 && eff.sol == 1.0);
+    }
+
+    #[test]
+    fn test_tradable_subtracts_reserve_after_buffer() {
+        // No reserve: just the 2% buffer
+        assert!((tradable(100.0, 0.0) - 98.0).abs() < 1e-9);
+        assert!((tradable(100.0, 25.0) - 73.0).abs() < 1e-9);
+        // Reserve above the balance leaves nothing, never a negative
+        assert_eq!(tradable(10.0, 25.0), 0.0);
+        assert!((tradable(10.0, -5.0) - 9.8).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_fill_before_recon_draws_from_inflight() {
+        let mut c = CommitmentTracker::default();
+        c.add_inflight_bid(50.0);
+        c.fill_bid(20.0);
+        assert!((c.inflight_usdt - 30.0).abs() < 1e-9);
+        assert_eq!(c.live_usdt, 0.0);
+    }
+
+    fn place_op(key: i32, is_bid: bool) -> LevelOp {
+        LevelOp::Place { key, is_bid, price: 100.0, size: 0.5, req: WsOrderRequest {
+            symbol: symbol().name.clone(), side: if is_bid { "buy" } else { "sell" }.into(),
+            price: "100".into(), size: "0.5".into(), client_oid: format!("{}{}_1", if is_bid { 'b' } else { 'a' }, key),
+            order_type: "limit".into(), time_in_force: Some("GTC".into()), post_only: Some(true), stp: None,
+            cancel_after: None,
+        }}
+    }
+
+    #[tokio::test]
+    async fn test_signal_mid_tick_places_nothing_after_cancel_all() {
+        let auth = KucoinAuth::new(String::new(), String::new(), String::new(), true);
+        let ws = WsOrderClientV2::new(auth, String::new(), String::new());
+        let shutdown = Shutdown::new();
+        // Before the signal the REST batch goes out whole
+        let (send, held) = hold_batch_on_shutdown(vec![place_op(1, true), place_op(2, false)], &shutdown);
+        assert_eq!((send.len(), held.len()), (2, 0));
+
+        // The signal lands mid-tick: the rest of the batch is held...
+        begin_shutdown(&ws, &shutdown);
+        let (send, held) = hold_batch_on_shutdown(vec![place_op(3, true), place_op(4, false)], &shutdown);
+        assert_eq!((send.len(), held.len()), (0, 2));
+        // ...and the WS client refuses placements still in flight in the tick
+        let LevelOp::Place { req, .. } = place_op(5, true) else { panic!() };
+        let err = ws.place_order(req).await.unwrap_err();
+        assert!(err.to_string().contains("order entry closed"));
+    }
+
+    #[tokio::test]
+    async fn test_level_ops_apply_independent_of_completion_order() {
+        // Later ops finish first under concurrency
+        let ops: Vec<LevelOp> = (0..4).map(|k| place_op(k, k % 2 == 0))
+            .chain([LevelOp::Cancel { key: 9, is_bid: true, order_id: "old".into(), price: 99.0 }])
+            .collect();
+        let results = run_level_ops(ops, 5, |op| async move {
+            let outcome = match &op {
+                LevelOp::Place { key, .. } => {
+                    tokio::time::sleep(Duration::from_millis(20 - *key as u64 * 5)).await;
+                    if *key == 3 { OpOutcome::Failed } else { OpOutcome::Placed(format!("o{}", key)) }
+                }
+                _ => OpOutcome::Sent,
+            };
+            (op, outcome)
+        }).await;
+        assert_eq!(results.len(), 5);
+        assert!(matches!(results[0].0, LevelOp::Cancel { .. }));
+
+        let mut levels = HashMap::new();
+        let mut c = CommitmentTracker::default();
+        // Reservations as made while planning
+        c.add_inflight_bid(100.0);
+        c.add_inflight_bid(50.0);
+        c.add_inflight_ask(1.0);
+        for (op, outcome) in results.iter().rev() {
+            apply_level_op(&mut levels, &mut c, op, outcome);
+        }
+        assert!(matches!(&levels[&0].0, LevelOrderState::Live { order_id, .. } if order_id == "o0"));
+        assert!(matches!(&levels[&1].1, LevelOrderState::Live { order_id, .. } if order_id == "o1"));
+        assert!(!levels.contains_key(&3));
+        assert!(matches!(levels[&9].0, LevelOrderState::CancelPending { .. }));
+        // Failed ask released its reservation
+        assert!((c.inflight_sol - 0.5).abs() < 1e-9);
+        assert!((c.inflight_usdt - 150.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_post_only_backoff_one_tick_inside_touch() {
+        let sym = SymbolInfo::default();
+        let close = |a: Option<f64>, b: f64| a.is_some_and(|a| (a - b).abs() < 1e-9);
+        // Bid rounded up onto the best ask: one tick under it
+        assert!(close(post_only_backoff(true, 100.05, Some(100.05), 0.01, 3), 100.04));
+        // Ask on the best bid: one tick over it; two ticks through: three back
+        assert!(close(post_only_backoff(false, 100.00, Some(100.00), 0.01, 3), 100.01));
+        assert!(close(post_only_backoff(true, 100.07, Some(100.05), 0.01, 3), 100.04));
+        // Already inside, further than the max, or off: untouched
+        assert_eq!(post_only_backoff(true, 100.04, Some(100.05), 0.01, 3), None);
+        assert_eq!(post_only_backoff(true, 100.10, Some(100.05), 0.01, 3), None);
+        assert_eq!(post_only_backoff(true, 100.05, Some(100.05), 0.01, 0), None);
+
+        // A would-cross reject retries one tick further out, same size, as a
+        // new order of the same level
+        let op = place_op(4, true);
+        let LevelOp::Place { req, .. } = &op else { panic!() };
+        let Some(LevelOp::Place { price: to, req: retry, .. }) = backed_off_place(&op, &sym, 1) else { panic!() };
+        assert!((to - 99.99).abs() < 1e-9);
+        assert_eq!(retry.price, sym.price_str(to));
+        assert_eq!(retry.size, req.size);
+        assert_ne!(retry.client_oid, req.client_oid);
+        assert_eq!(level_key_from_oid(&retry.client_oid), Some((true, 4)));
+        let Some(LevelOp::Place { price: up, .. }) = backed_off_place(&place_op(4, false), &sym, 1) else { panic!() };
+        assert!((up - 100.01).abs() < 1e-9);
+        // Off, or not a placement: no retry
+        assert!(backed_off_place(&op, &sym, 0).is_none());
+        assert!(backed_off_place(&LevelOp::RestCancel { order_id: "o".into() }, &sym, 1).is_none());
+    }
+
+    #[test]
+    fn test_rest_order_side_parsed_not_defaulted() {
+        let LevelOp::Place { req, .. } = place_op(4, false) else { panic!() };
+        assert_eq!(rest_order(&req).unwrap().side, Side::Sell);
+        let upper = WsOrderRequest { side: "SELL".into(), ..req.clone() };
+        assert_eq!(rest_order(&upper).unwrap().side, Side::Sell);
+        // A typo is an error, not a buy
+        assert!(rest_order(&WsOrderRequest { side: "sel".into(), ..req }).is_err());
+    }
+
+    #[test]
+    fn test_merged_level_order_resized() {
+        let mut placed_lots = HashMap::new();
+        // L1 resting alone; L2 and L3 then round onto its tick and merge into it
+        placed_lots.insert((10, true), 1);
+        let mut q = [SideQuote::new(187.50, 187.505), SideQuote::new(187.50, 187.505), SideQuote::new(187.50, 187.505)];
+        price_collision::resolve(&mut q, true, 0.01, PriceCollision::Merge);
+        assert!(merge_resize_due(&placed_lots, 10, true, &q[0]));
+        // The merged-away levels are cancelled as before, not resized
+        assert!(!merge_resize_due(&placed_lots, 20, true, &q[1]));
+        // Re-placed at three levels' size: settled until the merge dissolves
+        placed_lots.insert((10, true), q[0].lots);
+        assert!(!merge_resize_due(&placed_lots, 10, true, &q[0]));
+        assert!(merge_resize_due(&placed_lots, 10, true, &SideQuote::new(187.50, 187.505)));
+        // Never merged (adopted or placed before): one level's size
+        assert!(!merge_resize_due(&placed_lots, 30, false, &SideQuote::new(187.52, 187.515)));
+    }
+
+    #[test]
+    fn test_walk_one_tick_steps_to_target_then_stops() {
+        let tick = 0.01;
+        // Bid at 100.00, level price now 100.04: one tick per step, then done
+        let mut levels = HashMap::new();
+        let mut c = CommitmentTracker::default();
+        let mut price = 100.0;
+        let mut steps = Vec::new();
+        while let Some(step) = reprice_step(RepriceMode::WalkOneTick, price, 100.04, tick, 5) {
+            let Reprice::Modify(to) = step else { panic!("walk fell back at {}", price) };
+            let op = LevelOp::Modify { key: 10, is_bid: true, order_id: format!("o{}", steps.len()), price: to, size: 0.5 };
+            apply_level_op(&mut levels, &mut c, &op, &OpOutcome::Placed(format!("o{}", steps.len() + 1)));
+            let LevelOrderState::Live { price: p, ref order_id, remaining_size, .. } = levels[&10].0 else { panic!() };
+            assert_eq!((order_id.as_str(), remaining_size), (format!("o{}", steps.len() + 1).as_str(), 0.5));
+            price = p;
+            steps.push(price);
+            assert!(steps.len() <= 4);
+        }
+        let expect = [100.01, 100.02, 100.03, 100.04];
+        assert!(steps.iter().zip(expect).all(|(a, b)| (a - b).abs() < 1e-9), "{:?}", steps);
+        // Walking down works the same way
+        assert!(matches!(reprice_step(RepriceMode::WalkOneTick, 100.04, 100.0, tick, 5),
+            Some(Reprice::Modify(p)) if (p - 100.03).abs() < 1e-9));
+        // Beyond the gap: cancel/replace rather than lag the move; 0 = no limit
+        assert_eq!(reprice_step(RepriceMode::WalkOneTick, 100.0, 100.06, tick, 5), Some(Reprice::CancelReplace));
+        assert!(matches!(reprice_step(RepriceMode::WalkOneTick, 100.0, 100.06, tick, 0), Some(Reprice::Modify(_))));
+        // Modify goes straight there; cancel/replace is unchanged
+        assert!(matches!(reprice_step(RepriceMode::Modify, 100.0, 100.06, tick, 5),
+            Some(Reprice::Modify(p)) if (p - 100.06).abs() < 1e-9));
+        assert_eq!(reprice_step(RepriceMode::CancelReplace, 100.0, 100.06, tick, 5), Some(Reprice::CancelReplace));
+        // A failed modify leaves the order as it was
+        let op = LevelOp::Modify { key: 10, is_bid: true, order_id: "o4".into(), price: 100.05, size: 0.5 };
+        apply_level_op(&mut levels, &mut c, &op, &OpOutcome::Failed);
+        assert!(matches!(&levels[&10].0, LevelOrderState::Live { order_id, .. } if order_id == "o4"));
+
+        // The bid commitment follows the modified price, not the old one
+        let mut c = CommitmentTracker { live_usdt: 80.0, ..Default::default() };
+        let op = LevelOp::Modify { key: 10, is_bid: true, order_id: "o4".into(), price: 101.0, size: 0.5 };
+        apply_level_op(&mut levels, &mut c, &op, &OpOutcome::Placed("o5".into()));
+        assert!((c.live_usdt - (80.0 - 0.5 * 100.04)).abs() < 1e-9);
+        assert!((c.inflight_usdt - 0.5 * 101.0).abs() < 1e-9);
+    }
+
+    // Full-ladder requote time vs concurrency: cargo test -- --ignored --nocapture
+    #[tokio::test]
+    #[ignore]
+    async fn bench_full_ladder_requote() {
+        let rtt = Duration::from_millis(5);
+        for k in [1, 2, 4, 8] {
+            let ops: Vec<LevelOp> = (0..levels().len() as i32 * 2).map(|i| place_op(i, i % 2 == 0)).collect();
+            let start = Instant::now();
+            run_level_ops(ops, k, |op| async move {
+                tokio::time::sleep(rtt).await;
+                (op, OpOutcome::Sent)
+            }).await;
+            println!("concurrency {}: {} ops in {:?}", k, levels().len() * 2, start.elapsed());
+        }
+    }
+
+    #[test]
+    fn test_min_refresh_floor() {
+        // Default floor leaves the table untouched
+        for (i, (_, thresh)) in levels().iter().enumerate() {
+            assert_eq!(refresh_threshold(i, 0.0), *thresh);
+        }
+        // Inner levels lifted to the floor, wider ones keep their own value
+        assert_eq!(refresh_threshold(0, 5.0), 5.0);
+        assert_eq!(refresh_threshold(levels().len() - 1, 5.0), levels()[levels().len() - 1].1);
+    }
+
+    #[test]
+    fn test_gtt_far_layers() {
+        // Off by default: everything GTC on the table threshold
+        let lc = LevelsConfig::default();
+        assert_eq!(level_time_in_force(20, &lc), ("GTC", None));
+        assert_eq!(refresh_trigger_bps(20, &lc), levels()[20].1);
+
+        let lc = LevelsConfig { gtt_from_index: 15, gtt_cancel_after_secs: 120, ..Default::default() };
+        assert_eq!(level_time_in_force(14, &lc), ("GTC", None));
+        assert_eq!(level_time_in_force(15, &lc), ("GTT", Some(120)));
+        assert_eq!(refresh_trigger_bps(14, &lc), levels()[14].1);
+        assert_eq!(refresh_trigger_bps(15, &lc), levels()[15].1 * 2.0);
+    }
+
+    #[test]
+    fn test_parse_rest_book() {
+        let v: serde_json::Value = serde_json::from_str(r#"{"code":"200000","data":{"sequence":"1","time":1,
+            "bids":[["187.50","2"],["187.49","8.25"]],"asks":[["187.52","10.5"]]}}"#).unwrap();
+        let (bids, asks) = parse_rest_book(&v).unwrap();
+        assert_eq!(bids, vec![(187.50, 2.0), (187.49, 8.25)]);
+        assert_eq!(asks, vec![(187.52, 10.5)]);
+        assert!(parse_rest_book(&serde_json::json!({"code": "400100"})).is_none());
+    }
+
+    #[test]
+    fn test_parse_symbol_trading() {
+        let v = serde_json::json!({"code": "200000", "data": {"symbol": "SOL-USDT", "enableTrading": false}});
+        assert_eq!(parse_symbol_trading(&v), Some(false));
+        assert_eq!(parse_symbol_trading(&serde_json::json!({"code": "200000", "data": {"enableTrading": true}})), Some(true));
+        // Delisted outright; any other error is no verdict
+        assert_eq!(parse_symbol_trading(&serde_json::json!({"code": "900001", "msg": "symbol not exists"})), Some(false));
+        assert_eq!(parse_symbol_trading(&serde_json::json!({"code": "429000", "msg": "Too many requests"})), None);
+    }
+
+    #[test]
+    fn test_basis_skew_tightens_converging_side() {
+        // KuCoin 4bps rich: positive skew -> bids widen, asks tighten
+        let k = basis_skew_bps(100.04, 100.0, 0.5, 3.0);
+        assert!((k - 2.0).abs() < 1e-9);
+        // Cheap mirrors it, capped
+        assert!((basis_skew_bps(99.9, 100.0, 0.5, 3.0) + 3.0).abs() < 1e-9);
+        // Default sensitivity or a missing mid stays symmetric
+        assert_eq!(basis_skew_bps(100.04, 100.0, 0.0, 3.0), 0.0);
+        assert_eq!(basis_skew_bps(0.0, 100.0, 0.5, 3.0), 0.0);
+    }
+
+    #[test]
+    fn test_jitter_within_bounds_and_increments() {
+        let sym = SymbolInfo { price_increment: 0.01, base_increment: 0.0001, base_min_size: 0.01 };
+        let on_grid = |v: f64, inc: f64| ((v / inc).round() * inc - v).abs() < 1e-9;
+        for i in 0..=200 {
+            let u = i as f64 / 100.0 - 1.0;
+            let sz = jitter_size(0.1333, 10.0, u, &sym);
+            assert!((0.1199..=0.1467).contains(&sz) && on_grid(sz, sym.base_increment), "{}", sz);
+            // Never below the exchange minimum
+            assert!(jitter_size(0.0105, 50.0, u, &sym) >= sym.base_min_size);
+
+            // Away from mid only, capped at half the 1.92bps threshold
+            let bp = jitter_price(true, 187.50, 5.0, 1.92, u, &sym);
+            let ap = jitter_price(false, 187.52, 5.0, 1.92, u, &sym);
+            assert!((187.50 * (1.0 - 0.96 / 10000.0) - 0.005..=187.50).contains(&bp) && on_grid(bp, 0.01));
+            assert!((187.52..=187.52 * (1.0 + 0.96 / 10000.0) + 0.005).contains(&ap) && on_grid(ap, 0.01));
+        }
+        // Off by default
+        assert_eq!(jitter_size(0.1333, 0.0, 0.9, &sym), 0.1333);
+        assert_eq!(jitter_price(true, 187.5, 0.0, 1.92, 0.9, &sym), 187.5);
+    }
+
+    #[test]
+    fn test_fill_flow_scales_filling_side() {
+        let sym = SymbolInfo { price_increment: 0.01, base_increment: 0.0001, base_min_size: 0.01 };
+        // Bids filled 3:1 -> imbalance 0.5, sensitivity 1 halves the bid
+        let (b, a) = fill_flow::size_multipliers(0.5, 1.0, 0.25);
+        assert_eq!(scaled_size(0.1333, b, &sym), 0.0666);
+        assert_eq!(scaled_size(0.1333, a, &sym), 0.1333);
+        assert_eq!(scaled_size(0.0150, 0.25, &sym), sym.base_min_size);
+    }
+
+    #[test]
+    fn test_side_multipliers_applied() {
+        let sym = SymbolInfo { price_increment: 0.01, base_increment: 0.0001, base_min_size: 0.01 };
+        let qc = QuotingConfig::default();
+        assert_eq!(side_spreads(2.0, 3.0, &qc), (2.0, 3.0));
+        assert_eq!(side_size(0.1333, qc.bid_size_mult, &sym), 0.1333);
+
+        let qc = QuotingConfig { bid_spread_mult: 1.5, ask_spread_mult: 0.5, ..QuotingConfig::default() };
+        assert_eq!(side_spreads(2.0, 3.0, &qc), (3.0, 1.5));
+        // Up or down, lot-aligned and floored at the minimum
+        assert_eq!(side_size(0.1333, 2.0, &sym), 0.2666);
+        assert_eq!(side_size(0.1333, 0.5, &sym), 0.0666);
+        assert_eq!(side_size(0.0150, 0.1, &sym), sym.base_min_size);
+    }
+
+    #[test]
+    fn test_book_boost_grows_favoured_side_bounded() {
+        let sym = SymbolInfo { price_increment: 0.01, base_increment: 0.0001, base_min_size: 0.01 };
+        let off = SizingConfig::default();
+        assert_eq!(book_boost_multipliers(Some(0.9), &off), (1.0, 1.0));
+
+        let sizing = SizingConfig { book_boost_coeff: 1.0, ..SizingConfig::default() };
+        // No book, or inside the threshold
+        assert_eq!(book_boost_multipliers(None, &sizing), (1.0, 1.0));
+        assert_eq!(book_boost_multipliers(Some(-0.3), &sizing), (1.0, 1.0));
+        // Bid-heavy 0.5 -> bids x1.2, lot-rounded
+        let (b, a) = book_boost_multipliers(Some(0.5), &sizing);
+        assert!((b - 1.2).abs() < 1e-9 && a == 1.0);
+        assert!((side_size(0.1333, b, &sym) - 0.1599).abs() < 1e-9);
+        // Ask-heavy 0.95 -> 1.65, capped at 1.5
+        assert_eq!(book_boost_multipliers(Some(-0.95), &sizing), (1.0, 1.5));
+        assert!((side_size(0.1333, 1.5, &sym) - 0.1999).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_boosted_order_clamped_to_level_notional() {
+        let sym = SymbolInfo { price_increment: 0.01, base_increment: 0.0001, base_min_size: 0.01 };
+        // 0.1333 boosted x1.5 = 0.1999 SOL @ 150 = $29.99, over a $25 cap
+        let boosted = side_size(0.1333, 1.5, &sym);
+        let (sz, clamped) = cap_notional(boosted, 150.0, 25.0, &sym);
+        assert!(clamped && (sz - 0.1666).abs() < 1e-9);
+        assert!(sz * 150.0 <= 25.0);
+        // Lot-valid: a whole number of increments, above the minimum
+        let lots = sz / sym.base_increment;
+        assert!((lots - lots.round()).abs() < 1e-6 && sz >= sym.base_min_size);
+        // Under the cap, or off: untouched
+        assert_eq!(cap_notional(0.1333, 150.0, 25.0, &sym), (0.1333, false));
+        assert_eq!(cap_notional(boosted, 150.0, 0.0, &sym), (boosted, false));
+        // A cap below one minimum order still places the minimum
+        assert_eq!(cap_notional(boosted, 150.0, 1.0, &sym), (0.01, true));
+    }
+
+    #[test]
+    fn test_equal_fill_prob_spacing_monotone() {
+        let (first, last) = (levels()[0].0, levels()[levels().len() - 1].0);
+        for sigma in [0.0, 0.016, 0.3, 1.0, 5.0] {
+            let d = equal_fill_prob_spacing(sigma, 60.0, first, last, levels().len());
+            assert_eq!(d.len(), levels().len());
+            assert!(d.windows(2).all(|w| w[1] > w[0]), "sigma {}: {:?}", sigma, d);
+            assert!((d[0] - first).abs() < 1e-9 && d[d.len() - 1] <= last + 0.5);
+        }
+        // Dense near mid, sparse far out
+        let d = equal_fill_prob_spacing(0.5, 60.0, first, last, levels().len());
+        assert!(d[1] - d[0] < d[d.len() - 1] - d[d.len() - 2]);
+    }
+}
@@ -0,0 +1,108 @@
+//! Symbol Trading Status
+//!
+//! When KuCoin delists or suspends a symbol its resting orders are cancelled
+//! exchange-side and every new one is rejected, so the ladder would keep
+//! re-placing into a dead market. Quoting halts on either signal - the
+//! symbol endpoint reporting `enableTrading: false`, or a run of
+//! not-trading rejects - and the periodic symbol check resumes it once the
+//! exchange reports trading again and the halt has lasted `min_halt`.
+
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HaltReason {
+    /// Symbol endpoint reports trading disabled
+    Inactive,
+    /// Consecutive not-trading placement rejects
+    Rejects(u32),
+}
+
+impl HaltReason {
+    pub fn describe(&self) -> String {
+        match self {
+            HaltReason::Inactive => "symbol reported not trading".into(),
+            HaltReason::Rejects(n) => format!("{} placements rejected as not trading", n),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct MarketStatus {
+    reject_threshold: u32,
+    min_halt: Duration,
+    rejects: u32,
+    halted: Option<(Instant, HaltReason)>,
+}
+
+impl MarketStatus {
+    pub fn new(reject_threshold: u32, min_halt: Duration) -> Self {
+        Self { reject_threshold: reject_threshold.max(1), min_halt, rejects: 0, halted: None }
+    }
+
+    pub fn halted(&self) -> Option<HaltReason> {
+        self.halted.map(|(_, r)| r)
+    }
+
+    fn halt(&mut self, reason: HaltReason, now: Instant) {
+        if self.halted.is_none() {
+            self.halted = Some((now, reason));
+        }
+    }
+
+    /// Placement rejected because the symbol isn't trading
+    pub fn on_reject(&mut self, now: Instant) {
+        self.rejects += 1;
+        if self.rejects >= self.reject_threshold {
+            self.halt(HaltReason::Rejects(self.rejects), now);
+        }
+    }
+
+    pub fn on_placed(&mut self) {
+        self.rejects = 0;
+    }
+
+    /// Result of the periodic symbol check
+    pub fn on_symbol_status(&mut self, enable_trading: bool, now: Instant) {
+        match self.halted {
+            None if !enable_trading => self.halt(HaltReason::Inactive, now),
+            Some((at, _)) if enable_trading && now.duration_since(at) >= self.min_halt => {
+                self.halted = None;
+                self.rejects = 0;
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_halt_on_rejects_or_inactive_and_resume() {
+        let t0 = Instant::now();
+        let s = Duration::from_secs;
+        let mut m = MarketStatus::new(3, s(60));
+        m.on_reject(t0);
+        m.on_reject(t0);
+        // A placement going through resets the run
+        m.on_placed();
+        m.on_reject(t0);
+        m.on_reject(t0);
+        assert_eq!(m.halted(), None);
+        m.on_reject(t0);
+        assert_eq!(m.halted(), Some(HaltReason::Rejects(3)));
+        // Trading again but too soon - stay out
+        m.on_symbol_status(true, t0 + s(30));
+        assert!(m.halted().is_some());
+        m.on_symbol_status(false, t0 + s(90));
+        assert_eq!(m.halted(), Some(HaltReason::Rejects(3)));
+        m.on_symbol_status(true, t0 + s(90));
+        assert_eq!(m.halted(), None);
+
+        m.on_symbol_status(false, t0 + s(100));
+        assert_eq!(m.halted(), Some(HaltReason::Inactive));
+        m.on_symbol_status(true, t0 + s(160));
+        assert_eq!(m.halted(), None);
+    }
+}
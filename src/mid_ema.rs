@@ -0,0 +1,88 @@
+//! Smoothed Reference Mid
+//!
+//! The instantaneous reference mid carries tick-to-tick microstructure
+//! noise, and the whole ladder and every refresh target move with it. A
+//! time-based EMA with a short half-life takes most of that out, at the
+//! cost of trailing a real move by about the half-life. Updates arrive
+//! irregularly, so each mid is weighted by how long it was the mid.
+
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone)]
+pub struct MidEma {
+    half_life: Duration,
+    value: f64,
+    last_mid: f64,
+    at: Option<Instant>,
+}
+
+impl MidEma {
+    /// Zero half-life follows the mid exactly
+    pub fn new(half_life: Duration) -> Self {
+        Self { half_life, value: 0.0, last_mid: 0.0, at: None }
+    }
+
+    /// Share of the gap to the current mid closed after `dt`
+    fn weight(&self, dt: Duration) -> f64 {
+        if self.half_life.is_zero() {
+            return 1.0;
+        }
+        1.0 - 0.5_f64.powf(dt.as_secs_f64() / self.half_life.as_secs_f64())
+    }
+
+    pub fn update(&mut self, mid: f64, now: Instant) {
+        if mid <= 0.0 {
+            return;
+        }
+        self.value = self.value_at(now).unwrap_or(mid);
+        self.last_mid = mid;
+        self.at = Some(now);
+    }
+
+    /// Smoothed mid at `now`, the last mid holding since it arrived; None
+    /// before the first
+    pub fn value_at(&self, now: Instant) -> Option<f64> {
+        let at = self.at?;
+        Some(self.value + self.weight(now.saturating_duration_since(at)) * (self.last_mid - self.value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_smooths_noise_and_lags_a_step_by_half_life() {
+        let t0 = Instant::now();
+        let ms = |n: u64| t0 + Duration::from_millis(n);
+        let mut ema = MidEma::new(Duration::from_millis(200));
+        assert_eq!(ema.value_at(t0), None);
+
+        // +-5 cent flicker every 10ms around 100: the EMA barely moves
+        ema.update(100.0, t0);
+        let mut worst: f64 = 0.0;
+        for i in 1..=100 {
+            ema.update(if i % 2 == 0 { 100.05 } else { 99.95 }, ms(i * 10));
+            worst = worst.max((ema.value_at(ms(i * 10)).unwrap() - 100.0).abs());
+        }
+        assert!(worst < 0.005, "ema wandered {}", worst);
+
+        // Step to 101: half the gap closed one half-life later, 3/4 after two
+        let mut ema = MidEma::new(Duration::from_millis(200));
+        ema.update(100.0, t0);
+        ema.update(101.0, ms(1000));
+        let at = |n: u64| ema.value_at(ms(n)).unwrap();
+        assert!((at(1000) - 100.0).abs() < 1e-9);
+        assert!((at(1200) - 100.5).abs() < 1e-9);
+        assert!((at(1400) - 100.75).abs() < 1e-9);
+        // Further updates at the same mid don't change the path
+        ema.update(101.0, ms(1100));
+        assert!((ema.value_at(ms(1200)).unwrap() - 100.5).abs() < 1e-9);
+
+        // Off: the raw mid
+        let mut raw = MidEma::new(Duration::ZERO);
+        raw.update(100.0, t0);
+        raw.update(101.0, ms(5));
+        assert_eq!(raw.value_at(ms(5)), Some(101.0));
+    }
+}
@@ -0,0 +1,73 @@
+//! OFI Resume Hysteresis
+//!
+//! After an OFI pause, book OFI dropping back under the resume threshold is
+//! not enough on its own - a sweep can pause for a beat while the book
+//! refills. Resume also needs the Binance aggressor imbalance calm, and
+//! both must stay calm for a minimum duration.
+
+use std::time::{Duration, Instant};
+
+#[derive(Debug)]
+pub struct ResumeGate {
+    ofi_max: f64,
+    trade_imbalance_max: f64,
+    min_calm: Duration,
+    /// When both signals last became calm (None = not calm)
+    calm_since: Option<Instant>,
+}
+
+impl ResumeGate {
+    pub fn new(ofi_max: f64, trade_imbalance_max: f64, min_calm: Duration) -> Self {
+        Self { ofi_max, trade_imbalance_max, min_calm, calm_since: None }
+    }
+
+    /// Feed the current signals while paused; true once both have been calm
+    /// for `min_calm`. Any relapse restarts the clock.
+    pub fn ready(&mut self, ofi: f64, trade_imbalance: f64, now: Instant) -> bool {
+        if ofi.abs() >= self.ofi_max || trade_imbalance.abs() > self.trade_imbalance_max {
+            self.calm_since = None;
+            return false;
+        }
+        let since = *self.calm_since.get_or_insert(now);
+        now.duration_since(since) >= self.min_calm
+    }
+
+    /// How long both signals have been calm
+    pub fn calm_for(&self, now: Instant) -> Duration {
+        self.calm_since.map_or(Duration::ZERO, |t| now.duration_since(t))
+    }
+
+    pub fn reset(&mut self) {
+        self.calm_since = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_combined_gate_needs_both_calm_for_min_duration() {
+        let t0 = Instant::now();
+        let s = Duration::from_secs;
+        let mut g = ResumeGate::new(0.35, 0.3, s(5));
+        // Book calm, sweep still running
+        assert!(!g.ready(0.1, 0.8, t0));
+        assert_eq!(g.calm_for(t0), Duration::ZERO);
+        // Both calm - clock starts
+        assert!(!g.ready(0.1, 0.2, t0 + s(1)));
+        assert!(!g.ready(-0.2, -0.1, t0 + s(4)));
+        // Flow relapses - clock restarts
+        assert!(!g.ready(0.1, -0.5, t0 + s(5)));
+        assert!(!g.ready(0.1, 0.0, t0 + s(6)));
+        assert_eq!(g.calm_for(t0 + s(9)), s(3));
+        assert!(g.ready(0.1, 0.0, t0 + s(11)));
+        // OFI alone above threshold still blocks
+        assert!(!g.ready(0.4, 0.0, t0 + s(12)));
+
+        // Defaults (flow check off, no min duration) = the old OFI-only resume
+        let mut g = ResumeGate::new(0.35, 1.0, Duration::ZERO);
+        assert!(g.ready(0.3, 1.0, t0));
+        assert!(!g.ready(0.35, 0.0, t0));
+    }
+}
@@ -0,0 +1,83 @@
+//! Own-Side Depth
+//!
+//! When our resting orders are most of the size near the touch, a fill on
+//! that side is likely informed flow taking the only liquidity left; with a
+//! deep natural book around us it is mostly noise. Per side, the ratio of
+//! our resting size to the natural depth (the KuCoin book less our orders)
+//! within `own_depth_window_bps` of the touch moves that side's distances:
+//! wider above `own_depth_neutral`, tighter below, bounded both ways.
+
+use crate::config::QuotingConfig;
+use crate::exchange::order_book::{OrderBook, PriceLevel};
+
+/// Our size over the natural size near the touch; None for an empty side.
+/// Only ours near the touch (or no book left beside it) is infinite.
+pub fn own_ratio(levels: &[PriceLevel], own: &[(f64, f64)], is_bid: bool, window_bps: f64) -> Option<f64> {
+    let touch = levels.first()?.price;
+    let edge = if is_bid { touch * (1.0 - window_bps / 10000.0) } else { touch * (1.0 + window_bps / 10000.0) };
+    let near = |price: f64| if is_bid { price >= edge } else { price <= edge };
+    let book: f64 = levels.iter().take_while(|l| near(l.price)).map(|l| l.size).sum();
+    let ours: f64 = own.iter().filter(|(price, _)| near(*price)).map(|(_, size)| size).sum();
+    // Our orders are part of the book; clamp in case it lags them
+    let natural = (book - ours).max(0.0);
+    Some(match (ours > 0.0, natural > 0.0) {
+        (_, true) => ours / natural,
+        (true, false) => f64::INFINITY,
+        (false, false) => 0.0,
+    })
+}
+
+/// Distance change in bps (+ = wider) for a ratio: k * (ratio - neutral), bounded
+pub fn adjust_bps(ratio: f64, qc: &QuotingConfig) -> f64 {
+    if qc.own_depth_k <= 0.0 {
+        return 0.0;
+    }
+    (qc.own_depth_k * (ratio - qc.own_depth_neutral)).clamp(-qc.own_depth_max_tighten_bps, qc.own_depth_max_widen_bps)
+}
+
+/// (bid, ask) distance changes from the book and our live (price, size) orders
+pub fn side_adjustments(book: &OrderBook, own_bids: &[(f64, f64)], own_asks: &[(f64, f64)], qc: &QuotingConfig) -> (f64, f64) {
+    let side = |levels: &[PriceLevel], own: &[(f64, f64)], is_bid: bool| {
+        own_ratio(levels, own, is_bid, qc.own_depth_window_bps).map_or(0.0, |r| adjust_bps(r, qc))
+    };
+    (side(book.bids(), own_bids, true), side(book.asks(), own_asks, false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thin_own_side_widens_deep_book_tightens() {
+        let qc = QuotingConfig {
+            own_depth_k: 2.0, own_depth_neutral: 1.0, own_depth_window_bps: 5.0,
+            own_depth_max_widen_bps: 3.0, own_depth_max_tighten_bps: 1.0,
+            ..QuotingConfig::default()
+        };
+        let mut book = OrderBook::new("SOL-USDT".into());
+        // Bids: our 1.0 at the touch is all there is near it (99.90 is past 5bps).
+        // Asks: our 1.0 beside 9.0 of natural depth inside the window.
+        book.update_snapshot(
+            vec![(100.00, 1.0), (99.90, 50.0)],
+            vec![(100.01, 4.0), (100.02, 1.0), (100.05, 5.0)], 1);
+        let own_bids = [(100.00, 1.0)];
+        let own_asks = [(100.02, 1.0)];
+
+        assert_eq!(own_ratio(book.bids(), &own_bids, true, 5.0), Some(f64::INFINITY));
+        let r = own_ratio(book.asks(), &own_asks, false, 5.0).unwrap();
+        assert!((r - 1.0 / 9.0).abs() < 1e-9);
+        // Only ours near the bid touch: capped widen; deep asks: tighten, capped
+        assert_eq!(side_adjustments(&book, &own_bids, &own_asks, &qc), (3.0, -1.0));
+
+        // Half the natural depth: k * (0.5 - 1) = -1 (just within the cap)
+        book.update_snapshot(vec![(100.00, 3.0)], vec![(100.01, 1.0)], 2);
+        let adj = side_adjustments(&book, &own_bids, &[], &qc);
+        assert!((adj.0 + 1.0).abs() < 1e-9);
+        // A 1:1 side is neutral; no orders on a side is read as all natural
+        assert_eq!(adjust_bps(1.0, &qc), 0.0);
+        assert_eq!(adj.1, -1.0);
+        // Off by default
+        assert_eq!(adjust_bps(f64::INFINITY, &QuotingConfig::default()), 0.0);
+        assert_eq!(own_ratio(&[], &own_bids, true, 5.0), None);
+    }
+}
@@ -0,0 +1,71 @@
+//! Post-Fill Skew Boost
+//!
+//! A fill pushes the quotes toward unwinding it - after a buy the whole
+//! ladder shifts down, after a sell up - and the push halves every
+//! `half_life`. Layered on top of the inventory skew, so a fresh position
+//! is leaned on harder than an old one of the same size.
+
+use std::time::{Duration, Instant};
+
+#[derive(Debug)]
+pub struct PostFillSkew {
+    strength_bps: f64,
+    half_life: Duration,
+    /// Boost at `at`, in skew bps (+ = lean toward selling)
+    value: f64,
+    at: Option<Instant>,
+}
+
+impl PostFillSkew {
+    pub fn new(strength_bps: f64, half_life: Duration) -> Self {
+        Self { strength_bps, half_life, value: 0.0, at: None }
+    }
+
+    /// Each fill adds a full-strength push; the total is capped at ±strength
+    pub fn on_fill(&mut self, is_buy: bool, now: Instant) {
+        if self.strength_bps <= 0.0 {
+            return;
+        }
+        let push = if is_buy { self.strength_bps } else { -self.strength_bps };
+        self.value = (self.boost_bps(now) + push).clamp(-self.strength_bps, self.strength_bps);
+        self.at = Some(now);
+    }
+
+    /// Current boost in skew bps, decayed since the last fill
+    pub fn boost_bps(&self, now: Instant) -> f64 {
+        let Some(at) = self.at else { return 0.0 };
+        if self.half_life.is_zero() {
+            return 0.0;
+        }
+        let halves = now.duration_since(at).as_secs_f64() / self.half_life.as_secs_f64();
+        self.value * 0.5f64.powf(halves)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_boost_decays_by_half_life() {
+        let t0 = Instant::now();
+        let s = Duration::from_secs;
+        let mut b = PostFillSkew::new(4.0, s(10));
+        assert_eq!(b.boost_bps(t0), 0.0);
+        b.on_fill(true, t0);
+        assert_eq!(b.boost_bps(t0), 4.0);
+        assert!((b.boost_bps(t0 + s(10)) - 2.0).abs() < 1e-9);
+        assert!((b.boost_bps(t0 + s(20)) - 1.0).abs() < 1e-9);
+        // A sell half a life later nets against what's left
+        b.on_fill(false, t0 + s(10));
+        assert!((b.boost_bps(t0 + s(10)) + 2.0).abs() < 1e-9);
+        // Repeated fills cap at full strength
+        b.on_fill(false, t0 + s(10));
+        b.on_fill(false, t0 + s(10));
+        assert_eq!(b.boost_bps(t0 + s(10)), -4.0);
+
+        let mut off = PostFillSkew::new(0.0, s(10));
+        off.on_fill(true, t0);
+        assert_eq!(off.boost_bps(t0), 0.0);
+    }
+}
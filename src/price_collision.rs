@@ -0,0 +1,107 @@
+//! Ladder Price Collisions
+//!
+//! Skew and widening can round neighbouring levels onto the same tick, or
+//! even invert them, stacking several orders at one price. That wastes
+//! order slots and hides the ladder's real depth. Each side is walked inner
+//! to outer; a level that isn't strictly further from mid than the last one
+//! kept is either nudged one tick past it or merged into it.
+
+use crate::config::PriceCollision;
+
+/// One side of a level after collision handling
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SideQuote {
+    pub price: f64,
+    /// Refresh target, moved along with `price`
+    pub refresh: f64,
+    /// Levels' worth of size this quote carries; 0 = merged into an inner level
+    pub lots: u32,
+}
+
+impl SideQuote {
+    pub fn new(price: f64, refresh: f64) -> Self {
+        Self { price, refresh, lots: 1 }
+    }
+
+    pub fn merged(&self) -> bool {
+        self.lots == 0
+    }
+}
+
+/// Collisions found on one side
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Resolved {
+    pub nudged: u32,
+    pub merged: u32,
+}
+
+/// Resolve collisions in place; `quotes` are in level order, innermost first
+pub fn resolve(quotes: &mut [SideQuote], is_bid: bool, tick: f64, mode: PriceCollision) -> Resolved {
+    let mut out = Resolved::default();
+    if mode == PriceCollision::Stack || tick <= 0.0 {
+        return out;
+    }
+    let away = if is_bid { -1.0 } else { 1.0 };
+    let mut kept: Option<usize> = None;
+    for i in 0..quotes.len() {
+        if let Some(k) = kept {
+            let last = quotes[k].price;
+            if (quotes[i].price - last) * away < tick / 2.0 {
+                match mode {
+                    PriceCollision::Nudge => {
+                        let target = ((last + away * tick) / tick).round() * tick;
+                        quotes[i].refresh += target - quotes[i].price;
+                        quotes[i].price = target;
+                        out.nudged += 1;
+                    }
+                    PriceCollision::Merge => {
+                        quotes[k].lots += quotes[i].lots;
+                        quotes[i].lots = 0;
+                        out.merged += 1;
+                        continue;
+                    }
+                    PriceCollision::Stack => unreachable!(),
+                }
+            }
+        }
+        kept = Some(i);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn side(prices: &[f64]) -> Vec<SideQuote> {
+        prices.iter().map(|&p| SideQuote::new(p, p + 0.005)).collect()
+    }
+
+    fn prices(q: &[SideQuote]) -> Vec<f64> {
+        q.iter().map(|q| (q.price * 100.0).round() / 100.0).collect()
+    }
+
+    #[test]
+    fn test_colliding_levels_nudged_or_merged() {
+        // L2 and L3 round onto L1's tick, L4 clear of them
+        let bids = [187.50, 187.50, 187.50, 187.45];
+        let mut q = side(&bids);
+        assert_eq!(resolve(&mut q, true, 0.01, PriceCollision::Stack), Resolved::default());
+        assert_eq!(prices(&q), bids);
+
+        let mut q = side(&bids);
+        assert_eq!(resolve(&mut q, true, 0.01, PriceCollision::Nudge), Resolved { nudged: 2, merged: 0 });
+        assert_eq!(prices(&q), [187.50, 187.49, 187.48, 187.45]);
+        assert!((q[2].refresh - 187.485).abs() < 1e-9);
+
+        let mut q = side(&bids);
+        assert_eq!(resolve(&mut q, true, 0.01, PriceCollision::Merge), Resolved { nudged: 0, merged: 2 });
+        assert_eq!(q.iter().map(|q| q.lots).collect::<Vec<_>>(), [3, 0, 0, 1]);
+        assert!(q[1].merged() && !q[3].merged());
+
+        // Asks walk up; an inverted level counts as a collision too
+        let mut q = side(&[187.52, 187.51, 187.53]);
+        assert_eq!(resolve(&mut q, false, 0.01, PriceCollision::Nudge).nudged, 2);
+        assert_eq!(prices(&q), [187.52, 187.53, 187.54]);
+    }
+}
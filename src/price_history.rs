@@ -0,0 +1,96 @@
+//! Bounded Price History
+//!
+//! Momentum only needs the mid at the start of its window, so the history
+//! is downsampled to at most one sample per interval and hard-capped in
+//! length. Memory and per-update trim cost no longer scale with feed rate.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+pub struct PriceHistory {
+    samples: VecDeque<(Instant, f64)>,
+    window: Duration,
+    interval: Duration,
+    max_len: usize,
+}
+
+impl PriceHistory {
+    pub fn new(window: Duration, interval: Duration, max_len: usize) -> Self {
+        Self { samples: VecDeque::with_capacity(max_len.min(4096)), window, interval, max_len: max_len.max(1) }
+    }
+
+    pub fn push(&mut self, now: Instant, px: f64) {
+        // Keep the first sample of each interval - it is the one the window start reads
+        let due = match self.samples.back() {
+            Some((t, _)) => now.duration_since(*t) >= self.interval,
+            None => true,
+        };
+        if due {
+            self.samples.push_back((now, px));
+        }
+        if let Some(cutoff) = now.checked_sub(self.window) {
+            while matches!(self.samples.front(), Some((t, _)) if *t < cutoff) {
+                self.samples.pop_front();
+            }
+        }
+        while self.samples.len() > self.max_len {
+            self.samples.pop_front();
+        }
+    }
+
+    /// Oldest retained price (≈ window start)
+    pub fn oldest(&self) -> Option<f64> {
+        self.samples.front().map(|(_, p)| *p)
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.samples.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_downsamples_and_trims_by_time() {
+        let t0 = Instant::now();
+        let mut h = PriceHistory::new(Duration::from_secs(10), Duration::from_millis(500), 1000);
+        // 100 updates/sec for 20s
+        for i in 0..2000u64 {
+            h.push(t0 + Duration::from_millis(i * 10), 100.0 + i as f64);
+        }
+        // ~10s / 500ms samples retained
+        assert!(h.len() <= 21, "len {}", h.len());
+        // Window start is ~10s back: update index ~1000
+        let oldest = h.oldest().unwrap() - 100.0;
+        assert!((990.0..=1050.0).contains(&oldest), "oldest {}", oldest);
+    }
+
+    #[test]
+    fn test_max_len_caps_regardless_of_window() {
+        let t0 = Instant::now();
+        let mut h = PriceHistory::new(Duration::from_secs(3600), Duration::ZERO, 64);
+        for i in 0..10_000u64 {
+            h.push(t0 + Duration::from_millis(i), i as f64);
+        }
+        assert_eq!(h.len(), 64);
+        assert_eq!(h.oldest(), Some(9936.0));
+    }
+
+    /// `cargo test --release bench_push -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn bench_push_cost() {
+        let t0 = Instant::now();
+        let mut h = PriceHistory::new(Duration::from_secs(180), Duration::from_millis(500), 1024);
+        let n = 1_000_000u64;
+        let start = Instant::now();
+        for i in 0..n {
+            h.push(t0 + Duration::from_millis(i), i as f64);
+        }
+        let ns = start.elapsed().as_nanos() as f64 / n as f64;
+        println!("PriceHistory::push: {:.1} ns/update, len {}", ns, h.len());
+    }
+}
@@ -0,0 +1,189 @@
+//! Quote Gate - one answer to "why isn't it quoting?"
+//!
+//! Each tick the strategy records every active pause/skip condition here.
+//! The gate decides which sides are blocked, logs whenever the set of
+//! reasons changes, and exposes the current set for `/status`.
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use tracing::info;
+
+/// Why a side (or both) is not being quoted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GateReason {
+    /// No reference mid yet
+    NoMid,
+    /// OFI pause with hysteresis
+    OfiPaused,
+    /// Momentum below -`--momentum-threshold`
+    Downtrend,
+    /// Strong OFI: adverse resting orders are being cancelled. Reported
+    /// only - new placements on that side still go out
+    TrendProtect,
+    /// Next order would breach `--max-inv-sol`
+    InventoryLimit,
+    /// Inside a configured UTC pause window
+    Schedule,
+    /// Symbol switched off in the control file
+    SymbolDisabled,
+    /// Exchange isn't trading the symbol (delisted / suspended)
+    NotTrading,
+    /// Re-polling orders, balances and fills after an order WS reconnect
+    Resync,
+}
+
+impl GateReason {
+    /// Whether the reason stops new placements on its side
+    pub fn blocks_placement(&self) -> bool {
+        !matches!(self, GateReason::TrendProtect)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GateReason::NoMid => "no_mid",
+            GateReason::OfiPaused => "ofi_paused",
+            GateReason::Downtrend => "downtrend",
+            GateReason::TrendProtect => "trend_protect",
+            GateReason::InventoryLimit => "inventory_limit",
+            GateReason::Schedule => "schedule",
+            GateReason::SymbolDisabled => "symbol_disabled",
+            GateReason::NotTrading => "not_trading",
+            GateReason::Resync => "resync",
+        }
+    }
+}
+
+/// Which side a gate blocks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GateScope {
+    Bids,
+    Asks,
+    Both,
+}
+
+impl GateScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GateScope::Bids => "bids",
+            GateScope::Asks => "asks",
+            GateScope::Both => "both",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GateEntry {
+    pub reason: GateReason,
+    pub scope: GateScope,
+    pub detail: String,
+}
+
+/// Aggregated pause/skip reasons for the current tick
+#[derive(Debug, Default)]
+pub struct QuoteGate {
+    active: BTreeMap<GateReason, GateEntry>,
+    /// Reason set last logged - detail strings are ignored for change detection
+    published: BTreeMap<GateReason, GateScope>,
+}
+
+impl QuoteGate {
+    /// Start a new tick - every condition must be re-asserted
+    pub fn clear(&mut self) {
+        self.active.clear();
+    }
+
+    pub fn set(&mut self, reason: GateReason, scope: GateScope, detail: impl Into<String>) {
+        self.active.insert(reason, GateEntry { reason, scope, detail: detail.into() });
+    }
+
+    pub fn blocks_bids(&self) -> bool {
+        self.active.values().any(|e| e.reason.blocks_placement() && matches!(e.scope, GateScope::Bids | GateScope::Both))
+    }
+
+    pub fn blocks_asks(&self) -> bool {
+        self.active.values().any(|e| e.reason.blocks_placement() && matches!(e.scope, GateScope::Asks | GateScope::Both))
+    }
+
+    /// Log if the reason set changed since the last call. Returns true on change.
+    pub fn publish(&mut self) -> bool {
+        let current: BTreeMap<GateReason, GateScope> =
+            self.active.iter().map(|(r, e)| (*r, e.scope)).collect();
+        if current == self.published {
+            return false;
+        }
+        if current.is_empty() {
+            info!("[GATE] Open - quoting both sides");
+        } else {
+            info!("[GATE] {}", self.describe());
+        }
+        self.published = current;
+        true
+    }
+
+    /// "ofi_paused(bids): OFI -0.742; downtrend(bids): Mom -0.41%"
+    pub fn describe(&self) -> String {
+        self.active.values()
+            .map(|e| format!("{}({}): {}", e.reason.as_str(), e.scope.as_str(), e.detail))
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "quoting_bids": !self.blocks_bids(),
+            "quoting_asks": !self.blocks_asks(),
+            "reasons": self.active.values().collect::<Vec<_>>(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scopes_block_sides() {
+        let mut gate = QuoteGate::default();
+        assert!(!gate.blocks_bids() && !gate.blocks_asks());
+
+        gate.set(GateReason::Downtrend, GateScope::Bids, "Mom -0.40%");
+        assert!(gate.blocks_bids());
+        assert!(!gate.blocks_asks());
+
+        gate.set(GateReason::NoMid, GateScope::Both, "");
+        assert!(gate.blocks_asks());
+
+        // Trend protect cancels adverse orders but doesn't stop placing
+        gate.clear();
+        gate.set(GateReason::TrendProtect, GateScope::Bids, "OFI -0.91");
+        assert!(!gate.blocks_bids() && gate.describe().starts_with("trend_protect(bids)"));
+
+        gate.clear();
+        assert!(!gate.blocks_bids() && !gate.blocks_asks());
+    }
+
+    #[test]
+    fn test_publish_only_on_reason_change() {
+        let mut gate = QuoteGate::default();
+        assert!(!gate.publish()); // empty -> empty
+
+        gate.set(GateReason::OfiPaused, GateScope::Bids, "OFI -0.71");
+        assert!(gate.publish());
+
+        // Same reason with a new detail is not a change
+        gate.clear();
+        gate.set(GateReason::OfiPaused, GateScope::Bids, "OFI -0.65");
+        assert!(!gate.publish());
+
+        // Scope flip is a change
+        gate.clear();
+        gate.set(GateReason::OfiPaused, GateScope::Asks, "OFI 0.72");
+        assert!(gate.publish());
+
+        gate.clear();
+        assert!(gate.publish());
+    }
+}
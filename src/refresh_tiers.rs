@@ -0,0 +1,98 @@
+//! Two-Speed Level Refresh
+//!
+//! Inner layers want to re-quote on every tick; outer layers drifting for a
+//! few seconds cost nothing and re-quoting them burns rate limit. Levels are
+//! split into tiers by ladder index, each on its own interval: a level is
+//! visited on the fast tick (`cadence.tick_ms`) only when its tier is due.
+//! Levels before the first tier run every tick, and a trend-protect cancel
+//! still reaches every level on the tick it fires.
+
+use std::time::{Duration, Instant};
+
+use crate::config::LevelTier;
+
+#[derive(Debug)]
+struct Tier {
+    from_index: usize,
+    interval: Duration,
+    last_run: Option<Instant>,
+    due: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct RefreshTiers {
+    /// Sorted by `from_index`
+    tiers: Vec<Tier>,
+}
+
+impl RefreshTiers {
+    pub fn new(config: &[LevelTier]) -> Self {
+        let mut tiers: Vec<Tier> = config.iter().map(|t| Tier {
+            from_index: t.from_index,
+            interval: Duration::from_millis(t.interval_ms),
+            last_run: None,
+            due: true,
+        }).collect();
+        tiers.sort_by_key(|t| t.from_index);
+        Self { tiers }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tiers.is_empty()
+    }
+
+    /// Work out which tiers this tick visits, and restart their timers
+    pub fn begin_tick(&mut self, now: Instant) {
+        for t in &mut self.tiers {
+            t.due = t.last_run.is_none_or(|at| now.duration_since(at) >= t.interval);
+            if t.due {
+                t.last_run = Some(now);
+            }
+        }
+    }
+
+    /// Whether the level at `index` is visited this tick
+    pub fn is_due(&self, index: usize) -> bool {
+        self.tiers.iter().rev().find(|t| index >= t.from_index).is_none_or(|t| t.due)
+    }
+
+    pub fn describe(&self) -> String {
+        self.tiers.iter()
+            .map(|t| format!("L{}+ every {}ms", t.from_index + 1, t.interval.as_millis()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_outer_tier_skipped_on_fast_tick() {
+        let ms = Duration::from_millis;
+        let mut tiers = RefreshTiers::new(&[
+            LevelTier { from_index: 10, interval_ms: 2000 },
+            LevelTier { from_index: 4, interval_ms: 500 },
+        ]);
+        let t0 = Instant::now();
+        // Everything runs on the first tick
+        tiers.begin_tick(t0);
+        assert!(tiers.is_due(0) && tiers.is_due(4) && tiers.is_due(12));
+
+        // Fast tick: inner levels only
+        tiers.begin_tick(t0 + ms(100));
+        assert!(tiers.is_due(0) && tiers.is_due(3));
+        assert!(!tiers.is_due(4) && !tiers.is_due(10) && !tiers.is_due(19));
+
+        tiers.begin_tick(t0 + ms(600));
+        assert!(tiers.is_due(4) && tiers.is_due(9) && !tiers.is_due(10));
+        tiers.begin_tick(t0 + ms(2000));
+        assert!(tiers.is_due(10) && tiers.is_due(19));
+        assert_eq!(tiers.describe(), "L5+ every 500ms, L11+ every 2000ms");
+
+        // No tiers: every level every tick
+        let none = RefreshTiers::new(&[]);
+        assert!(none.is_empty() && none.is_due(19));
+    }
+}
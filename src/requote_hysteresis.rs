@@ -0,0 +1,77 @@
+//! Requote Hysteresis
+//!
+//! A live order is refreshed once its refresh target drifts past the
+//! level's trigger. With the mid oscillating around that edge the new order
+//! drifts straight back out and the level flips cancel/place every swing.
+//! After a refresh the level side is held until its new order has sat
+//! within `refresh_inner_frac` of the trigger at least once; drift past
+//! twice the trigger still refreshes, so a real move isn't left behind.
+
+use std::collections::HashSet;
+
+#[derive(Debug, Default)]
+pub struct RequoteHysteresis {
+    /// Inner band as a fraction of the trigger; 0 = off
+    inner_frac: f64,
+    /// (level key, is_bid) refreshed and not yet settled
+    unsettled: HashSet<(i32, bool)>,
+}
+
+impl RequoteHysteresis {
+    pub fn new(inner_frac: f64) -> Self {
+        Self { inner_frac: inner_frac.max(0.0), unsettled: HashSet::new() }
+    }
+
+    /// Whether a live order `drift_bps` off its target may be refreshed at
+    /// `trigger_bps`; call every visit so settling is seen
+    pub fn allows(&mut self, key: i32, is_bid: bool, drift_bps: f64, trigger_bps: f64) -> bool {
+        if drift_bps <= trigger_bps * self.inner_frac {
+            self.unsettled.remove(&(key, is_bid));
+        }
+        !self.unsettled.contains(&(key, is_bid)) || drift_bps > trigger_bps * 2.0
+    }
+
+    /// The order sits on its desired tick
+    pub fn settle(&mut self, key: i32, is_bid: bool) {
+        self.unsettled.remove(&(key, is_bid));
+    }
+
+    pub fn on_refresh(&mut self, key: i32, is_bid: bool) {
+        if self.inner_frac > 0.0 {
+            self.unsettled.insert((key, is_bid));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_oscillating_mid_refreshes_once_per_settle() {
+        // Trigger 2bps, inner band 0.25 * 2 = 0.5bps. The mid swings 2.5bps
+        // either side: each new order ends up 2.5bps off on the next swing.
+        let drive = |h: &mut RequoteHysteresis, drifts: &[f64]| {
+            drifts.iter().filter(|&&d| {
+                let go = h.allows(10, true, d, 2.0) && d > 2.0;
+                if go {
+                    h.on_refresh(10, true);
+                }
+                go
+            }).count()
+        };
+        let swings = [2.5; 6];
+        // Off: every swing is a cancel/place
+        assert_eq!(drive(&mut RequoteHysteresis::new(0.0), &swings), 6);
+        let mut h = RequoteHysteresis::new(0.25);
+        assert_eq!(drive(&mut h, &swings), 1);
+        // Settles (0.3bps off) - the next swing refreshes again, once
+        assert_eq!(drive(&mut h, &[1.0, 0.3, 2.5, 2.5]), 1);
+        // A real move past twice the trigger goes regardless
+        assert_eq!(drive(&mut h, &[4.5]), 1);
+        // Other sides and levels are independent
+        assert!(h.allows(10, false, 2.5, 2.0) && h.allows(20, true, 2.5, 2.0));
+        h.settle(10, true);
+        assert!(h.allows(10, true, 2.5, 2.0));
+    }
+}
@@ -0,0 +1,90 @@
+//! Post-Reconnect Resync
+//!
+//! Fills can be missed while the order WS is down, so after a reconnect the
+//! inventory and balance view is suspect. Placement stays gated until a full
+//! reconciliation (active orders + balances) and a fill poll have both
+//! completed after the reconnect; cancels and recon carry on as usual.
+
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy)]
+struct Pending {
+    since: Instant,
+    /// Inventory when the reconnect was seen
+    pre_inv: f64,
+    recon: bool,
+    fills: bool,
+}
+
+/// Resync finished: inventory before it and how long it took
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Resynced {
+    pub pre_inv: f64,
+    pub took: Duration,
+}
+
+#[derive(Debug, Default)]
+pub struct Resync {
+    pending: Option<Pending>,
+}
+
+impl Resync {
+    /// Start (or restart) the gate; a reconnect mid-resync keeps the first
+    /// pre-outage inventory
+    pub fn on_reconnect(&mut self, inv: f64, now: Instant) {
+        let (since, pre_inv) = self.pending.map_or((now, inv), |p| (p.since, p.pre_inv));
+        self.pending = Some(Pending { since, pre_inv, recon: false, fills: false });
+    }
+
+    /// A recon cycle polled orders and balances successfully
+    pub fn on_recon(&mut self) {
+        if let Some(p) = self.pending.as_mut() {
+            p.recon = true;
+        }
+    }
+
+    /// A fill poll succeeded
+    pub fn on_fills(&mut self) {
+        if let Some(p) = self.pending.as_mut() {
+            p.fills = true;
+        }
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// Clears the gate once both have run, returning what it covered
+    pub fn take_done(&mut self, now: Instant) -> Option<Resynced> {
+        let p = self.pending.filter(|p| p.recon && p.fills)?;
+        self.pending = None;
+        Some(Resynced { pre_inv: p.pre_inv, took: now.duration_since(p.since) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gate_needs_recon_and_fills_after_reconnect() {
+        let t0 = Instant::now();
+        let s = Duration::from_secs;
+        let mut r = Resync::default();
+        // Nothing pending - recon and fills are no-ops
+        r.on_recon();
+        r.on_fills();
+        assert!(!r.is_pending() && r.take_done(t0).is_none());
+
+        r.on_reconnect(1.5, t0);
+        r.on_recon();
+        assert!(r.is_pending() && r.take_done(t0 + s(1)).is_none());
+        // Drops again before the fill poll: the recon has to be redone
+        r.on_reconnect(2.0, t0 + s(2));
+        r.on_fills();
+        assert!(r.take_done(t0 + s(3)).is_none());
+        r.on_recon();
+        assert_eq!(r.take_done(t0 + s(4)), Some(Resynced { pre_inv: 1.5, took: s(4) }));
+        assert!(!r.is_pending());
+    }
+}
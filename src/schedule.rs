@@ -0,0 +1,105 @@
+//! Quoting Schedule
+//!
+//! UTC time-of-day windows (exchange maintenance, thin hours) during which
+//! the bot stands down: everything is cancelled and nothing is quoted until
+//! the window ends. Purely time-based - independent of the risk pauses.
+
+use anyhow::{anyhow, Context, Result};
+use std::fmt;
+
+const MINUTES_PER_DAY: u32 = 24 * 60;
+
+/// `HH:MM-HH:MM` in UTC, end exclusive; wraps past midnight if end <= start
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Window {
+    start: u32,
+    end: u32,
+}
+
+impl Window {
+    pub fn parse(spec: &str) -> Result<Self> {
+        let (a, b) = spec.split_once('-').ok_or_else(|| anyhow!("expected HH:MM-HH:MM, got {:?}", spec))?;
+        let start = parse_hhmm(a.trim()).with_context(|| format!("bad window {:?}", spec))?;
+        let end = parse_hhmm(b.trim()).with_context(|| format!("bad window {:?}", spec))?;
+        if start == end {
+            return Err(anyhow!("empty window {:?}", spec));
+        }
+        Ok(Self { start, end })
+    }
+
+    fn contains(&self, minute: u32) -> bool {
+        if self.start < self.end {
+            minute >= self.start && minute < self.end
+        } else {
+            minute >= self.start || minute < self.end
+        }
+    }
+}
+
+impl fmt::Display for Window {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}:{:02}-{:02}:{:02}", self.start / 60, self.start % 60, self.end / 60, self.end % 60)
+    }
+}
+
+fn parse_hhmm(s: &str) -> Result<u32> {
+    let (h, m) = s.split_once(':').ok_or_else(|| anyhow!("expected HH:MM, got {:?}", s))?;
+    let (h, m): (u32, u32) = (h.parse()?, m.parse()?);
+    if h > 23 || m > 59 {
+        return Err(anyhow!("time out of range {:?}", s));
+    }
+    Ok(h * 60 + m)
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Schedule {
+    windows: Vec<Window>,
+}
+
+impl Schedule {
+    pub fn parse(specs: &[String]) -> Result<Self> {
+        Ok(Self { windows: specs.iter().map(|s| Window::parse(s)).collect::<Result<_>>()? })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.windows.is_empty()
+    }
+
+    /// Window covering `unix_secs`, if any
+    pub fn active(&self, unix_secs: u64) -> Option<Window> {
+        let minute = ((unix_secs / 60) % MINUTES_PER_DAY as u64) as u32;
+        self.windows.iter().copied().find(|w| w.contains(minute))
+    }
+
+    pub fn describe(&self) -> String {
+        self.windows.iter().map(|w| w.to_string()).collect::<Vec<_>>().join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(h: u64, m: u64) -> u64 {
+        // 2024-01-01 00:00 UTC plus time of day
+        1_704_067_200 + h * 3600 + m * 60
+    }
+
+    #[test]
+    fn test_windows_including_midnight_wrap() {
+        let s = Schedule::parse(&["02:00-02:30".to_string(), "23:50-00:10".to_string()]).unwrap();
+        assert_eq!(s.active(at(1, 59)), None);
+        assert_eq!(s.active(at(2, 0)).unwrap().to_string(), "02:00-02:30");
+        assert_eq!(s.active(at(2, 30)), None);
+        assert!(s.active(at(23, 55)).is_some());
+        assert!(s.active(at(0, 5)).is_some());
+        assert_eq!(s.active(at(0, 10)), None);
+    }
+
+    #[test]
+    fn test_rejects_bad_specs() {
+        for bad in ["02:00", "24:00-01:00", "02:00-02:00", "2h-3h"] {
+            assert!(Window::parse(bad).is_err(), "{}", bad);
+        }
+    }
+}
@@ -0,0 +1,108 @@
+//! Scratch Exit on Toxic Fills
+//!
+//! Each fill is marked out against the mid `horizon` after it was seen. If
+//! the mid has moved against it by `toxic_bps` or more, the fill is
+//! toxic: rather than wait for two-sided flow to unwind it, a reducing
+//! post-only order goes out at the fill price conceded by a few ticks, so
+//! the position is scratched for about the spread instead of riding the move.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// clientOid prefix of scratch orders; recon leaves these alone (they expire GTT)
+pub const SCRATCH_OID_PREFIX: &str = "scr_";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ToxicFill {
+    pub is_buy: bool,
+    pub price: f64,
+    pub size: f64,
+    /// Markout at the horizon, bps (negative = against us)
+    pub markout_bps: f64,
+}
+
+/// Mid move since the fill in our favour, bps: a buy gains as the mid rises
+pub fn markout_bps(is_buy: bool, fill_px: f64, mid: f64) -> f64 {
+    let mv = (mid - fill_px) / fill_px * 10000.0;
+    if is_buy { mv } else { -mv }
+}
+
+/// Exit price for a toxic fill: `exit_ticks` worse than the fill price, but
+/// never through the mid so it still rests post-only
+pub fn scratch_price(fill: &ToxicFill, exit_ticks: u32, tick: f64, mid: f64) -> f64 {
+    let concede = exit_ticks as f64 * tick;
+    let ticks = mid / tick;
+    if fill.is_buy {
+        // Selling back: at least the first tick above mid
+        (fill.price - concede).max(((ticks + 1e-9).floor() + 1.0) * tick)
+    } else {
+        (fill.price + concede).min(((ticks - 1e-9).ceil() - 1.0) * tick)
+    }
+}
+
+#[derive(Debug)]
+pub struct ScratchWatch {
+    horizon: Duration,
+    toxic_bps: f64,
+    /// (seen at, is_buy, price, size) awaiting markout
+    pending: VecDeque<(Instant, bool, f64, f64)>,
+}
+
+impl ScratchWatch {
+    pub fn new(horizon: Duration, toxic_bps: f64) -> Self {
+        Self { horizon, toxic_bps, pending: VecDeque::new() }
+    }
+
+    pub fn on_fill(&mut self, is_buy: bool, price: f64, size: f64, now: Instant) {
+        self.pending.push_back((now, is_buy, price, size));
+    }
+
+    /// Fills whose horizon has passed, returning the toxic ones; the rest are dropped
+    pub fn poll(&mut self, mid: f64, now: Instant) -> Vec<ToxicFill> {
+        let mut toxic = Vec::new();
+        while let Some(&(at, is_buy, price, size)) = self.pending.front() {
+            if now.duration_since(at) < self.horizon {
+                break;
+            }
+            self.pending.pop_front();
+            let markout_bps = markout_bps(is_buy, price, mid);
+            if -markout_bps >= self.toxic_bps {
+                toxic.push(ToxicFill { is_buy, price, size, markout_bps });
+            }
+        }
+        toxic
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toxic_trigger_and_exit_price() {
+        let t0 = Instant::now();
+        let s = Duration::from_secs;
+        let mut w = ScratchWatch::new(s(2), 5.0);
+        w.on_fill(true, 200.0, 0.3, t0);
+        w.on_fill(false, 200.0, 0.2, t0);
+        w.on_fill(true, 200.0, 0.1, t0 + s(1));
+        // Too early to mark out
+        assert!(w.poll(199.0, t0 + s(1)).is_empty());
+        // Mid down 10bps: the buys are toxic, the sell is in profit. Only the
+        // first buy has reached the horizon.
+        let toxic = w.poll(199.8, t0 + s(2));
+        assert_eq!(toxic.len(), 1);
+        assert!(toxic[0].is_buy && toxic[0].size == 0.3);
+        assert!((toxic[0].markout_bps + 10.0).abs() < 1e-9);
+        // Mid recovered to -2.5bps before the last buy's horizon - not toxic
+        assert!(w.poll(199.95, t0 + s(3)).is_empty());
+
+        // Sell back one tick under the buy, still above the mid
+        let fill = ToxicFill { is_buy: true, price: 200.0, size: 0.3, markout_bps: -10.0 };
+        assert!((scratch_price(&fill, 1, 0.01, 199.8) - 199.99).abs() < 1e-9);
+        // Mid above the exit price - clamp to the first tick above mid
+        assert!((scratch_price(&fill, 1, 0.01, 200.005) - 200.01).abs() < 1e-9);
+        let sold = ToxicFill { is_buy: false, price: 200.0, size: 0.2, markout_bps: -8.0 };
+        assert!((scratch_price(&sold, 2, 0.01, 200.2) - 200.02).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,125 @@
+//! Status HTTP Endpoint
+//!
+//! Minimal `GET /status` server returning a JSON snapshot that the main loop
+//! keeps up to date, plus the `POST /panic` emergency cancel trigger. Plain
+//! tokio TCP - no HTTP framework needed for two routes. `/panic` is
+//! unauthenticated: keep `status_addr` on loopback.
+
+use anyhow::Result;
+use serde_json::{Map, Value};
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{Notify, RwLock};
+use tracing::{info, warn};
+
+use crate::event_log::SharedEventLog;
+
+/// Status sections keyed by subsystem name (e.g. "quote_gate")
+pub type SharedStatus = Arc<RwLock<Map<String, Value>>>;
+
+pub fn new_shared_status() -> SharedStatus {
+    Arc::new(RwLock::new(Map::new()))
+}
+
+/// Replace one section of the status snapshot
+pub async fn publish(status: &SharedStatus, section: &str, value: Value) {
+    status.write().await.insert(section.to_string(), value);
+}
+
+/// Serve `/status` until the listener fails; `?events=N` adds the last N events
+pub async fn serve(addr: String, status: SharedStatus, events: SharedEventLog, panic: Arc<Notify>) -> Result<()> {
+    let listener = TcpListener::bind(&addr).await?;
+    info!("[STATUS] Listening on http://{}/status", addr);
+    loop {
+        let (sock, _) = listener.accept().await?;
+        let (status, events, panic) = (status.clone(), events.clone(), panic.clone());
+        tokio::spawn(async move {
+            if let Err(e) = handle(sock, status, events, panic).await {
+                warn!("[STATUS] Request error: {}", e);
+            }
+        });
+    }
+}
+
+async fn handle(mut sock: TcpStream, status: SharedStatus, events: SharedEventLog, panic: Arc<Notify>) -> Result<()> {
+    let mut buf = [0u8; 2048];
+    let n = sock.read(&mut buf).await?;
+    let req = String::from_utf8_lossy(&buf[..n]);
+
+    let (code, body) = match parse_request_line(&req) {
+        Some(("GET", "/status", query)) => {
+            let mut snapshot = status.read().await.clone();
+            if let Some(n) = query_param(query, "events").and_then(|v| v.parse::<usize>().ok()) {
+                snapshot.insert("events".to_string(), events.read().await.recent_json(n));
+            }
+            ("200 OK", Value::Object(snapshot).to_string())
+        }
+        Some(("POST", "/panic", _)) => {
+            warn!("[STATUS] POST /panic received");
+            panic.notify_one();
+            ("202 Accepted", r#"{"panic":"triggered"}"#.to_string())
+        }
+        Some(_) => ("404 Not Found", r#"{"error":"not found"}"#.to_string()),
+        None => ("400 Bad Request", r#"{"error":"bad request"}"#.to_string()),
+    };
+
+    let resp = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        code, body.len(), body
+    );
+    sock.write_all(resp.as_bytes()).await?;
+    Ok(())
+}
+
+/// Split "GET /status?x=1 HTTP/1.1" into (method, path, query)
+fn parse_request_line(req: &str) -> Option<(&str, &str, &str)> {
+    let mut parts = req.lines().next()?.split_whitespace();
+    let method = parts.next()?;
+    let target = parts.next()?;
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    Some((method, path, query))
+}
+
+fn query_param<'a>(query: &'a str, key: &str) -> Option<&'a str> {
+    query.split('&').find_map(|kv| kv.split_once('=').filter(|(k, _)| *k == key).map(|(_, v)| v))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_request_line() {
+        let req = "GET /status?events=5 HTTP/1.1\r\nHost: x\r\n\r\n";
+        assert_eq!(parse_request_line(req), Some(("GET", "/status", "events=5")));
+        assert_eq!(parse_request_line("GET /status HTTP/1.1"), Some(("GET", "/status", "")));
+        assert_eq!(parse_request_line(""), None);
+    }
+
+    #[tokio::test]
+    async fn test_post_panic_notifies() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let panic = Arc::new(Notify::new());
+        let p = panic.clone();
+        tokio::spawn(async move {
+            let (sock, _) = listener.accept().await.unwrap();
+            handle(sock, new_shared_status(), crate::event_log::new_shared_event_log(4), p).await.unwrap();
+        });
+        let mut client = TcpStream::connect(addr).await.unwrap();
+        client.write_all(b"POST /panic HTTP/1.1\r\n\r\n").await.unwrap();
+        let mut resp = String::new();
+        client.read_to_string(&mut resp).await.unwrap();
+        assert!(resp.starts_with("HTTP/1.1 202"), "{}", resp);
+        tokio::time::timeout(std::time::Duration::from_secs(1), panic.notified()).await.unwrap();
+    }
+
+    #[test]
+    fn test_query_param() {
+        assert_eq!(query_param("events=50", "events"), Some("50"));
+        assert_eq!(query_param("a=1&events=5", "events"), Some("5"));
+        assert_eq!(query_param("", "events"), None);
+        assert_eq!(query_param("eventsx=5", "events"), None);
+    }
+}
@@ -0,0 +1,107 @@
+//! Per-Symbol Enable Control
+//!
+//! An operator-edited TOML file (`disabled = ["SOL-USDT"]`) pulls symbols
+//! offline without a restart. The file is re-read only when its mtime
+//! changes; a missing or unreadable file leaves every symbol enabled.
+//!
+//! `disabled_levels = [0, 1, 2]` (ladder indices, 0 = innermost) switches
+//! individual layers off the same way; when present it replaces
+//! `levels.disabled_levels` from the config.
+
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::time::SystemTime;
+use tracing::warn;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ControlFile {
+    disabled: Vec<String>,
+    disabled_levels: Option<Vec<usize>>,
+}
+
+#[derive(Debug, Default)]
+pub struct SymbolControl {
+    /// Empty = control disabled
+    path: String,
+    modified: Option<SystemTime>,
+    disabled: HashSet<String>,
+    disabled_levels: Option<Vec<usize>>,
+}
+
+impl SymbolControl {
+    pub fn new(path: &str) -> Self {
+        Self { path: path.to_string(), ..Default::default() }
+    }
+
+    /// Re-read the file if it changed since the last call
+    pub fn refresh(&mut self) {
+        if self.path.is_empty() {
+            return;
+        }
+        let modified = std::fs::metadata(&self.path).and_then(|m| m.modified()).ok();
+        if modified == self.modified {
+            return;
+        }
+        self.modified = modified;
+        (self.disabled, self.disabled_levels) = match modified.map(|_| std::fs::read_to_string(&self.path)) {
+            Some(Ok(text)) => self.parse(&text),
+            _ => (HashSet::new(), None),
+        };
+    }
+
+    fn parse(&self, text: &str) -> (HashSet<String>, Option<Vec<usize>>) {
+        match toml::from_str::<ControlFile>(text) {
+            Ok(f) => (f.disabled.into_iter().collect(), f.disabled_levels),
+            Err(e) => {
+                // Keep the last good state rather than flipping on a half-written file
+                warn!("[CONTROL] Ignoring invalid {}: {}", self.path, e);
+                (self.disabled.clone(), self.disabled_levels.clone())
+            }
+        }
+    }
+
+    pub fn is_enabled(&self, symbol: &str) -> bool {
+        !self.disabled.contains(symbol)
+    }
+
+    /// Ladder indices switched off by the file; None = not set there
+    pub fn disabled_levels(&self) -> Option<&[usize]> {
+        self.disabled_levels.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disable_and_reenable_via_file() {
+        let path = std::env::temp_dir().join(format!("mm_control_{}.toml", std::process::id()));
+        let path_str = path.to_str().unwrap();
+        let mut ctl = SymbolControl::new(path_str);
+
+        ctl.refresh();
+        assert!(ctl.is_enabled("SOL-USDT"));
+
+        std::fs::write(&path, "disabled = [\"SOL-USDT\"]\n").unwrap();
+        ctl.refresh();
+        assert!(!ctl.is_enabled("SOL-USDT"));
+        assert!(ctl.is_enabled("BTC-USDT"));
+        assert_eq!(ctl.disabled_levels(), None);
+
+        std::fs::write(&path, "disabled_levels = [0, 1, 2]\n").unwrap();
+        // Only re-read on an mtime change
+        ctl.modified = None;
+        ctl.refresh();
+        assert!(ctl.is_enabled("SOL-USDT"));
+        assert_eq!(ctl.disabled_levels(), Some(&[0, 1, 2][..]));
+
+        // Garbage keeps the last good state
+        assert_eq!(ctl.parse("disabled = ["), (ctl.disabled.clone(), Some(vec![0, 1, 2])));
+
+        std::fs::remove_file(&path).unwrap();
+        ctl.refresh();
+        assert!(ctl.is_enabled("SOL-USDT"));
+    }
+}
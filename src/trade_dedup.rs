@@ -0,0 +1,99 @@
+//! Fill Dedup
+//!
+//! Fills arrive from the private WS and from the REST poll, often both for
+//! the same trade. Each source checks the tradeId here before a fill touches
+//! PnL or inventory, so whichever sees it first books it. Both run on the
+//! main loop, which owns this set - no lock needed. Ids are kept for the last
+//! `capacity` trades, oldest evicted first; that only has to outlast the
+//! poll's page (20 fills) plus whatever the feed delivers between polls.
+//!
+//! The feed carries no fee, so its fills are booked on the model fee and
+//! marked here; when the poll's row for one arrives it is skipped as a fill
+//! but its real fee replaces the estimate.
+
+use std::collections::{HashSet, VecDeque};
+
+#[derive(Debug, Default)]
+pub struct SeenTrades {
+    /// 0 = keep every id
+    capacity: usize,
+    ids: HashSet<String>,
+    order: VecDeque<String>,
+    /// Booked on an estimated fee, the real one not seen yet
+    estimated: HashSet<String>,
+}
+
+impl SeenTrades {
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity, ..Self::default() }
+    }
+
+    /// True the first time a tradeId is offered - the caller books the fill
+    pub fn insert(&mut self, trade_id: &str) -> bool {
+        if self.ids.contains(trade_id) {
+            return false;
+        }
+        self.ids.insert(trade_id.to_string());
+        self.order.push_back(trade_id.to_string());
+        while self.capacity > 0 && self.order.len() > self.capacity {
+            if let Some(old) = self.order.pop_front() {
+                self.ids.remove(&old);
+                self.estimated.remove(&old);
+            }
+        }
+        true
+    }
+
+    /// `insert` for a fill booked on an estimated fee
+    pub fn insert_estimated(&mut self, trade_id: &str) -> bool {
+        let first = self.insert(trade_id);
+        if first {
+            self.estimated.insert(trade_id.to_string());
+        }
+        first
+    }
+
+    /// True once for a trade booked on an estimated fee - its fee is due
+    pub fn take_estimated(&mut self, trade_id: &str) -> bool {
+        self.estimated.remove(trade_id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_sighting_only_and_bounded() {
+        let mut s = SeenTrades::new(3);
+        assert!(s.insert("t1"));
+        assert!(!s.insert("t1"));
+        for t in ["t2", "t3", "t4"] {
+            assert!(s.insert(t));
+        }
+        // t1 aged out, the rest are still caught
+        assert_eq!(s.len(), 3);
+        assert!(!s.insert("t4") && !s.insert("t2"));
+        assert!(s.insert("t1"));
+
+        // A feed fill's fee is owed once, and not after it ages out
+        assert!(s.insert_estimated("t5"));
+        assert!(!s.insert("t5"));
+        assert!(s.take_estimated("t5") && !s.take_estimated("t5"));
+        assert!(s.insert_estimated("t6") && !s.insert_estimated("t6"));
+        for t in ["t7", "t8", "t9"] {
+            s.insert(t);
+        }
+        assert!(!s.take_estimated("t6"));
+
+        let mut unbounded = SeenTrades::new(0);
+        for i in 0..1000 {
+            assert!(unbounded.insert(&i.to_string()));
+        }
+        assert_eq!(unbounded.len(), 1000);
+    }
+}
@@ -0,0 +1,67 @@
+//! Trade Log
+//!
+//! One JSON line per fill, with the mid at the time it was seen and the
+//! ladder level that placed the order, for offline analysis
+//! (`test_multi_layers analyze --input trades.jsonl`).
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use tracing::warn;
+
+use crate::exchange::Side;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TradeRecord {
+    /// Unix ms when the fill was seen
+    pub ts_ms: u64,
+    pub order_id: String,
+    pub side: Side,
+    pub price: f64,
+    pub size: f64,
+    pub maker: bool,
+    pub fee: Option<f64>,
+    pub fee_currency: String,
+    /// Reference mid when the fill was seen
+    pub mid: f64,
+    /// Ladder level key (bps * 10); None for flatten / scratch / adopted orders
+    pub level: Option<i32>,
+}
+
+/// Appends records to the log file; a disabled or failed log drops them
+pub struct TradeLog {
+    file: Option<File>,
+}
+
+impl TradeLog {
+    /// Empty path = disabled
+    pub fn open(path: &str) -> Result<Self> {
+        if path.is_empty() {
+            return Ok(Self { file: None });
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)
+            .with_context(|| format!("opening trade log {}", path))?;
+        Ok(Self { file: Some(file) })
+    }
+
+    pub fn append(&mut self, rec: &TradeRecord) {
+        let Some(file) = self.file.as_mut() else { return };
+        let line = match serde_json::to_string(rec) {
+            Ok(l) => l,
+            Err(e) => return warn!("[TRADE-LOG] Encode failed: {}", e),
+        };
+        if let Err(e) = writeln!(file, "{}", line) {
+            warn!("[TRADE-LOG] Write failed, disabling: {}", e);
+            self.file = None;
+        }
+    }
+}
+
+/// Parse a trade log, skipping blank lines; a malformed line is an error
+pub fn parse(text: &str) -> Result<Vec<TradeRecord>> {
+    text.lines().enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(i, l)| serde_json::from_str(l).with_context(|| format!("line {}", i + 1)))
+        .collect()
+}
@@ -0,0 +1,109 @@
+//! Traded Volume / Turnover
+//!
+//! Fill volume, maker vs taker, over a rolling window and since start - for
+//! watching rebate accrual and VIP-tier progression (tiers are set on
+//! trailing volume).
+
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::exchange::Liquidity;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct Volume {
+    pub maker_base: f64,
+    pub maker_quote: f64,
+    pub taker_base: f64,
+    pub taker_quote: f64,
+}
+
+impl Volume {
+    fn add(&mut self, liquidity: Liquidity, base: f64, quote: f64) {
+        match liquidity {
+            Liquidity::Maker => { self.maker_base += base; self.maker_quote += quote; }
+            Liquidity::Taker => { self.taker_base += base; self.taker_quote += quote; }
+        }
+    }
+
+    pub fn base(&self) -> f64 {
+        self.maker_base + self.taker_base
+    }
+
+    pub fn quote(&self) -> f64 {
+        self.maker_quote + self.taker_quote
+    }
+
+    /// Maker share of quote volume, 0..1 (1 when nothing traded)
+    pub fn maker_share(&self) -> f64 {
+        if self.quote() > 0.0 { self.maker_quote / self.quote() } else { 1.0 }
+    }
+}
+
+#[derive(Debug)]
+pub struct Turnover {
+    window: Duration,
+    /// (time, liquidity, base, quote) inside the window
+    fills: VecDeque<(Instant, Liquidity, f64, f64)>,
+    total: Volume,
+}
+
+impl Turnover {
+    pub fn new(window: Duration) -> Self {
+        Self { window, fills: VecDeque::new(), total: Volume::default() }
+    }
+
+    pub fn record(&mut self, liquidity: Liquidity, base: f64, price: f64, now: Instant) {
+        self.total.add(liquidity, base, base * price);
+        self.fills.push_back((now, liquidity, base, base * price));
+        self.prune(now);
+    }
+
+    fn prune(&mut self, now: Instant) {
+        while self.fills.front().is_some_and(|(t, ..)| now.duration_since(*t) > self.window) {
+            self.fills.pop_front();
+        }
+    }
+
+    /// Volume over the trailing window
+    pub fn windowed(&mut self, now: Instant) -> Volume {
+        self.prune(now);
+        let mut v = Volume::default();
+        for &(_, liquidity, base, quote) in &self.fills {
+            v.add(liquidity, base, quote);
+        }
+        v
+    }
+
+    /// Volume since start
+    pub fn total(&self) -> Volume {
+        self.total
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_windowed_volume_and_split() {
+        let t0 = Instant::now();
+        let s = Duration::from_secs;
+        let mut t = Turnover::new(s(3600));
+        t.record(Liquidity::Maker, 1.0, 100.0, t0);
+        t.record(Liquidity::Taker, 0.5, 100.0, t0 + s(600));
+        t.record(Liquidity::Maker, 2.0, 101.0, t0 + s(1800));
+
+        let w = t.windowed(t0 + s(1800));
+        assert!((w.base() - 3.5).abs() < 1e-9);
+        assert!((w.quote() - 352.0).abs() < 1e-9);
+        assert!((w.taker_quote - 50.0).abs() < 1e-9);
+        // First fill ages out of the hour, the total keeps it
+        let w = t.windowed(t0 + s(3601));
+        assert!((w.maker_quote - 202.0).abs() < 1e-9);
+        assert!((w.maker_share() - 202.0 / 252.0).abs() < 1e-9);
+        assert!((t.total().quote() - 352.0).abs() < 1e-9);
+        assert_eq!(t.windowed(t0 + s(9000)), Volume::default());
+        assert_eq!(Volume::default().maker_share(), 1.0);
+    }
+}